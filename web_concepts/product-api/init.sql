@@ -16,18 +16,206 @@ CREATE TABLE IF NOT EXISTS users (
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Category tree (see migrations/20251017000008_categories.sql)
+CREATE TABLE IF NOT EXISTS categories (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    parent_id UUID REFERENCES categories(id) ON DELETE RESTRICT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT categories_parent_not_self CHECK (parent_id <> id)
+);
+
+-- Exchange rates per unit of the base currency (see migrations/20251017000012_multi_currency.sql)
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    currency VARCHAR(3) PRIMARY KEY CONSTRAINT exchange_rates_currency_format CHECK (currency ~ '^[A-Z]{3}$'),
+    rate NUMERIC(18,8) NOT NULL CHECK (rate > 0),
+    updated_by UUID REFERENCES users(id),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 -- Products table
 CREATE TABLE IF NOT EXISTS products (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     name VARCHAR(255) NOT NULL,
     description TEXT,
     price DECIMAL(10,2) NOT NULL,
-    quantity INTEGER DEFAULT 0,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD' REFERENCES exchange_rates(currency) ON DELETE RESTRICT,
+    quantity INTEGER DEFAULT 0 CONSTRAINT products_quantity_non_negative CHECK (quantity >= 0),
+    -- Units held by active reservations (see migrations/20251017000006_stock_reservations.sql)
+    reserved_quantity INTEGER NOT NULL DEFAULT 0 CONSTRAINT products_reserved_quantity_non_negative CHECK (reserved_quantity >= 0),
+    -- Display name cached from categories.name
     category VARCHAR(100),
+    category_id UUID REFERENCES categories(id) ON DELETE RESTRICT,
+    -- Unique among live products (see migrations/20251017000009_product_identifiers.sql)
+    sku VARCHAR(64),
+    barcode VARCHAR(13) CONSTRAINT products_barcode_format CHECK (barcode ~ '^[0-9]{12,13}$'),
     created_by UUID REFERENCES users(id),
     updated_by UUID REFERENCES users(id),
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    -- Row version for optimistic concurrency (see migrations/20251017000002_products_version.sql)
+    version INTEGER NOT NULL DEFAULT 1,
+    -- Soft delete marker; NULL for live products (see migrations/20251017000003_products_soft_delete.sql)
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    -- Full-text search document (see migrations/20251017000001_products_full_text_search.sql)
+    search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(description, '')), 'B')
+    ) STORED
+);
+
+-- Product audit trail (see migrations/20251017000004_product_history.sql)
+CREATE TABLE IF NOT EXISTS product_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    changed_by UUID REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    changes JSONB NOT NULL DEFAULT '{}'::jsonb
+);
+
+-- Inventory ledger (see migrations/20251017000005_stock_movements.sql)
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    reason VARCHAR(16) NOT NULL,
+    quantity_delta INTEGER NOT NULL,
+    quantity_after INTEGER NOT NULL CHECK (quantity_after >= 0),
+    reference VARCHAR(100),
+    note TEXT,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Product images (see migrations/20251017000010_product_images.sql)
+CREATE TABLE IF NOT EXISTS product_images (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    blob_key VARCHAR(255) NOT NULL UNIQUE,
+    url TEXT NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    position INTEGER NOT NULL DEFAULT 0,
+    orphaned_at TIMESTAMP WITH TIME ZONE,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Scheduled prices (see migrations/20251017000011_price_schedules.sql)
+CREATE TABLE IF NOT EXISTS price_schedules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    price DECIMAL(10,2) NOT NULL CHECK (price >= 0),
+    effective_from TIMESTAMP WITH TIME ZONE NOT NULL,
+    effective_to TIMESTAMP WITH TIME ZONE,
+    label VARCHAR(100),
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT price_schedules_window_check CHECK (effective_to IS NULL OR effective_to > effective_from)
+);
+
+-- Product variants (see migrations/20251017000007_product_variants.sql)
+CREATE TABLE IF NOT EXISTS product_variants (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    sku VARCHAR(64) NOT NULL UNIQUE,
+    options JSONB NOT NULL,
+    price DECIMAL(10,2) CHECK (price >= 0),
+    quantity INTEGER NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, options)
+);
+
+-- Stock reservations (see migrations/20251017000006_stock_reservations.sql)
+CREATE TABLE IF NOT EXISTS stock_reservations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    reference VARCHAR(100),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Idempotency keys (see migrations/20251017000013_idempotency_keys.sql)
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_method VARCHAR(10) NOT NULL,
+    request_path TEXT NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL,
+    status_code SMALLINT,
+    response_headers JSONB,
+    response_body BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, idempotency_key)
+);
+
+-- Webhooks (see migrations/20251017000014_webhooks.sql)
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    url TEXT NOT NULL,
+    secret VARCHAR(255) NOT NULL,
+    event_types JSONB NOT NULL DEFAULT '[]',
+    low_stock_threshold INTEGER NOT NULL DEFAULT 10 CHECK (low_stock_threshold >= 0),
+    description VARCHAR(255),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS webhook_events (
+    id UUID PRIMARY KEY,
+    event_type VARCHAR(32) NOT NULL,
+    product_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    event_id UUID NOT NULL REFERENCES webhook_events(id) ON DELETE CASCADE,
+    subscription_id UUID NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMP WITH TIME ZONE,
+    response_status SMALLINT,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Refresh tokens (see migrations/20251017000015_refresh_tokens.sql)
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    replaced_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Access token revocations (see migrations/20251017000016_token_revocations.sql)
+CREATE TABLE IF NOT EXISTS revoked_access_tokens (
+    jti VARCHAR(64) PRIMARY KEY,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS user_token_revocations (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
 );
 
 -- Insert default users with different roles for testing
@@ -44,6 +232,18 @@ ON CONFLICT (username) DO UPDATE SET
     password_hash = EXCLUDED.password_hash;
 
 
+-- Sample categories
+INSERT INTO exchange_rates (currency, rate) VALUES
+('USD', 1),
+('EUR', 0.92),
+('GBP', 0.79),
+('JPY', 149.5);
+
+INSERT INTO categories (name, slug) VALUES
+('Electronics', 'electronics'),
+('Office Supplies', 'office-supplies')
+ON CONFLICT (slug) DO NOTHING;
+
 -- Insert some sample products with creator tracking
 INSERT INTO products (name, description, price, quantity, category, created_by) 
 SELECT 
@@ -74,8 +274,42 @@ SELECT
 FROM users u WHERE u.username = 'admin'
 ON CONFLICT DO NOTHING;
 
+-- Link the sample products to their categories
+UPDATE products p
+SET category_id = c.id
+FROM categories c
+WHERE p.category_id IS NULL AND p.category = c.name;
+
+-- Opening balance for the sample stock
+INSERT INTO stock_movements (product_id, reason, quantity_delta, quantity_after, note, created_by, created_at)
+SELECT p.id, 'adjustment', p.quantity, p.quantity, 'Opening balance', p.created_by, p.created_at
+FROM products p
+WHERE p.quantity > 0
+  AND NOT EXISTS (SELECT 1 FROM stock_movements m WHERE m.product_id = p.id);
+
 -- Create indexes for better performance
 CREATE INDEX IF NOT EXISTS idx_users_role ON users(role);
 CREATE INDEX IF NOT EXISTS idx_users_active ON users(is_active);
 CREATE INDEX IF NOT EXISTS idx_products_created_by ON products(created_by);
-CREATE INDEX IF NOT EXISTS idx_products_category ON products(category);
\ No newline at end of file
+CREATE INDEX IF NOT EXISTS idx_products_category ON products(category);
+CREATE INDEX IF NOT EXISTS idx_products_category_id ON products(category_id);
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id);
+CREATE INDEX IF NOT EXISTS idx_products_search_vector ON products USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_product_history_product ON product_history(product_id, changed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product ON stock_movements(product_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_stock_reservations_active_expiry ON stock_reservations(expires_at) WHERE status = 'active';
+CREATE INDEX IF NOT EXISTS idx_stock_reservations_product ON stock_reservations(product_id);
+CREATE INDEX IF NOT EXISTS idx_product_variants_product ON product_variants(product_id);
+CREATE INDEX IF NOT EXISTS idx_product_variants_options ON product_variants USING GIN (options jsonb_path_ops);
+CREATE UNIQUE INDEX IF NOT EXISTS products_sku_key ON products(sku) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS products_barcode_key ON products(lpad(barcode, 13, '0')) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_product_images_product ON product_images(product_id, position);
+CREATE INDEX IF NOT EXISTS idx_product_images_orphaned ON product_images(orphaned_at) WHERE orphaned_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_price_schedules_product ON price_schedules(product_id, effective_from DESC);
+CREATE INDEX IF NOT EXISTS idx_products_currency ON products(currency);
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_subscription ON webhook_deliveries(subscription_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_products_deleted_at ON products(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id ON refresh_tokens(family_id);
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Row version for optimistic concurrency control
+-- Every update bumps it; it is served as the ETag and checked against If-Match
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
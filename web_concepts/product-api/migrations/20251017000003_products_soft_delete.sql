@@ -0,0 +1,6 @@
+-- Soft delete: deleted products stay in the table (the trash) until purged
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+
+-- Trash listing only; live queries filter on deleted_at IS NULL
+CREATE INDEX IF NOT EXISTS idx_products_deleted_at ON products(deleted_at) WHERE deleted_at IS NOT NULL;
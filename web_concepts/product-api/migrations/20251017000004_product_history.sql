@@ -0,0 +1,14 @@
+-- Audit trail: one row per create/update/delete/restore/purge of a product,
+-- written in the same transaction as the change itself.
+-- product_id is intentionally not a foreign key so history survives a purge.
+CREATE TABLE IF NOT EXISTS product_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    changed_by UUID REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- {"field": {"old": ..., "new": ...}} for every audited field that changed
+    changes JSONB NOT NULL DEFAULT '{}'::jsonb
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_history_product ON product_history(product_id, changed_at DESC);
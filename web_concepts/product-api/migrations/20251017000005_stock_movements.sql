@@ -0,0 +1,26 @@
+-- Inventory ledger: every change to products.quantity is booked as a movement
+-- in the same transaction as the quantity update.
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    -- receipt | sale | adjustment | return | transfer
+    reason VARCHAR(16) NOT NULL,
+    quantity_delta INTEGER NOT NULL,
+    quantity_after INTEGER NOT NULL CHECK (quantity_after >= 0),
+    reference VARCHAR(100),
+    note TEXT,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product ON stock_movements(product_id, created_at DESC);
+
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_quantity_non_negative;
+ALTER TABLE products ADD CONSTRAINT products_quantity_non_negative CHECK (quantity >= 0);
+
+-- Opening balance for products that already hold stock
+INSERT INTO stock_movements (product_id, reason, quantity_delta, quantity_after, note, created_by, created_at)
+SELECT p.id, 'adjustment', p.quantity, p.quantity, 'Opening balance', p.created_by, p.created_at
+FROM products p
+WHERE p.quantity > 0
+  AND NOT EXISTS (SELECT 1 FROM stock_movements m WHERE m.product_id = p.id);
@@ -0,0 +1,24 @@
+-- Temporary stock holds for checkout flows. products.reserved_quantity is the sum
+-- of the product's active reservations and is kept in step in the same transaction;
+-- available-to-sell stock is quantity - reserved_quantity.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS reserved_quantity INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_reserved_quantity_non_negative;
+ALTER TABLE products ADD CONSTRAINT products_reserved_quantity_non_negative CHECK (reserved_quantity >= 0);
+
+CREATE TABLE IF NOT EXISTS stock_reservations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    -- active | confirmed | released | expired
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    reference VARCHAR(100),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Serves the expiry sweep, which only looks at active holds
+CREATE INDEX IF NOT EXISTS idx_stock_reservations_active_expiry
+    ON stock_reservations(expires_at) WHERE status = 'active';
+CREATE INDEX IF NOT EXISTS idx_stock_reservations_product ON stock_reservations(product_id);
@@ -0,0 +1,19 @@
+-- Sellable option combinations (size, colour, ...) of a product, each with its own
+-- SKU, stock and optional price override.
+CREATE TABLE IF NOT EXISTS product_variants (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    sku VARCHAR(64) NOT NULL UNIQUE,
+    -- {"size": "M", "colour": "red"}; names are stored lowercase
+    options JSONB NOT NULL,
+    -- NULL: sell at the product's price
+    price DECIMAL(10,2) CHECK (price >= 0),
+    quantity INTEGER NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, options)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_variants_product ON product_variants(product_id);
+-- Serves the variant_options search filter (options @> '{"size": "M"}')
+CREATE INDEX IF NOT EXISTS idx_product_variants_options ON product_variants USING GIN (options jsonb_path_ops);
@@ -0,0 +1,46 @@
+-- Category tree. products.category_id is the source of truth; products.category keeps
+-- the category's display name so existing readers and exports keep working.
+CREATE TABLE IF NOT EXISTS categories (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL,
+    -- Lowercase ASCII with single dashes, e.g. "office-supplies"
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    -- NULL for root categories; children must be moved or deleted first
+    parent_id UUID REFERENCES categories(id) ON DELETE RESTRICT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT categories_parent_not_self CHECK (parent_id <> id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id);
+
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS category_id UUID REFERENCES categories(id) ON DELETE RESTRICT;
+
+CREATE INDEX IF NOT EXISTS idx_products_category_id ON products(category_id);
+
+-- Backfill: one root category per distinct free-text value. Spellings that normalise
+-- to the same slug ("Electronics", " electronics ") are merged under the most common one.
+WITH spellings AS (
+    SELECT
+        trim(both '-' from regexp_replace(lower(trim(category)), '[^a-z0-9]+', '-', 'g')) AS slug,
+        trim(category) AS name,
+        count(*) AS uses
+    FROM products
+    WHERE category IS NOT NULL AND trim(category) <> ''
+    GROUP BY 1, 2
+)
+INSERT INTO categories (name, slug)
+SELECT DISTINCT ON (slug) name, slug
+FROM spellings
+WHERE slug <> ''
+ORDER BY slug, uses DESC, name
+ON CONFLICT (slug) DO NOTHING;
+
+UPDATE products p
+SET category_id = c.id,
+    category = c.name
+FROM categories c
+WHERE p.category_id IS NULL
+  AND p.category IS NOT NULL
+  AND c.slug = trim(both '-' from regexp_replace(lower(trim(p.category)), '[^a-z0-9]+', '-', 'g'));
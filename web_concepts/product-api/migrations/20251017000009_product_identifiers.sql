@@ -0,0 +1,17 @@
+-- Scanner-friendly product identifiers. Check digits are validated by the API;
+-- the database only enforces the shape and uniqueness.
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS sku VARCHAR(64),
+    -- EAN-13 (13 digits) or UPC-A (12 digits), stored as entered
+    ADD COLUMN IF NOT EXISTS barcode VARCHAR(13)
+        CONSTRAINT products_barcode_format CHECK (barcode ~ '^[0-9]{12,13}$');
+
+-- Unique among live products only, so a trashed product doesn't block re-listing
+-- the same item; restoring it checks for collisions first.
+CREATE UNIQUE INDEX IF NOT EXISTS products_sku_key
+    ON products(sku) WHERE deleted_at IS NULL;
+
+-- A UPC-A code is the EAN-13 code with a leading zero, so both spellings share one key.
+-- Lookups must use the same expression to hit this index.
+CREATE UNIQUE INDEX IF NOT EXISTS products_barcode_key
+    ON products(lpad(barcode, 13, '0')) WHERE deleted_at IS NULL;
@@ -0,0 +1,18 @@
+-- Product images. The files live in the blob store (MEDIA_ROOT for the local
+-- backend); this table holds their keys, URLs and display order.
+CREATE TABLE IF NOT EXISTS product_images (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    blob_key VARCHAR(255) NOT NULL UNIQUE,
+    url TEXT NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    position INTEGER NOT NULL DEFAULT 0,
+    -- Set while the product is in the trash; purging deletes the blobs
+    orphaned_at TIMESTAMP WITH TIME ZONE,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_images_product ON product_images(product_id, position);
+CREATE INDEX IF NOT EXISTS idx_product_images_orphaned ON product_images(orphaned_at) WHERE orphaned_at IS NOT NULL;
@@ -0,0 +1,20 @@
+-- Multi-currency prices. Each product is priced in one currency; exchange_rates
+-- holds how many units of a currency one unit of the base currency
+-- (BASE_CURRENCY, USD by default) buys. The base currency has a row fixed at 1,
+-- so every product currency references a row and conversions never miss a rate.
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    currency VARCHAR(3) PRIMARY KEY CONSTRAINT exchange_rates_currency_format CHECK (currency ~ '^[A-Z]{3}$'),
+    rate NUMERIC(18,8) NOT NULL CHECK (rate > 0),
+    updated_by UUID REFERENCES users(id),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO exchange_rates (currency, rate) VALUES ('USD', 1)
+ON CONFLICT (currency) DO NOTHING;
+
+-- Existing prices were entered without a currency and are taken to be USD
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD'
+    REFERENCES exchange_rates(currency) ON DELETE RESTRICT;
+
+CREATE INDEX IF NOT EXISTS idx_products_currency ON products(currency);
@@ -0,0 +1,19 @@
+-- Idempotency keys for write requests. One row per (user, Idempotency-Key)
+-- holds the fingerprint of the first request and, once it finished, the
+-- response to replay on retries. status_code is NULL while the first request
+-- is still running. Rows are purged after expires_at.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_method VARCHAR(10) NOT NULL,
+    request_path TEXT NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL,
+    status_code SMALLINT,
+    response_headers JSONB,
+    response_body BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
@@ -0,0 +1,19 @@
+-- Access tokens revoked before they expire. These tables are the source of
+-- truth; every replica keeps a copy in memory (TokenDenylist) and reloads it
+-- every few seconds, so a logout on one replica reaches all of them. Rows are
+-- deleted once the tokens they cover have expired.
+CREATE TABLE IF NOT EXISTS revoked_access_tokens (
+    jti VARCHAR(64) PRIMARY KEY,
+    -- The token's own expiry; the row is useless after it
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Every access token of a deactivated user issued at or before revoked_at.
+-- Reactivating the user deletes the row.
+CREATE TABLE IF NOT EXISTS user_token_revocations (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- When the last access token issued before revoked_at expires
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
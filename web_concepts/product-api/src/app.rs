@@ -0,0 +1,412 @@
+use crate::{
+    handlers::{
+        auth, category, exchange_rate, metrics, price_schedule, product, product_image,
+        product_variant, webhook,
+    },
+    middleware::{
+        auth::auth_middleware,
+        client_ip::{client_ip_middleware, ClientIpResolver},
+        idempotency::{idempotency_middleware, RouteBodyLimits},
+        logging::request_logging_middleware,
+        rate_limit::{ip_rate_limit_middleware, user_rate_limit_middleware},
+        rbac::rbac_middleware,
+    },
+    openapi::ApiDoc,
+    rate_limit::RouteGroup,
+    AppError, AppState,
+};
+use axum::{
+    extract::{DefaultBodyLimit, Request},
+    handler::Handler,
+    http::{Method, StatusCode},
+    response::IntoResponse,
+    routing::{on, MethodFilter, MethodRouter, Route},
+    Router,
+};
+use std::convert::Infallible;
+use tower::{Layer, Service, ServiceBuilder};
+use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Imports carry whole files, so they get a larger body limit than the 2MB default
+const MAX_IMPORT_BODY_BYTES: usize = 10 * 1024 * 1024;
+/// Image uploads get a body limit with room for this many full-size files
+const MAX_IMAGE_FILES_PER_UPLOAD: usize = 10;
+
+/// A router that remembers the `(method, pattern)` of every route added to it,
+/// so checks like the RBAC policy check at startup see exactly what is served
+pub struct RouteTable {
+    router: Router<AppState>,
+    routes: Vec<(Method, &'static str)>,
+    body_limits: Vec<(Method, &'static str, usize)>,
+}
+
+impl Default for RouteTable {
+    fn default() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+            body_limits: Vec::new(),
+        }
+    }
+}
+
+impl RouteTable {
+    pub fn get<H: Handler<T, AppState>, T: 'static>(self, path: &'static str, handler: H) -> Self {
+        self.on(Method::GET, path, handler)
+    }
+
+    pub fn post<H: Handler<T, AppState>, T: 'static>(self, path: &'static str, handler: H) -> Self {
+        self.on(Method::POST, path, handler)
+    }
+
+    pub fn put<H: Handler<T, AppState>, T: 'static>(self, path: &'static str, handler: H) -> Self {
+        self.on(Method::PUT, path, handler)
+    }
+
+    pub fn patch<H: Handler<T, AppState>, T: 'static>(
+        self,
+        path: &'static str,
+        handler: H,
+    ) -> Self {
+        self.on(Method::PATCH, path, handler)
+    }
+
+    pub fn delete<H: Handler<T, AppState>, T: 'static>(
+        self,
+        path: &'static str,
+        handler: H,
+    ) -> Self {
+        self.on(Method::DELETE, path, handler)
+    }
+
+    /// A `POST` route whose body may be up to `limit` bytes
+    pub fn post_with_body_limit<H: Handler<T, AppState>, T: 'static>(
+        mut self,
+        path: &'static str,
+        handler: H,
+        limit: usize,
+    ) -> Self {
+        let method_router = on(MethodFilter::POST, handler).layer(DefaultBodyLimit::max(limit));
+        self.body_limits.push((Method::POST, path, limit));
+        self.add(Method::POST, path, method_router)
+    }
+
+    fn on<H: Handler<T, AppState>, T: 'static>(
+        self,
+        method: Method,
+        path: &'static str,
+        handler: H,
+    ) -> Self {
+        let filter = MethodFilter::try_from(method.clone()).expect("a routable method");
+        self.add(method, path, on(filter, handler))
+    }
+
+    fn add(
+        mut self,
+        method: Method,
+        path: &'static str,
+        method_router: MethodRouter<AppState>,
+    ) -> Self {
+        // Axum merges method routers registered on the same path
+        self.router = self.router.route(path, method_router);
+        self.routes.push((method, path));
+        self
+    }
+
+    pub fn merge(mut self, other: RouteTable) -> Self {
+        self.router = self.router.merge(other.router);
+        self.routes.extend(other.routes);
+        self.body_limits.extend(other.body_limits);
+        self
+    }
+
+    /// Wrap every route added so far in `layer`, like `Router::layer`
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.layer(layer);
+        self
+    }
+
+    /// `(method, pattern)` of every route, patterns in axum's `:param` form
+    pub fn routes(&self) -> &[(Method, &'static str)] {
+        &self.routes
+    }
+
+    /// Limits of the routes added with `post_with_body_limit`
+    pub fn body_limits(&self) -> RouteBodyLimits {
+        RouteBodyLimits::new(self.body_limits.iter().cloned())
+    }
+
+    pub fn into_router(self) -> Router<AppState> {
+        self.router
+    }
+}
+
+/// Routes anyone can call, rate limited per client IP
+pub fn public_routes(state: &AppState) -> RouteTable {
+    RouteTable::default()
+        .get("/health", health_check)
+        .post("/auth/login", auth::login)
+        .post("/auth/register", auth::register)
+        .post("/auth/refresh", auth::refresh)
+        .layer(axum::middleware::from_fn_with_state(
+            state.rate_limiter.clone(),
+            ip_rate_limit_middleware,
+        ))
+}
+
+/// Routes that need a bearer token, each group rate limited on its own.
+/// Authentication, RBAC and idempotency are layered on in `create_app`.
+pub fn protected_routes(state: &AppState) -> RouteTable {
+    let rate_limited = |group: RouteGroup| {
+        axum::middleware::from_fn_with_state(
+            (state.rate_limiter.clone(), group),
+            user_rate_limit_middleware,
+        )
+    };
+
+    // Read-only routes (all authenticated users can access)
+    let read_only_routes = RouteTable::default()
+        .get("/products", product::get_all_products)
+        .get("/products/:id", product::get_product)
+        .get("/products/:id/history", product::get_product_history)
+        .get(
+            "/products/:id/stock/movements",
+            product::get_stock_movements,
+        )
+        .get("/reservations/:id", product::get_reservation)
+        .get("/categories", category::list_categories)
+        .get("/categories/:id", category::get_category)
+        .get("/exchange-rates", exchange_rate::list_exchange_rates)
+        .get("/products/:id/variants", product_variant::list_variants)
+        .get(
+            "/products/:id/variants/:variant_id",
+            product_variant::get_variant,
+        )
+        .get("/products/:id/images", product_image::list_images)
+        .get(
+            "/products/:id/price-schedules",
+            price_schedule::list_price_schedules,
+        )
+        .get("/products/lookup", product::lookup_product)
+        .get("/products/search", product::search_products)
+        .get("/products/category", product::get_products_by_category)
+        .get(
+            "/products/price-range",
+            product::get_products_by_price_range,
+        )
+        .get("/products/low-stock", product::get_low_stock_products)
+        .get("/products/similar", product::get_similar_products)
+        .get("/products/stats", product::get_product_stats)
+        .get("/products/export", product::export_products)
+        .get(
+            "/products/trending-categories",
+            product::get_trending_categories,
+        )
+        .layer(rate_limited(RouteGroup::Read));
+
+    // Create routes (Admin and Manager can access)
+    let create_routes = RouteTable::default()
+        .post("/products", product::create_product)
+        .post("/categories", category::create_category)
+        .post("/products/:id/variants", product_variant::create_variant)
+        .post(
+            "/products/:id/price-schedules",
+            price_schedule::create_price_schedule,
+        )
+        .post_with_body_limit(
+            "/products/:id/images",
+            product_image::upload_images,
+            state.config.max_image_bytes * MAX_IMAGE_FILES_PER_UPLOAD,
+        )
+        .post_with_body_limit(
+            "/products/import",
+            product::import_products,
+            MAX_IMPORT_BODY_BYTES,
+        )
+        .layer(rate_limited(RouteGroup::Create));
+
+    // Update routes (Admin and Manager can access)
+    let update_routes = RouteTable::default()
+        .put("/products/:id", product::update_product)
+        .patch("/products/batch", product::batch_update_products)
+        .post("/products/:id/stock/adjust", product::adjust_stock)
+        .put("/categories/:id", category::update_category)
+        .put("/products/:id/images/order", product_image::reorder_images)
+        .put(
+            "/products/:id/price-schedules/:schedule_id",
+            price_schedule::update_price_schedule,
+        )
+        .put(
+            "/products/:id/variants/:variant_id",
+            product_variant::update_variant,
+        )
+        .post("/products/:id/reservations", product::create_reservation)
+        .post("/reservations/:id/confirm", product::confirm_reservation)
+        .post("/reservations/:id/release", product::release_reservation)
+        .layer(rate_limited(RouteGroup::Update));
+
+    // Delete routes (Admin only)
+    let delete_routes = RouteTable::default()
+        .delete("/products/:id", product::delete_product)
+        .post("/products/batch-delete", product::batch_delete_products)
+        .delete("/categories/:id", category::delete_category)
+        .delete(
+            "/products/:id/images/:image_id",
+            product_image::delete_image,
+        )
+        .delete(
+            "/products/:id/price-schedules/:schedule_id",
+            price_schedule::delete_price_schedule,
+        )
+        .delete(
+            "/products/:id/variants/:variant_id",
+            product_variant::delete_variant,
+        )
+        .layer(rate_limited(RouteGroup::Delete));
+
+    // Trash routes for soft-deleted products (Admin only)
+    let trash_routes = RouteTable::default()
+        .get("/products/trash", product::get_trash)
+        .post("/products/:id/restore", product::restore_product)
+        .delete("/products/:id/purge", product::purge_product)
+        .layer(rate_limited(RouteGroup::Admin));
+
+    // Exchange rate maintenance (Admin only)
+    let exchange_rate_routes = RouteTable::default()
+        .put(
+            "/exchange-rates/:currency",
+            exchange_rate::upsert_exchange_rate,
+        )
+        .delete(
+            "/exchange-rates/:currency",
+            exchange_rate::delete_exchange_rate,
+        )
+        .layer(rate_limited(RouteGroup::Admin));
+
+    // Operational metrics (Admin only)
+    let metrics_routes = RouteTable::default()
+        .get("/metrics/cache", metrics::cache_metrics)
+        .layer(rate_limited(RouteGroup::Admin));
+
+    // Webhook subscriptions and their delivery log (Admin only)
+    let webhook_routes = RouteTable::default()
+        .get("/webhooks", webhook::list_webhooks)
+        .post("/webhooks", webhook::create_webhook)
+        .get("/webhooks/:id", webhook::get_webhook)
+        .put("/webhooks/:id", webhook::update_webhook)
+        .delete("/webhooks/:id", webhook::delete_webhook)
+        .get("/webhooks/:id/deliveries", webhook::list_deliveries)
+        .layer(rate_limited(RouteGroup::Admin));
+
+    // Session routes (all authenticated users can access)
+    let session_routes = RouteTable::default()
+        .post("/auth/logout", auth::logout)
+        .layer(rate_limited(RouteGroup::Read));
+
+    // User administration routes (Admin only)
+    let user_routes = RouteTable::default()
+        .patch("/users/:id/status", auth::update_user_status)
+        .layer(rate_limited(RouteGroup::Admin));
+
+    RouteTable::default()
+        .merge(session_routes)
+        .merge(read_only_routes)
+        .merge(create_routes)
+        .merge(update_routes)
+        .merge(delete_routes)
+        .merge(trash_routes)
+        .merge(exchange_rate_routes)
+        .merge(webhook_routes)
+        .merge(metrics_routes)
+        .merge(user_routes)
+}
+
+/// Every route of the API with its middleware stack. Fails if a protected
+/// route has no policy in `state.rbac`.
+pub fn create_app(state: AppState) -> Result<Router, AppError> {
+    // Public routes (no authentication required)
+    let public_routes = public_routes(&state).into_router();
+
+    // Every protected route needs a policy before the server takes requests
+    let protected_routes = protected_routes(&state);
+    state
+        .rbac
+        .check_routes(protected_routes.routes().iter().cloned())?;
+
+    // Combine all protected routes
+    let body_limits = protected_routes.body_limits();
+    let protected_routes = protected_routes
+        .into_router()
+        // Replay retried writes that carry an Idempotency-Key
+        .layer(axum::middleware::from_fn_with_state(
+            (state.clone(), body_limits),
+            idempotency_middleware,
+        ))
+        // Check the caller's role against the route's policy in RbacConfig
+        .layer(axum::middleware::from_fn_with_state(
+            state.rbac.clone(),
+            rbac_middleware,
+        ))
+        // Authenticate all protected routes; each group above is rate limited on its own
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    // Uploaded images are public, like any other static asset. An absolute
+    // MEDIA_BASE_URL means something else (a CDN, nginx) serves MEDIA_ROOT.
+    let mut media_routes = Router::new();
+    if state.config.media_base_url.starts_with('/') {
+        media_routes = media_routes.nest_service(
+            &state.config.media_base_url,
+            ServeDir::new(&state.config.media_root),
+        );
+    }
+
+    // The OpenAPI document and an interactive explorer for it
+    let docs_routes =
+        SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi());
+
+    Ok(Router::new()
+        .merge(public_routes)
+        .merge(protected_routes)
+        .merge(media_routes)
+        .merge(docs_routes)
+        .layer(
+            ServiceBuilder::new()
+                // Outermost: resolve the client address for logging and rate limiting
+                .layer(axum::middleware::from_fn_with_state(
+                    ClientIpResolver::new(state.config.trusted_proxies.clone()),
+                    client_ip_middleware,
+                ))
+                // Log every request and response, with the address resolved above
+                .layer(axum::middleware::from_fn(request_logging_middleware))
+                // Add HTTP tracing for internal spans
+                .layer(TraceLayer::new_for_http())
+                // Add CORS support
+                .layer(CorsLayer::permissive()),
+        )
+        .with_state(state))
+}
+
+/// Liveness probe
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service is up", body = String, content_type = "text/plain"),
+    )
+)]
+pub async fn health_check() -> impl IntoResponse {
+    (StatusCode::OK, "Service is healthy")
+}
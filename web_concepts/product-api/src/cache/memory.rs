@@ -0,0 +1,205 @@
+use crate::cache::product_cache::{CacheKey, CacheMetrics, CachedValue, ProductCache};
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Products share this many invalidation counters, picked by id. Two products
+/// on one counter only cost a skipped `put` now and then.
+const PRODUCT_GENERATION_SLOTS: usize = 64;
+
+struct Entry {
+    value: CachedValue,
+    expires_at: Instant,
+    /// Position in `Entries::recency`
+    last_used: u64,
+}
+
+struct Entries {
+    map: HashMap<CacheKey, Entry>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, CacheKey>,
+    clock: u64,
+    /// Invalidations of product entries, per slot
+    product_generations: Vec<u64>,
+    /// Invalidations of the aggregates
+    aggregate_generation: u64,
+}
+
+impl Default for Entries {
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            product_generations: vec![0; PRODUCT_GENERATION_SLOTS],
+            aggregate_generation: 0,
+        }
+    }
+}
+
+impl Entries {
+    fn generation(&mut self, key: &CacheKey) -> &mut u64 {
+        match key {
+            CacheKey::Product(id) => {
+                let slot = (id.as_u128() % PRODUCT_GENERATION_SLOTS as u128) as usize;
+                &mut self.product_generations[slot]
+            }
+            _ => &mut self.aggregate_generation,
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        self.clock += 1;
+        if let Some(entry) = self.map.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = self.clock;
+            self.recency.insert(self.clock, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) -> Option<Entry> {
+        let entry = self.map.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    fn retain(&mut self, keep: impl Fn(&CacheKey) -> bool) {
+        let dropped: Vec<CacheKey> = self.map.keys().filter(|key| !keep(key)).cloned().collect();
+        for key in dropped {
+            self.remove(&key);
+        }
+    }
+}
+
+/// Per-process cache with a TTL per entry and least-recently-used eviction
+/// once `capacity` entries are held
+pub struct InMemoryProductCache {
+    entries: Mutex<Entries>,
+    ttl: Duration,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl InMemoryProductCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries::default()),
+            ttl,
+            capacity: capacity.max(1),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        // A panic mid-update leaves at worst a stale entry, which expires anyway
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ProductCache for InMemoryProductCache {
+    async fn get(&self, key: &CacheKey) -> Option<CachedValue> {
+        let mut entries = self.entries();
+
+        let value = match entries.map.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        };
+
+        match value {
+            Some(value) => {
+                entries.touch(key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(value)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    async fn generation(&self, key: &CacheKey) -> u64 {
+        *self.entries().generation(key)
+    }
+
+    async fn put(&self, key: CacheKey, value: CachedValue, generation: u64) {
+        let mut entries = self.entries();
+        if *entries.generation(&key) != generation {
+            return;
+        }
+        entries.remove(&key);
+
+        while entries.map.len() >= self.capacity {
+            let Some((_, oldest)) = entries.recency.pop_first() else {
+                break;
+            };
+            entries.map.remove(&oldest);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        entries.map.insert(
+            key.clone(),
+            Entry {
+                value,
+                expires_at: Instant::now() + self.ttl,
+                last_used: 0,
+            },
+        );
+        entries.touch(&key);
+    }
+
+    async fn invalidate_product(&self, id: Uuid) {
+        let mut entries = self.entries();
+        let key = CacheKey::Product(id);
+        *entries.generation(&key) += 1;
+        entries.aggregate_generation += 1;
+        entries.retain(|cached| !cached.is_aggregate() && *cached != key);
+    }
+
+    async fn invalidate_aggregates(&self) {
+        let mut entries = self.entries();
+        entries.aggregate_generation += 1;
+        entries.retain(|key| !key.is_aggregate());
+    }
+
+    async fn invalidate_all(&self) {
+        let mut entries = self.entries();
+        entries.map.clear();
+        entries.recency.clear();
+        entries
+            .product_generations
+            .iter_mut()
+            .for_each(|generation| *generation += 1);
+        entries.aggregate_generation += 1;
+    }
+
+    fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            enabled: true,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            hit_ratio: 0.0,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: self.entries().map.len(),
+            capacity: self.capacity,
+            ttl_seconds: self.ttl.as_secs(),
+        }
+        .with_hit_ratio()
+    }
+}
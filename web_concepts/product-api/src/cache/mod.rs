@@ -0,0 +1,5 @@
+pub mod memory;
+pub mod product_cache;
+
+pub use memory::*;
+pub use product_cache::*;
@@ -0,0 +1,117 @@
+use crate::{
+    entities::product,
+    models::{CategoryStats, ProductStatsResponse},
+};
+use async_trait::async_trait;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a cached value was read for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    /// A live product by id
+    Product(Uuid),
+    /// `/products/stats`, per requested currency
+    Stats { currency: String },
+    /// `/products/trending-categories`, per limit and requested currency
+    TrendingCategories { limit: u64, currency: String },
+}
+
+impl CacheKey {
+    /// Aggregates span every product, so any product write invalidates them
+    pub fn is_aggregate(&self) -> bool {
+        !matches!(self, Self::Product(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CachedValue {
+    Product(product::Model),
+    Stats(ProductStatsResponse),
+    TrendingCategories(Vec<CategoryStats>),
+}
+
+/// Counters since startup, for `GET /metrics/cache`
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct CacheMetrics {
+    pub enabled: bool,
+    pub hits: u64,
+    pub misses: u64,
+    /// Share of lookups answered from the cache, 0 to 1
+    pub hit_ratio: f64,
+    /// Entries dropped to stay within capacity (expired and invalidated ones not counted)
+    pub evictions: u64,
+    pub entries: usize,
+    pub capacity: usize,
+    pub ttl_seconds: u64,
+}
+
+impl CacheMetrics {
+    pub fn with_hit_ratio(mut self) -> Self {
+        let lookups = self.hits + self.misses;
+        self.hit_ratio = if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        };
+        self
+    }
+}
+
+/// Read-through cache for product reads and the product aggregates.
+///
+/// Lookups never fail: an unreachable backend is just a miss. Writes through
+/// [`CachedProductRepository`](crate::repository::cached_product::CachedProductRepository)
+/// invalidate what they touch; everything else (scheduled prices taking
+/// effect, writes from outside this process) shows up once entries expire.
+#[async_trait]
+pub trait ProductCache: Send + Sync {
+    async fn get(&self, key: &CacheKey) -> Option<CachedValue>;
+
+    /// How often `key` has been invalidated. Taken before reading the value
+    /// from the database and handed to `put`.
+    async fn generation(&self, key: &CacheKey) -> u64;
+
+    /// Store a value read after `generation(&key)` returned `generation`. It is
+    /// dropped if `key` was invalidated since, as the read may predate the write.
+    async fn put(&self, key: CacheKey, value: CachedValue, generation: u64);
+
+    /// Drop one product and every aggregate
+    async fn invalidate_product(&self, id: Uuid);
+
+    /// Drop every aggregate, for writes that change totals but no product row
+    /// (variants, price schedules, categories, exchange rates)
+    async fn invalidate_aggregates(&self);
+
+    async fn invalidate_all(&self);
+
+    fn metrics(&self) -> CacheMetrics;
+}
+
+/// Used when `PRODUCT_CACHE_ENABLED=false`: every lookup goes to the database
+#[derive(Debug, Clone, Default)]
+pub struct DisabledProductCache;
+
+#[async_trait]
+impl ProductCache for DisabledProductCache {
+    async fn get(&self, _key: &CacheKey) -> Option<CachedValue> {
+        None
+    }
+
+    async fn generation(&self, _key: &CacheKey) -> u64 {
+        0
+    }
+
+    async fn put(&self, _key: CacheKey, _value: CachedValue, _generation: u64) {}
+
+    async fn invalidate_product(&self, _id: Uuid) {}
+
+    async fn invalidate_aggregates(&self) {}
+
+    async fn invalidate_all(&self) {}
+
+    fn metrics(&self) -> CacheMetrics {
+        CacheMetrics::default()
+    }
+}
@@ -1,4 +1,7 @@
-use crate::error::AppError;
+use crate::{
+    error::AppError, middleware::client_ip::IpNet, rate_limit::RateLimitPolicy,
+    utils::is_valid_currency_code,
+};
 use std::env;
 
 #[derive(Debug, Clone)]
@@ -6,8 +9,28 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
     pub jwt_expiration: i64,
+    pub refresh_token_ttl_seconds: i64,
     pub rate_limit_per_ip: u32,
     pub rate_limit_per_user: u32,
+    pub rate_limit_policies: Vec<RateLimitPolicy>,
+    pub rate_limit_backend: String,
+    pub redis_url: String,
+    pub rate_limit_eviction_interval_seconds: u64,
+    pub trusted_proxies: Vec<IpNet>,
+    pub reservation_ttl_seconds: i64,
+    pub reservation_sweep_interval_seconds: u64,
+    pub media_root: String,
+    pub media_base_url: String,
+    pub max_image_bytes: usize,
+    pub base_currency: String,
+    pub idempotency_ttl_seconds: i64,
+    pub webhook_max_attempts: u32,
+    pub webhook_retry_base_seconds: u64,
+    pub webhook_timeout_seconds: u64,
+    pub webhook_poll_interval_seconds: u64,
+    pub product_cache_enabled: bool,
+    pub product_cache_ttl_seconds: u64,
+    pub product_cache_capacity: usize,
 }
 
 impl Config {
@@ -26,7 +49,7 @@ impl Config {
         println!("JWT secret loaded (length: {})", jwt_secret.len());
 
         let jwt_expiration = env::var("JWT_EXPIRATION")
-            .unwrap_or_else(|_| "86400".to_string()) // 24 hours
+            .unwrap_or_else(|_| "900".to_string()) // 15 minutes, refresh tokens outlive it
             .parse()
             .map_err(|e| {
                 eprintln!("Failed to parse JWT_EXPIRATION: {e}");
@@ -37,6 +60,18 @@ impl Config {
             })?;
         println!("JWT expiration: {jwt_expiration} seconds");
 
+        let refresh_token_ttl_seconds = env::var("REFRESH_TOKEN_TTL_SECONDS")
+            .unwrap_or_else(|_| "2592000".to_string()) // 30 days
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse REFRESH_TOKEN_TTL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid REFRESH_TOKEN_TTL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Refresh tokens expire after {refresh_token_ttl_seconds} seconds");
+
         let rate_limit_per_ip = env::var("RATE_LIMIT_PER_IP")
             .unwrap_or_else(|_| "100".to_string())
             .parse()
@@ -61,14 +96,232 @@ impl Config {
             })?;
         println!("Rate limit per user: {rate_limit_per_user} requests/minute");
 
+        // Per route group and role overrides of RATE_LIMIT_PER_USER
+        let rate_limit_policies =
+            RateLimitPolicy::parse_list(&env::var("RATE_LIMIT_POLICIES").unwrap_or_default())
+                .map_err(|e| {
+                    eprintln!("Failed to parse RATE_LIMIT_POLICIES: {e}");
+                    AppError::BadRequest {
+                        message: "Invalid RATE_LIMIT_POLICIES".to_string(),
+                        error_id: uuid::Uuid::new_v4(),
+                    }
+                })?;
+        println!("Rate limit policies: {}", rate_limit_policies.len());
+
+        // "memory" limits each replica on its own, "redis" shares the limits
+        let rate_limit_backend =
+            env::var("RATE_LIMIT_BACKEND").unwrap_or_else(|_| "memory".to_string());
+        if !matches!(rate_limit_backend.as_str(), "memory" | "redis") {
+            eprintln!("Invalid RATE_LIMIT_BACKEND: {rate_limit_backend}");
+            return Err(AppError::BadRequest {
+                message: "Invalid RATE_LIMIT_BACKEND".to_string(),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+        println!("Rate limit backend: {rate_limit_backend}");
+
+        let redis_url =
+            env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+
+        let rate_limit_eviction_interval_seconds = env::var("RATE_LIMIT_EVICTION_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse RATE_LIMIT_EVICTION_INTERVAL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid RATE_LIMIT_EVICTION_INTERVAL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!(
+            "Idle rate limit buckets evicted every {rate_limit_eviction_interval_seconds} seconds"
+        );
+
+        // Only these peers may tell us the client address in Forwarded/X-Forwarded-For
+        let trusted_proxies = IpNet::parse_list(&env::var("TRUSTED_PROXIES").unwrap_or_default())
+            .map_err(|e| {
+            eprintln!("Failed to parse TRUSTED_PROXIES: {e}");
+            AppError::BadRequest {
+                message: "Invalid TRUSTED_PROXIES".to_string(),
+                error_id: uuid::Uuid::new_v4(),
+            }
+        })?;
+        println!("Trusted proxy networks: {}", trusted_proxies.len());
+
+        let reservation_ttl_seconds = env::var("RESERVATION_TTL_SECONDS")
+            .unwrap_or_else(|_| "900".to_string()) // 15 minutes
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse RESERVATION_TTL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid RESERVATION_TTL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Default reservation TTL: {reservation_ttl_seconds} seconds");
+
+        let reservation_sweep_interval_seconds = env::var("RESERVATION_SWEEP_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse RESERVATION_SWEEP_INTERVAL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid RESERVATION_SWEEP_INTERVAL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Reservation expiry sweep every {reservation_sweep_interval_seconds} seconds");
+
+        let media_root = env::var("MEDIA_ROOT").unwrap_or_else(|_| "./media".to_string());
+        let media_base_url = env::var("MEDIA_BASE_URL").unwrap_or_else(|_| "/media".to_string());
+        println!("Media stored in {media_root}, served from {media_base_url}");
+
+        let max_image_bytes = env::var("MAX_IMAGE_BYTES")
+            .unwrap_or_else(|_| "5242880".to_string()) // 5 MiB
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse MAX_IMAGE_BYTES: {e}");
+                AppError::BadRequest {
+                    message: "Invalid MAX_IMAGE_BYTES".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Maximum image size: {max_image_bytes} bytes");
+
+        let base_currency = env::var("BASE_CURRENCY").unwrap_or_else(|_| "USD".to_string());
+        if !is_valid_currency_code(&base_currency) {
+            eprintln!("Invalid BASE_CURRENCY: {base_currency}");
+            return Err(AppError::BadRequest {
+                message: "Invalid BASE_CURRENCY".to_string(),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+        println!("Base currency: {base_currency}");
+
+        let idempotency_ttl_seconds = env::var("IDEMPOTENCY_TTL_SECONDS")
+            .unwrap_or_else(|_| "86400".to_string()) // 24 hours
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse IDEMPOTENCY_TTL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid IDEMPOTENCY_TTL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Idempotency keys kept for {idempotency_ttl_seconds} seconds");
+
+        let webhook_max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse WEBHOOK_MAX_ATTEMPTS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid WEBHOOK_MAX_ATTEMPTS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Webhook deliveries give up after {webhook_max_attempts} attempts");
+
+        let webhook_retry_base_seconds = env::var("WEBHOOK_RETRY_BASE_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse WEBHOOK_RETRY_BASE_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid WEBHOOK_RETRY_BASE_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("First webhook retry after {webhook_retry_base_seconds} seconds");
+
+        let webhook_timeout_seconds = env::var("WEBHOOK_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse WEBHOOK_TIMEOUT_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid WEBHOOK_TIMEOUT_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Webhook request timeout: {webhook_timeout_seconds} seconds");
+
+        let webhook_poll_interval_seconds = env::var("WEBHOOK_POLL_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse WEBHOOK_POLL_INTERVAL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid WEBHOOK_POLL_INTERVAL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Webhook outbox polled every {webhook_poll_interval_seconds} seconds");
+
+        let product_cache_enabled = env::var("PRODUCT_CACHE_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse PRODUCT_CACHE_ENABLED: {e}");
+                AppError::BadRequest {
+                    message: "Invalid PRODUCT_CACHE_ENABLED".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Product cache enabled: {product_cache_enabled}");
+
+        let product_cache_ttl_seconds = env::var("PRODUCT_CACHE_TTL_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse PRODUCT_CACHE_TTL_SECONDS: {e}");
+                AppError::BadRequest {
+                    message: "Invalid PRODUCT_CACHE_TTL_SECONDS".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Product cache TTL: {product_cache_ttl_seconds} seconds");
+
+        let product_cache_capacity = env::var("PRODUCT_CACHE_CAPACITY")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .map_err(|e| {
+                eprintln!("Failed to parse PRODUCT_CACHE_CAPACITY: {e}");
+                AppError::BadRequest {
+                    message: "Invalid PRODUCT_CACHE_CAPACITY".to_string(),
+                    error_id: uuid::Uuid::new_v4(),
+                }
+            })?;
+        println!("Product cache holds up to {product_cache_capacity} entries");
+
         println!("Configuration loaded successfully!");
 
         Ok(Config {
             database_url,
             jwt_secret,
             jwt_expiration,
+            refresh_token_ttl_seconds,
             rate_limit_per_ip,
             rate_limit_per_user,
+            rate_limit_policies,
+            rate_limit_backend,
+            redis_url,
+            rate_limit_eviction_interval_seconds,
+            trusted_proxies,
+            reservation_ttl_seconds,
+            reservation_sweep_interval_seconds,
+            media_root,
+            media_base_url,
+            max_image_bytes,
+            base_currency,
+            idempotency_ttl_seconds,
+            webhook_max_attempts,
+            webhook_retry_base_seconds,
+            webhook_timeout_seconds,
+            webhook_poll_interval_seconds,
+            product_cache_enabled,
+            product_cache_ttl_seconds,
+            product_cache_capacity,
         })
     }
 }
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How many units of `currency` one unit of the base currency (`BASE_CURRENCY`)
+/// buys. The base currency has a row too, fixed at 1, so every product currency
+/// references a row here.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "exchange_rates")]
+pub struct Model {
+    /// ISO 4217 code
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub currency: String,
+    pub rate: Decimal,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::product::Entity")]
+    Products,
+}
+
+impl Related<super::product::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Products.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
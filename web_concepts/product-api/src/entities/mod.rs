@@ -1,6 +1,36 @@
+pub mod category;
+pub mod exchange_rate;
+pub mod idempotency_key;
 pub mod prelude;
+pub mod price_schedule;
 pub mod product;
+pub mod product_history;
+pub mod product_image;
+pub mod product_variant;
+pub mod refresh_token;
+pub mod revoked_access_token;
+pub mod stock_movement;
+pub mod stock_reservation;
 pub mod user;
+pub mod user_token_revocation;
+pub mod webhook_delivery;
+pub mod webhook_event;
+pub mod webhook_subscription;
 
+pub use category::Entity as Category;
+pub use exchange_rate::Entity as ExchangeRate;
+pub use idempotency_key::Entity as IdempotencyKey;
+pub use price_schedule::Entity as PriceSchedule;
 pub use product::Entity as Product;
+pub use product_history::Entity as ProductHistory;
+pub use product_image::Entity as ProductImage;
+pub use product_variant::Entity as ProductVariant;
+pub use refresh_token::Entity as RefreshToken;
+pub use revoked_access_token::Entity as RevokedAccessToken;
+pub use stock_movement::Entity as StockMovement;
+pub use stock_reservation::Entity as StockReservation;
 pub use user::Entity as User;
+pub use user_token_revocation::Entity as UserTokenRevocation;
+pub use webhook_delivery::Entity as WebhookDelivery;
+pub use webhook_event::Entity as WebhookEvent;
+pub use webhook_subscription::Entity as WebhookSubscription;
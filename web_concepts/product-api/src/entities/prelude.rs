@@ -1,2 +1,17 @@
+pub use super::category::Entity as Category;
+pub use super::exchange_rate::Entity as ExchangeRate;
+pub use super::idempotency_key::Entity as IdempotencyKey;
+pub use super::price_schedule::Entity as PriceSchedule;
 pub use super::product::Entity as Product;
+pub use super::product_history::Entity as ProductHistory;
+pub use super::product_image::Entity as ProductImage;
+pub use super::product_variant::Entity as ProductVariant;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::revoked_access_token::Entity as RevokedAccessToken;
+pub use super::stock_movement::Entity as StockMovement;
+pub use super::stock_reservation::Entity as StockReservation;
 pub use super::user::Entity as User;
+pub use super::user_token_revocation::Entity as UserTokenRevocation;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
+pub use super::webhook_event::Entity as WebhookEvent;
+pub use super::webhook_subscription::Entity as WebhookSubscription;
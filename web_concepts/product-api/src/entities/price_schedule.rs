@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where a schedule's window stands relative to a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceScheduleStatus {
+    /// Starts in the future
+    Scheduled,
+    /// The window contains the current time
+    Active,
+    /// Ended
+    Expired,
+}
+
+/// A price that applies to a product during `[effective_from, effective_to)`.
+/// Outside every window the product sells at its base `price`; when windows
+/// overlap, the one that started last wins.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "price_schedules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub price: Decimal,
+    pub effective_from: DateTime<Utc>,
+    /// `None` keeps the price in effect until another schedule or a delete
+    pub effective_to: Option<DateTime<Utc>>,
+    /// Free text such as "Weekend sale"
+    pub label: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Model {
+    pub fn status_at(&self, at: DateTime<Utc>) -> PriceScheduleStatus {
+        if at < self.effective_from {
+            PriceScheduleStatus::Scheduled
+        } else if self
+            .effective_to
+            .is_some_and(|effective_to| at >= effective_to)
+        {
+            PriceScheduleStatus::Expired
+        } else {
+            PriceScheduleStatus::Active
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::product::Entity",
+        from = "Column::ProductId",
+        to = "super::product::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Product,
+}
+
+impl Related<super::product::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Product.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
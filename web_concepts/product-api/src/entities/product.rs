@@ -11,12 +11,26 @@ pub struct Model {
     pub name: String,
     pub description: Option<String>,
     pub price: Decimal,
+    /// ISO 4217 code of `price`, variant prices and price schedules
+    pub currency: String,
     pub quantity: i32,
+    /// Units held by active reservations; still part of `quantity`
+    pub reserved_quantity: i32,
+    /// Name of the product's category, kept in step with `categories.name`
     pub category: Option<String>,
+    pub category_id: Option<Uuid>,
+    /// Unique among live products
+    pub sku: Option<String>,
+    /// EAN-13 or UPC-A as entered; unique among live products in its EAN-13 form
+    pub barcode: Option<String>,
     pub created_by: Option<Uuid>,
     pub updated_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Bumped on every update; exposed as the ETag for optimistic concurrency
+    pub version: i32,
+    /// Set when the product is moved to the trash; `None` for live products
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -33,6 +47,32 @@ pub enum Relation {
         to = "super::user::Column::Id"
     )]
     UpdatedByUser,
+    #[sea_orm(
+        belongs_to = "super::category::Entity",
+        from = "Column::CategoryId",
+        to = "super::category::Column::Id",
+        on_delete = "Restrict"
+    )]
+    Category,
+    #[sea_orm(
+        belongs_to = "super::exchange_rate::Entity",
+        from = "Column::Currency",
+        to = "super::exchange_rate::Column::Currency",
+        on_delete = "Restrict"
+    )]
+    ExchangeRate,
+    #[sea_orm(has_many = "super::product_history::Entity")]
+    History,
+    #[sea_orm(has_many = "super::price_schedule::Entity")]
+    PriceSchedules,
+    #[sea_orm(has_many = "super::product_image::Entity")]
+    Images,
+    #[sea_orm(has_many = "super::product_variant::Entity")]
+    Variants,
+    #[sea_orm(has_many = "super::stock_movement::Entity")]
+    StockMovements,
+    #[sea_orm(has_many = "super::stock_reservation::Entity")]
+    StockReservations,
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -41,4 +81,72 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl Related<super::exchange_rate::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExchangeRate.def()
+    }
+}
+
+impl Related<super::price_schedule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PriceSchedules.def()
+    }
+}
+
+impl Related<super::product_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::History.def()
+    }
+}
+
+impl Related<super::product_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Images.def()
+    }
+}
+
+impl Related<super::product_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Variants.def()
+    }
+}
+
+impl Related<super::stock_movement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockMovements.def()
+    }
+}
+
+impl Related<super::stock_reservation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockReservations.def()
+    }
+}
+
+impl Model {
+    /// Units that can still be sold or reserved
+    pub fn available_quantity(&self) -> i32 {
+        (self.quantity - self.reserved_quantity).max(0)
+    }
+}
+
+impl Entity {
+    /// Products that have not been soft-deleted. Use this instead of `find()`
+    /// everywhere except trash management.
+    pub fn find_active() -> Select<Entity> {
+        Self::find().filter(Column::DeletedAt.is_null())
+    }
+
+    /// Soft-deleted products (the trash)
+    pub fn find_deleted() -> Select<Entity> {
+        Self::find().filter(Column::DeletedAt.is_not_null())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
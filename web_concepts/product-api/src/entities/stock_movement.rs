@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum StockMovementReason {
+    /// Goods received from a supplier (always positive)
+    #[sea_orm(string_value = "receipt")]
+    Receipt,
+    /// Goods sold (always negative)
+    #[sea_orm(string_value = "sale")]
+    Sale,
+    /// Stock count corrections, shrinkage, damage
+    #[sea_orm(string_value = "adjustment")]
+    Adjustment,
+    /// Goods returned by a customer (always positive)
+    #[sea_orm(string_value = "return")]
+    Return,
+    /// Moved to or from another location
+    #[sea_orm(string_value = "transfer")]
+    Transfer,
+}
+
+impl StockMovementReason {
+    /// Whether a signed quantity delta makes sense for this reason
+    pub fn allows_delta(&self, quantity_delta: i32) -> bool {
+        match self {
+            Self::Receipt | Self::Return => quantity_delta > 0,
+            Self::Sale => quantity_delta < 0,
+            Self::Adjustment | Self::Transfer => quantity_delta != 0,
+        }
+    }
+}
+
+/// One entry of the inventory ledger. `products.quantity` is the running total
+/// of these deltas and is only ever changed together with a new row here.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "stock_movements")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub reason: StockMovementReason,
+    pub quantity_delta: i32,
+    /// Product quantity right after this movement
+    pub quantity_after: i32,
+    pub reference: Option<String>,
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::product::Entity",
+        from = "Column::ProductId",
+        to = "super::product::Column::Id"
+    )]
+    Product,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedBy",
+        to = "super::user::Column::Id"
+    )]
+    CreatedByUser,
+}
+
+impl Related<super::product::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Product.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
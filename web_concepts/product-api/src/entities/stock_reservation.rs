@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum ReservationStatus {
+    /// Holding stock until it is confirmed, released or expires
+    #[sea_orm(string_value = "active")]
+    Active,
+    /// Turned into a sale; the units left `products.quantity`
+    #[sea_orm(string_value = "confirmed")]
+    Confirmed,
+    /// Given back by the caller
+    #[sea_orm(string_value = "released")]
+    Released,
+    /// Given back by the expiry task after `expires_at` passed
+    #[sea_orm(string_value = "expired")]
+    Expired,
+}
+
+/// A temporary hold on stock, e.g. while a customer pays. Active reservations
+/// are summed into `products.reserved_quantity`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "stock_reservations")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: i32,
+    pub status: ReservationStatus,
+    /// Caller's identifier, e.g. a checkout or order ID
+    pub reference: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// When the reservation left the `active` state
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Model {
+    /// Still holding stock at `now`. An active reservation past its expiry no
+    /// longer counts even if the expiry task has not caught up with it yet.
+    pub fn is_holding(&self, now: DateTime<Utc>) -> bool {
+        self.status == ReservationStatus::Active && self.expires_at > now
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::product::Entity",
+        from = "Column::ProductId",
+        to = "super::product::Column::Id"
+    )]
+    Product,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedBy",
+        to = "super::user::Column::Id"
+    )]
+    CreatedByUser,
+}
+
+impl Related<super::product::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Product.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
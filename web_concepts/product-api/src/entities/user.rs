@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_role")]
 pub enum UserRole {
     #[sea_orm(string_value = "admin")]
@@ -30,12 +33,17 @@ impl UserRole {
         matches!(self, UserRole::Admin)
     }
 
+    pub fn can_administer(&self) -> bool {
+        matches!(self, UserRole::Admin)
+    }
+
     pub fn has_permission(&self, permission: &Permission) -> bool {
         match permission {
             Permission::Create => self.can_create(),
             Permission::Read => self.can_read(),
             Permission::Update => self.can_update(),
             Permission::Delete => self.can_delete(),
+            Permission::Admin => self.can_administer(),
         }
     }
 }
@@ -46,6 +54,9 @@ pub enum Permission {
     Read,
     Update,
     Delete,
+    /// Running the service rather than editing the catalog: the trash,
+    /// exchange rates, webhooks, metrics and user accounts
+    Admin,
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// The subscriber answered with a 2xx
+    #[sea_orm(string_value = "delivered")]
+    Delivered,
+    /// Gave up after the last retry
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+/// One event for one subscription: an outbox entry until it is delivered or
+/// gives up, and a delivery log entry afterwards
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub subscription_id: Uuid,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the dispatcher picks the delivery up next (pending only)
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last attempt, if the subscriber answered at all
+    pub response_status: Option<i16>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook_event::Entity",
+        from = "Column::EventId",
+        to = "super::webhook_event::Column::Id"
+    )]
+    Event,
+    #[sea_orm(
+        belongs_to = "super::webhook_subscription::Entity",
+        from = "Column::SubscriptionId",
+        to = "super::webhook_subscription::Column::Id"
+    )]
+    Subscription,
+}
+
+impl Related<super::webhook_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Event.def()
+    }
+}
+
+impl Related<super::webhook_subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Subscription.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, Iterable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+pub enum WebhookEventType {
+    #[sea_orm(string_value = "product.created")]
+    #[serde(rename = "product.created")]
+    ProductCreated,
+    /// Any change to a live product, including a restore from the trash
+    #[sea_orm(string_value = "product.updated")]
+    #[serde(rename = "product.updated")]
+    ProductUpdated,
+    /// Moved to the trash
+    #[sea_orm(string_value = "product.deleted")]
+    #[serde(rename = "product.deleted")]
+    ProductDeleted,
+    /// Quantity dropped to or below a subscription's `low_stock_threshold`
+    #[sea_orm(string_value = "product.low_stock")]
+    #[serde(rename = "product.low_stock")]
+    ProductLowStock,
+}
+
+impl WebhookEventType {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ProductCreated => "product.created",
+            Self::ProductUpdated => "product.updated",
+            Self::ProductDeleted => "product.deleted",
+            Self::ProductLowStock => "product.low_stock",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|event_type| event_type.name() == name)
+    }
+}
+
+/// Something that happened to a product, queued for every subscription to its
+/// type. `payload` is the exact JSON body sent to subscribers.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_events")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub event_type: WebhookEventType,
+    pub product_id: Uuid,
+    pub payload: Json,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    Deliveries,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deliveries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,56 @@
+use super::webhook_event::WebhookEventType;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An admin-registered URL that receives signed webhook deliveries
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_subscriptions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub url: String,
+    /// HMAC-SHA256 key for `X-Webhook-Signature`
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// JSON array of event type names
+    pub event_types: Json,
+    /// `product.low_stock` fires when quantity drops from above this to at or below it
+    pub low_stock_threshold: i32,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Model {
+    /// Subscribed event types; unknown names are skipped
+    pub fn event_types(&self) -> Vec<WebhookEventType> {
+        serde_json::from_value::<Vec<String>>(self.event_types.clone())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|name| WebhookEventType::from_name(name))
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    Deliveries,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedBy",
+        to = "super::user::Column::Id"
+    )]
+    CreatedByUser,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deliveries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -105,6 +105,28 @@ impl ErrorRegistry {
                 documentation_url: Some("/docs/errors#resource-conflict"),
             },
 
+            AppError::PreconditionFailed { .. } => ErrorDefinition {
+                code: "RESOURCE_PRECONDITION_FAILED",
+                message: "Resource was modified since it was last read",
+                user_message: "This item was changed by someone else. Reload it and try again",
+                status_code: StatusCode::PRECONDITION_FAILED,
+                category: ErrorCategory::Resource,
+                severity: ErrorSeverity::Medium,
+                retryable: false,
+                documentation_url: Some("/docs/errors#resource-precondition-failed"),
+            },
+
+            AppError::IdempotencyKeyReused { .. } => ErrorDefinition {
+                code: "RESOURCE_IDEMPOTENCY_KEY_REUSED",
+                message: "Idempotency key was already used for a different request",
+                user_message: "This request key was already used for different data. Use a new key",
+                status_code: StatusCode::UNPROCESSABLE_ENTITY,
+                category: ErrorCategory::Resource,
+                severity: ErrorSeverity::Medium,
+                retryable: false,
+                documentation_url: Some("/docs/errors#resource-idempotency-key-reused"),
+            },
+
             // Database Errors
             AppError::DatabaseError { .. } => ErrorDefinition {
                 code: "DATABASE_ERROR",
@@ -311,5 +333,14 @@ pub fn not_found_error(resource_type: &str, resource_id: Option<&str>) -> AppErr
 /// Helper function to create conflict errors
 ///  USE THIS TO ADD NEW CONFLICT ERRORS EASILY
 pub fn conflict_error(resource_type: &str, message: &str) -> AppError {
-    AppError::conflict(resource_type.to_string(), message.to_string())
+    AppError::conflict(resource_type.to_string(), None, message.to_string())
+}
+
+/// Helper function to create conflict errors on a unique field
+pub fn field_conflict_error(resource_type: &str, field_name: &str, message: &str) -> AppError {
+    AppError::conflict(
+        resource_type.to_string(),
+        Some(field_name.to_string()),
+        message.to_string(),
+    )
 }
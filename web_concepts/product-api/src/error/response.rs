@@ -6,8 +6,9 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{error, info, warn};
+use utoipa::ToSchema;
 /// Standardized error response format
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     /// Machine-readable error code
     pub error: ErrorInfo,
@@ -20,7 +21,7 @@ pub struct ErrorResponse {
     pub debug_info: Option<DebugInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ErrorInfo {
     /// Error code (e.g., "AUTH_UNAUTHORIZED")
     pub code: String,
@@ -40,10 +41,11 @@ pub struct ErrorInfo {
     pub documentation_url: Option<String>,
     /// Additional error-specific fields
     #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = Object)]
     pub details: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DebugInfo {
     /// Detailed error information (only in development)
     pub details: String,
@@ -97,6 +99,54 @@ impl ErrorResponse {
                     );
                 }
             }
+            AppError::Conflict {
+                resource_type,
+                field,
+                ..
+            } => {
+                details.insert(
+                    "resource_type".to_string(),
+                    serde_json::Value::String(resource_type.clone()),
+                );
+                if let Some(field) = field {
+                    details.insert(
+                        "field".to_string(),
+                        serde_json::Value::String(field.clone()),
+                    );
+                }
+            }
+            AppError::PreconditionFailed {
+                resource_type,
+                resource_id,
+                current_etag,
+                ..
+            } => {
+                details.insert(
+                    "resource_type".to_string(),
+                    serde_json::Value::String(resource_type.clone()),
+                );
+                if let Some(id) = resource_id {
+                    details.insert(
+                        "resource_id".to_string(),
+                        serde_json::Value::String(id.clone()),
+                    );
+                }
+                if let Some(etag) = current_etag {
+                    details.insert(
+                        "current_etag".to_string(),
+                        serde_json::Value::String(etag.clone()),
+                    );
+                }
+            }
+            AppError::IdempotencyKeyReused {
+                idempotency_key,
+                ..
+            } => {
+                details.insert(
+                    "idempotency_key".to_string(),
+                    serde_json::Value::String(idempotency_key.clone()),
+                );
+            }
             AppError::RateLimitExceeded {
                 limit_type,
                 retry_after,
@@ -286,6 +336,17 @@ impl IntoResponse for AppError {
             .headers_mut()
             .insert("x-error-id", self.error_id().to_string().parse().unwrap());
 
+        // Let clients retry a failed conditional request without refetching
+        if let AppError::PreconditionFailed {
+            current_etag: Some(etag),
+            ..
+        } = &self
+        {
+            if let Ok(value) = etag.parse() {
+                response.headers_mut().insert("etag", value);
+            }
+        }
+
         // Add cache control for error responses
         response.headers_mut().insert(
             "cache-control",
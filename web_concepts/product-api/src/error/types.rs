@@ -44,10 +44,27 @@ pub enum AppError {
     #[error("Resource conflict")]
     Conflict {
         resource_type: String,
+        /// Unique field that collided, when the conflict is about one field
+        field: Option<String>,
         message: String,
         error_id: Uuid,
     },
 
+    #[error("Precondition failed")]
+    PreconditionFailed {
+        resource_type: String,
+        resource_id: Option<String>,
+        /// ETag of the current representation, so the client can refetch
+        current_etag: Option<String>,
+        error_id: Uuid,
+    },
+
+    #[error("Idempotency key reused with a different request")]
+    IdempotencyKeyReused {
+        idempotency_key: String,
+        error_id: Uuid,
+    },
+
     // Database Errors
     #[error("Database operation failed")]
     DatabaseError {
@@ -201,14 +218,35 @@ impl AppError {
         }
     }
 
-    pub fn conflict(resource_type: String, message: String) -> Self {
+    pub fn conflict(resource_type: String, field: Option<String>, message: String) -> Self {
         Self::Conflict {
             resource_type,
+            field,
             message,
             error_id: Uuid::new_v4(),
         }
     }
 
+    pub fn precondition_failed(
+        resource_type: String,
+        resource_id: Option<String>,
+        current_etag: Option<String>,
+    ) -> Self {
+        Self::PreconditionFailed {
+            resource_type,
+            resource_id,
+            current_etag,
+            error_id: Uuid::new_v4(),
+        }
+    }
+
+    pub fn idempotency_key_reused(idempotency_key: String) -> Self {
+        Self::IdempotencyKeyReused {
+            idempotency_key,
+            error_id: Uuid::new_v4(),
+        }
+    }
+
     pub fn database_error(operation: String, table: Option<String>, details: String) -> Self {
         Self::DatabaseError {
             operation,
@@ -251,6 +289,8 @@ impl AppError {
             Self::BadRequest { error_id, .. } => *error_id,
             Self::NotFound { error_id, .. } => *error_id,
             Self::Conflict { error_id, .. } => *error_id,
+            Self::PreconditionFailed { error_id, .. } => *error_id,
+            Self::IdempotencyKeyReused { error_id, .. } => *error_id,
             Self::DatabaseError { error_id, .. } => *error_id,
             Self::DatabaseConnectionError { error_id, .. } => *error_id,
             Self::ExternalServiceError { error_id, .. } => *error_id,
@@ -274,6 +314,8 @@ impl AppError {
             }
             Self::RateLimitExceeded { .. }
             | Self::Conflict { .. }
+            | Self::PreconditionFailed { .. }
+            | Self::IdempotencyKeyReused { .. }
             | Self::BusinessRuleViolation { .. } => ErrorSeverity::Medium,
             Self::DatabaseError { .. }
             | Self::ExternalServiceError { .. }
@@ -295,7 +337,10 @@ impl AppError {
                 ErrorCategory::Authorization
             }
             Self::ValidationError { .. } | Self::BadRequest { .. } => ErrorCategory::Validation,
-            Self::NotFound { .. } | Self::Conflict { .. } => ErrorCategory::Resource,
+            Self::NotFound { .. }
+            | Self::Conflict { .. }
+            | Self::PreconditionFailed { .. }
+            | Self::IdempotencyKeyReused { .. } => ErrorCategory::Resource,
             Self::DatabaseError { .. } | Self::DatabaseConnectionError { .. } => {
                 ErrorCategory::Database
             }
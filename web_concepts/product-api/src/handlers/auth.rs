@@ -1,16 +1,29 @@
 use crate::{
-    error::{AppError},
+    error::AppError,
     middleware::logging::{log_application_error, PerformanceTimer},
-    models::{LoginRequest, RegisterRequest},
+    models::{
+        Claims, LoginRequest, LogoutRequest, RefreshTokenRequest, RegisterRequest,
+        UpdateUserStatusRequest,
+    },
     repository::auth::AuthRepository,
     services::AuthService,
     AppState,
 };
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
 use std::sync::Arc;
 use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
 use validator::Validate;
 
+fn auth_service(state: &AppState) -> AuthService<AuthRepository> {
+    AuthService::new(Arc::new(AuthRepository::new(state.db.clone())))
+}
+
 #[instrument(
     name = "auth_login",
     skip(state, request),
@@ -19,6 +32,18 @@ use validator::Validate;
         request_size = %std::mem::size_of_val(&request)
     )
 )]
+/// Exchange credentials for a JWT
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in", body = AuthResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+    )
+)]
 pub async fn login(
     State(state): State<AppState>,
     Json(request): Json<LoginRequest>,
@@ -100,6 +125,18 @@ pub async fn login(
         request_size = %std::mem::size_of_val(&request)
     )
 )]
+/// Create a user account
+#[utoipa::path(
+    post,
+    path = "/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created", body = AuthResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 409, description = "Username or email already taken", body = ErrorResponse),
+    )
+)]
 pub async fn register(
     State(state): State<AppState>,
     Json(request): Json<RegisterRequest>,
@@ -159,6 +196,7 @@ pub async fn register(
                     resource_type,
                     message,
                     error_id,
+                    ..
                 } => {
                     warn!(
                         conflict_reason = %message,
@@ -187,3 +225,93 @@ pub async fn register(
         }
     }
 }
+
+/// Exchange a refresh token for new tokens
+///
+/// The presented refresh token is revoked and a new one is returned. Using a
+/// refresh token twice revokes every token issued since that login.
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New access and refresh tokens", body = AuthResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Refresh token invalid, expired or reused", body = ErrorResponse),
+    )
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(request): Json<RefreshTokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let response = auth_service(&state)
+        .refresh(state.config.clone(), request)
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Revoke the current access token and, if given, the refresh token
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    request_body(content = Option<LogoutRequest>, description = "Refresh token to revoke with the access token"),
+    responses(
+        (status = 204, description = "Logged out"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    request: Option<Json<LogoutRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
+    auth_service(&state)
+        .logout(&claims, request, &state.token_denylist)
+        .await?;
+    info!(user_id = %claims.sub, "User logged out");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Activate or deactivate a user account
+///
+/// Deactivation revokes the user's refresh tokens and every access token
+/// issued to them so far.
+#[utoipa::path(
+    patch,
+    path = "/users/{id}/status",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = UpdateUserStatusRequest,
+    responses(
+        (status = 200, description = "User updated", body = UserResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 422, description = "Admins cannot deactivate themselves", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_user_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateUserStatusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let response = auth_service(&state)
+        .set_user_active(
+            state.config.clone(),
+            &claims,
+            id,
+            request.is_active,
+            &state.token_denylist,
+        )
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -0,0 +1,143 @@
+use crate::{
+    error::AppError,
+    models::{CreateCategoryRequest, UpdateCategoryRequest},
+    repository::category::CategoryRepository,
+    services::CategoryService,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Flat list of the category tree; rebuild the hierarchy from parent_id
+#[utoipa::path(
+    get,
+    path = "/categories",
+    tag = "categories",
+    responses(
+        (status = 200, description = "All categories", body = CategoryListResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_categories(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let category_repository = Arc::new(CategoryRepository::new(state.db.clone()));
+    let category_service = CategoryService::new(category_repository);
+
+    let response = category_service.list_categories().await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a category
+#[utoipa::path(
+    get,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 200, description = "The category", body = CategoryResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_category(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let category_repository = Arc::new(CategoryRepository::new(state.db.clone()));
+    let category_service = CategoryService::new(category_repository);
+
+    let response = category_service.get_category(id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Create a category
+#[utoipa::path(
+    post,
+    path = "/categories",
+    tag = "categories",
+    request_body = CreateCategoryRequest,
+    responses(
+        (status = 201, description = "Category created", body = CategoryResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_category(
+    State(state): State<AppState>,
+    Json(request): Json<CreateCategoryRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let category_repository = Arc::new(CategoryRepository::new(state.db.clone()));
+    let category_service = CategoryService::new(category_repository);
+
+    let response = category_service.create_category(request).await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Rename, change the slug or move the category in the tree
+#[utoipa::path(
+    put,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    request_body = UpdateCategoryRequest,
+    responses(
+        (status = 200, description = "Category updated", body = CategoryResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_category(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateCategoryRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let category_repository = Arc::new(CategoryRepository::new(state.db.clone()));
+    let category_service = CategoryService::new(category_repository);
+
+    let response = category_service.update_category(id, request).await?;
+    // Products carry the category name, and the trending categories show it
+    state.product_cache.invalidate_all().await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Only empty leaf categories can be deleted
+#[utoipa::path(
+    delete,
+    path = "/categories/{id}",
+    tag = "categories",
+    params(("id" = Uuid, Path, description = "Category ID")),
+    responses(
+        (status = 204, description = "Category deleted"),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 409, description = "Category still has products or subcategories", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_category(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let category_repository = Arc::new(CategoryRepository::new(state.db.clone()));
+    let category_service = CategoryService::new(category_repository);
+
+    category_service.delete_category(id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,109 @@
+use crate::{
+    error::AppError, middleware::rbac::UserContext, models::UpsertExchangeRateRequest,
+    repository::exchange_rate::ExchangeRateRepository, services::ExchangeRateService,
+    utils::CurrencyConversion, AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+/// List exchange rates against the base currency
+#[utoipa::path(
+    get,
+    path = "/exchange-rates",
+    tag = "exchange-rates",
+    responses(
+        (status = 200, description = "All exchange rates", body = ExchangeRateListResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_exchange_rates(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let rate_repository = Arc::new(ExchangeRateRepository::new(state.db.clone()));
+    let rate_service =
+        ExchangeRateService::new(rate_repository, state.config.base_currency.clone());
+
+    let response = rate_service.list_rates().await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Create or replace the rate of a currency
+#[utoipa::path(
+    put,
+    path = "/exchange-rates/{currency}",
+    tag = "exchange-rates",
+    params(("currency" = String, Path, description = "ISO 4217 currency code")),
+    request_body = UpsertExchangeRateRequest,
+    responses(
+        (status = 200, description = "Rate stored", body = ExchangeRateResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 422, description = "The base currency has no rate", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn upsert_exchange_rate(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(currency): Path<String>,
+    Json(request): Json<UpsertExchangeRateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let rate_repository = Arc::new(ExchangeRateRepository::new(state.db.clone()));
+    let rate_service =
+        ExchangeRateService::new(rate_repository, state.config.base_currency.clone());
+
+    let response = rate_service
+        .upsert_rate(&currency, request, user.user_uuid())
+        .await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Delete the rate of a currency
+#[utoipa::path(
+    delete,
+    path = "/exchange-rates/{currency}",
+    tag = "exchange-rates",
+    params(("currency" = String, Path, description = "ISO 4217 currency code")),
+    responses(
+        (status = 204, description = "Rate deleted"),
+        (status = 404, description = "Exchange rate not found", body = ErrorResponse),
+        (status = 422, description = "The base currency has no rate", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_exchange_rate(
+    State(state): State<AppState>,
+    Path(currency): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let rate_repository = Arc::new(ExchangeRateRepository::new(state.db.clone()));
+    let rate_service =
+        ExchangeRateService::new(rate_repository, state.config.base_currency.clone());
+
+    rate_service.delete_rate(&currency).await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Resolve a `currency=` query parameter for the product read endpoints,
+/// falling back to the base currency
+pub async fn currency_conversion(
+    state: &AppState,
+    currency: Option<&str>,
+) -> Result<CurrencyConversion, AppError> {
+    let rate_repository = Arc::new(ExchangeRateRepository::new(state.db.clone()));
+    let rate_service =
+        ExchangeRateService::new(rate_repository, state.config.base_currency.clone());
+
+    rate_service.conversion(currency).await
+}
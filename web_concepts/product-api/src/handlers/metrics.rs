@@ -0,0 +1,16 @@
+use crate::AppState;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+
+/// Hit/miss counters of the product read cache since startup
+#[utoipa::path(
+    get,
+    path = "/metrics/cache",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Cache counters", body = CacheMetrics),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cache_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.product_cache.metrics()))
+}
@@ -1,2 +1,9 @@
 pub mod auth;
+pub mod category;
+pub mod exchange_rate;
+pub mod metrics;
+pub mod price_schedule;
 pub mod product;
+pub mod product_image;
+pub mod product_variant;
+pub mod webhook;
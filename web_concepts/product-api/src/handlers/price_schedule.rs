@@ -0,0 +1,140 @@
+use crate::{
+    error::AppError,
+    middleware::rbac::UserContext,
+    models::{CreatePriceScheduleRequest, UpdatePriceScheduleRequest},
+    repository::price_schedule::PriceScheduleRepository,
+    services::PriceScheduleService,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+/// List the price schedules of a product
+#[utoipa::path(
+    get,
+    path = "/products/{id}/price-schedules",
+    tag = "price-schedules",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Price schedules of the product", body = PriceScheduleListResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_price_schedules(
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let schedule_repository = Arc::new(PriceScheduleRepository::new(state.db.clone()));
+    let schedule_service = PriceScheduleService::new(schedule_repository);
+
+    let response = schedule_service.list_schedules(product_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Schedule a price change
+#[utoipa::path(
+    post,
+    path = "/products/{id}/price-schedules",
+    tag = "price-schedules",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body = CreatePriceScheduleRequest,
+    responses(
+        (status = 201, description = "Price schedule created", body = PriceScheduleResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_price_schedule(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<CreatePriceScheduleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let schedule_repository = Arc::new(PriceScheduleRepository::new(state.db.clone()));
+    let schedule_service = PriceScheduleService::new(schedule_repository);
+
+    let response = schedule_service
+        .create_schedule(product_id, request, user.user_uuid())
+        .await?;
+    // Stats average the effective prices
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Update a pending price schedule
+#[utoipa::path(
+    put,
+    path = "/products/{id}/price-schedules/{schedule_id}",
+    tag = "price-schedules",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("schedule_id" = Uuid, Path, description = "Price schedule ID"),
+    ),
+    request_body = UpdatePriceScheduleRequest,
+    responses(
+        (status = 200, description = "Price schedule updated", body = PriceScheduleResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Price schedule not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_price_schedule(
+    State(state): State<AppState>,
+    Path((product_id, schedule_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdatePriceScheduleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let schedule_repository = Arc::new(PriceScheduleRepository::new(state.db.clone()));
+    let schedule_service = PriceScheduleService::new(schedule_repository);
+
+    let response = schedule_service
+        .update_schedule(product_id, schedule_id, request)
+        .await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Cancel a price schedule
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/price-schedules/{schedule_id}",
+    tag = "price-schedules",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("schedule_id" = Uuid, Path, description = "Price schedule ID"),
+    ),
+    responses(
+        (status = 204, description = "Price schedule deleted"),
+        (status = 404, description = "Price schedule not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_price_schedule(
+    State(state): State<AppState>,
+    Path((product_id, schedule_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let schedule_repository = Arc::new(PriceScheduleRepository::new(state.db.clone()));
+    let schedule_service = PriceScheduleService::new(schedule_repository);
+
+    schedule_service
+        .delete_schedule(product_id, schedule_id)
+        .await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
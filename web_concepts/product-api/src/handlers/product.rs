@@ -1,62 +1,173 @@
 use crate::{
     error::AppError,
-    models::{CreateProductRequest, ProductSearchRequest, UpdateProductRequest},
-    repository::product::ProductRepository,
-    services::ProductService,
+    handlers::exchange_rate::currency_conversion,
+    middleware::rbac::UserContext,
+    models::{
+        BatchDeleteProductsRequest, BatchUpdateProductsRequest, CreateProductRequest,
+        CreateReservationRequest, ProductLookupQuery, ProductSearchRequest, StockAdjustmentRequest,
+        UpdateProductRequest,
+    },
+    repository::{
+        cached_product::CachedProductRepository, product::ProductRepository,
+        product_image::ProductImageRepository,
+    },
+    services::{ProductImageService, ProductService},
+    utils::{parse_import, version_etag, IfMatch, TransferFormat},
     AppState,
 };
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
+use utoipa::IntoParams;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationQuery {
     pub page: Option<u64>,
     pub per_page: Option<u64>,
+    pub cursor: Option<String>,
+    pub include_total: Option<bool>,
+    /// Convert prices into this currency (product listing only)
+    pub currency: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CategoryQuery {
+    /// Category name or slug
     pub category: String,
+    /// Also return products of all subcategories
+    pub include_descendants: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PriceRangeQuery {
     pub min_price: Decimal,
     pub max_price: Decimal,
+    /// Currency of the bounds and the returned prices; bounds default to `BASE_CURRENCY`
+    pub currency: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LowStockQuery {
     pub threshold: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SimilarProductsQuery {
     pub name: String,
     pub limit: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TrendingQuery {
     pub limit: Option<u64>,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    pub format: Option<String>,
+    pub atomic: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BatchQuery {
+    pub atomic: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    pub format: Option<String>,
 }
 
+/// Upper bound on rows accepted by a single import request
+pub const MAX_IMPORT_ROWS: usize = 10_000;
+/// Upper bound on items accepted by a single batch update or delete
+pub const MAX_BATCH_ITEMS: usize = 100;
+
+/// The product repository behind the shared read-through cache
+fn cached_product_repository(state: &AppState) -> CachedProductRepository<ProductRepository> {
+    CachedProductRepository::new(
+        ProductRepository::new(state.db.clone()),
+        state.product_cache.clone(),
+    )
+}
+
+fn check_batch(ids: &[Uuid]) -> Result<(), AppError> {
+    if ids.is_empty() || ids.len() > MAX_BATCH_ITEMS {
+        return Err(AppError::validation_error(
+            None,
+            format!("A batch must have 1 to {MAX_BATCH_ITEMS} items"),
+        ));
+    }
+    let mut seen = HashSet::new();
+    if let Some(id) = ids.iter().find(|id| !seen.insert(**id)) {
+        return Err(AppError::validation_error(
+            Some("ids".to_string()),
+            format!("Product {id} appears more than once in the batch"),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a product
+#[utoipa::path(
+    post,
+    path = "/products",
+    tag = "products",
+    request_body = CreateProductRequest,
+    responses(
+        (status = 201, description = "Product created", body = ProductResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 409, description = "SKU or barcode already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_product(
     State(state): State<AppState>,
-    Json(request): Json<CreateProductRequest>,
+    Extension(user): Extension<UserContext>,
+    Json(mut request): Json<CreateProductRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     request.validate()?;
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    // The audit columns always name the caller, whatever the body says
+    request.created_by = user.user_uuid();
+    request.updated_by = user.user_uuid();
+    request
+        .currency
+        .get_or_insert_with(|| state.config.base_currency.clone());
+
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service.create_product(request).await?;
@@ -64,114 +175,670 @@ pub async fn create_product(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+/// List products with offset or cursor paging
+#[utoipa::path(
+    get,
+    path = "/products",
+    tag = "products",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "One page of products", body = ProductListResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_all_products(
     State(state): State<AppState>,
     Query(params): Query<PaginationQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let conversion = currency_conversion(&state, params.currency.as_deref()).await?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service
-        .get_all_products(params.page, params.per_page)
+        .get_all_products(
+            params.page,
+            params.per_page,
+            params.cursor,
+            params.include_total,
+            // Listings keep each product's own currency unless one was asked for
+            params.currency.is_some().then_some(&conversion),
+        )
         .await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Get a product
+#[utoipa::path(
+    get,
+    path = "/products/{id}",
+    tag = "products",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "The product", body = ProductResponse, headers(("ETag" = String, description = "Current version of the product"))),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_product(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service.get_product(id).await?;
+    let etag = version_etag(response.version);
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((StatusCode::OK, [(header::ETAG, etag)], Json(response)))
+}
+
+/// Scanner lookup by exactly one of ?sku= or ?barcode=
+#[utoipa::path(
+    get,
+    path = "/products/lookup",
+    tag = "products",
+    params(ProductLookupQuery),
+    responses(
+        (status = 200, description = "The product", body = ProductResponse, headers(("ETag" = String, description = "Current version of the product"))),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn lookup_product(
+    State(state): State<AppState>,
+    Query(params): Query<ProductLookupQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .lookup_product(params.sku, params.barcode)
+        .await?;
+    let etag = version_etag(response.version);
+
+    Ok((StatusCode::OK, [(header::ETAG, etag)], Json(response)))
 }
 
+/// Update a product, optionally guarded by If-Match
+#[utoipa::path(
+    put,
+    path = "/products/{id}",
+    tag = "products",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag of the version being updated"),
+    ),
+    request_body = UpdateProductRequest,
+    responses(
+        (status = 200, description = "Product updated", body = ProductResponse, headers(("ETag" = String, description = "Current version of the product"))),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 409, description = "SKU or barcode already in use", body = ErrorResponse),
+        (status = 412, description = "If-Match does not match the current version", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update_product(
     State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(request): Json<UpdateProductRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     request.validate()?;
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    // If-Match is optional; without it the update applies to the current version
+    let if_match = headers
+        .get(header::IF_MATCH)
+        .map(|value| {
+            value.to_str().map(IfMatch::parse).map_err(|_| {
+                AppError::validation_error(
+                    Some("If-Match".to_string()),
+                    "Invalid If-Match header".to_string(),
+                )
+            })
+        })
+        .transpose()?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
-    let response = product_service.update_product(id, request).await?;
+    let response = product_service
+        .update_product(id, request, if_match, user.user_uuid())
+        .await?;
+    let etag = version_etag(response.version);
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((StatusCode::OK, [(header::ETAG, etag)], Json(response)))
 }
 
+/// Move a product to the trash
+#[utoipa::path(
+    delete,
+    path = "/products/{id}",
+    tag = "products",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 204, description = "Product moved to the trash"),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_product(
     State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
-    product_service.delete_product(id).await?;
+    product_service.delete_product(id, user.user_uuid()).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update many products at once, e.g. a repricing; one result per item
+#[utoipa::path(
+    patch,
+    path = "/products/batch",
+    tag = "products",
+    params(BatchQuery),
+    request_body = BatchUpdateProductsRequest,
+    responses(
+        (status = 200, description = "Per-item results", body = BatchWriteReport),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 422, description = "Atomic batch rolled back", body = BatchWriteReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn batch_update_products(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Query(params): Query<BatchQuery>,
+    Json(request): Json<BatchUpdateProductsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // Items are validated one by one, so one bad item doesn't reject the rest
+    let ids: Vec<Uuid> = request.items.iter().map(|item| item.id).collect();
+    check_batch(&ids)?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let report = product_service
+        .batch_update_products(
+            request.items,
+            params.atomic.unwrap_or(false),
+            user.user_uuid(),
+        )
+        .await?;
+
+    // A rolled back atomic batch wrote nothing, so don't report success
+    let status = if report.committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(report)))
+}
+
+/// Move many products to the trash at once; one result per item
+#[utoipa::path(
+    post,
+    path = "/products/batch-delete",
+    tag = "products",
+    params(BatchQuery),
+    request_body = BatchDeleteProductsRequest,
+    responses(
+        (status = 200, description = "Per-item results", body = BatchWriteReport),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 422, description = "Atomic batch rolled back", body = BatchWriteReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn batch_delete_products(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Query(params): Query<BatchQuery>,
+    Json(request): Json<BatchDeleteProductsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    check_batch(&request.ids)?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let report = product_service
+        .batch_delete_products(
+            request.ids,
+            params.atomic.unwrap_or(false),
+            user.user_uuid(),
+        )
+        .await?;
+
+    let status = if report.committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(report)))
+}
+
+/// Audit trail of a product, newest change first
+#[utoipa::path(
+    get,
+    path = "/products/{id}/history",
+    tag = "products",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        HistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "One page of history entries", body = ProductHistoryResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_product_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .get_product_history(id, params.page, params.per_page)
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Book a stock movement (receipt, sale, adjustment, return, transfer)
+#[utoipa::path(
+    post,
+    path = "/products/{id}/stock/adjust",
+    tag = "stock",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body = StockAdjustmentRequest,
+    responses(
+        (status = 201, description = "Movement booked", body = StockAdjustmentResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 422, description = "Stock would go negative", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn adjust_stock(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<StockAdjustmentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .adjust_stock(id, request, user.user_uuid())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Inventory ledger of a product, newest movement first
+#[utoipa::path(
+    get,
+    path = "/products/{id}/stock/movements",
+    tag = "stock",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        HistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "One page of stock movements", body = StockMovementListResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_stock_movements(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .get_stock_movements(id, params.page, params.per_page)
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Hold stock for a checkout until it is confirmed, released or expires
+#[utoipa::path(
+    post,
+    path = "/products/{id}/reservations",
+    tag = "stock",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body = CreateReservationRequest,
+    responses(
+        (status = 201, description = "Stock reserved", body = ReservationResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 422, description = "Not enough available stock", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_reservation(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateReservationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .reserve_stock(
+            id,
+            request,
+            state.config.reservation_ttl_seconds,
+            user.user_uuid(),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Get a stock reservation
+#[utoipa::path(
+    get,
+    path = "/reservations/{id}",
+    tag = "stock",
+    params(("id" = Uuid, Path, description = "Reservation ID")),
+    responses(
+        (status = 200, description = "The reservation", body = ReservationResponse),
+        (status = 404, description = "Reservation not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_reservation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service.get_reservation(id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Sell the held units
+#[utoipa::path(
+    post,
+    path = "/reservations/{id}/confirm",
+    tag = "stock",
+    params(("id" = Uuid, Path, description = "Reservation ID")),
+    responses(
+        (status = 200, description = "Reservation confirmed", body = ReservationConfirmationResponse),
+        (status = 404, description = "Reservation not found", body = ErrorResponse),
+        (status = 422, description = "Reservation is no longer active", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_reservation(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .confirm_reservation(id, user.user_uuid())
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Give the held units back
+#[utoipa::path(
+    post,
+    path = "/reservations/{id}/release",
+    tag = "stock",
+    params(("id" = Uuid, Path, description = "Reservation ID")),
+    responses(
+        (status = 200, description = "Reservation released", body = ReservationResponse),
+        (status = 404, description = "Reservation not found", body = ErrorResponse),
+        (status = 422, description = "Reservation is no longer active", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn release_reservation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service.release_reservation(id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+// Trash management (soft-deleted products)
+/// List soft-deleted products
+#[utoipa::path(
+    get,
+    path = "/products/trash",
+    tag = "trash",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "One page of trashed products", body = ProductListResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_trash(
+    State(state): State<AppState>,
+    Query(params): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .get_trash(
+            params.page,
+            params.per_page,
+            params.cursor,
+            params.include_total,
+        )
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Restore a product from the trash
+#[utoipa::path(
+    post,
+    path = "/products/{id}/restore",
+    tag = "trash",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Product restored", body = ProductResponse, headers(("ETag" = String, description = "Current version of the product"))),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 409, description = "SKU or barcode already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restore_product(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let response = product_service
+        .restore_product(id, user.user_uuid())
+        .await?;
+    let etag = version_etag(response.version);
+
+    Ok((StatusCode::OK, [(header::ETAG, etag)], Json(response)))
+}
+
+/// Permanently delete a trashed product and its images
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/purge",
+    tag = "trash",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 204, description = "Product purged"),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn purge_product(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+    let image_repository = Arc::new(ProductImageRepository::new(state.db.clone()));
+    let image_service = ProductImageService::new(image_repository, state.blob_store.clone());
+
+    // Purging cascades to the image records, so their blob keys are read first
+    let blob_keys = image_service.product_blob_keys(id).await?;
+    product_service.purge_product(id, user.user_uuid()).await?;
+    image_service.delete_blobs(blob_keys).await;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 // NEW SEARCH ENDPOINTS
 
-// Advanced search with multiple filters
+/// Advanced search with multiple filters
+#[utoipa::path(
+    get,
+    path = "/products/search",
+    tag = "products",
+    params(ProductSearchRequest),
+    responses(
+        (status = 200, description = "Matching products", body = ProductSearchResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn search_products(
     State(state): State<AppState>,
     Query(search_request): Query<ProductSearchRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     search_request.validate()?;
+    let conversion = currency_conversion(&state, search_request.currency.as_deref()).await?;
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
-    let response = product_service.search_products(search_request).await?;
+    let response = product_service
+        .search_products(search_request, &conversion)
+        .await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get products by category
+/// Get products by category
+#[utoipa::path(
+    get,
+    path = "/products/category",
+    tag = "products",
+    params(CategoryQuery),
+    responses(
+        (status = 200, description = "Products of the category", body = Vec<ProductResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_products_by_category(
     State(state): State<AppState>,
     Query(params): Query<CategoryQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service
-        .get_products_by_category(&params.category)
+        .get_products_by_category(
+            &params.category,
+            params.include_descendants.unwrap_or(false),
+        )
         .await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get products by price range
+/// Get products by price range
+#[utoipa::path(
+    get,
+    path = "/products/price-range",
+    tag = "products",
+    params(PriceRangeQuery),
+    responses(
+        (status = 200, description = "Products within the range", body = Vec<ProductResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_products_by_price_range(
     State(state): State<AppState>,
     Query(params): Query<PriceRangeQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let conversion = currency_conversion(&state, params.currency.as_deref()).await?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service
-        .get_products_by_price_range(params.min_price, params.max_price)
+        .get_products_by_price_range(
+            params.min_price,
+            params.max_price,
+            &conversion,
+            params.currency.is_some(),
+        )
         .await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get low stock products
+/// Get low stock products
+#[utoipa::path(
+    get,
+    path = "/products/low-stock",
+    tag = "stock",
+    params(LowStockQuery),
+    responses(
+        (status = 200, description = "Products below the threshold", body = Vec<ProductResponse>),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_low_stock_products(
     State(state): State<AppState>,
     Query(params): Query<LowStockQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let threshold = params.threshold.unwrap_or(10); // Default threshold of 10
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service.get_low_stock_products(threshold).await?;
@@ -179,26 +846,50 @@ pub async fn get_low_stock_products(
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get product statistics
+/// Get product statistics
+#[utoipa::path(
+    get,
+    path = "/products/stats",
+    tag = "products",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Catalogue statistics", body = ProductStatsResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_product_stats(
     State(state): State<AppState>,
+    Query(params): Query<StatsQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let conversion = currency_conversion(&state, params.currency.as_deref()).await?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
-    let response = product_service.get_product_stats().await?;
+    let response = product_service.get_product_stats(&conversion).await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get similar products
+/// Get similar products
+#[utoipa::path(
+    get,
+    path = "/products/similar",
+    tag = "products",
+    params(SimilarProductsQuery),
+    responses(
+        (status = 200, description = "Products with a similar name", body = Vec<ProductResponse>),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_similar_products(
     State(state): State<AppState>,
     Query(params): Query<SimilarProductsQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let limit = params.limit.unwrap_or(5);
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
     let response = product_service
@@ -208,17 +899,156 @@ pub async fn get_similar_products(
     Ok((StatusCode::OK, Json(response)))
 }
 
-// Get trending categories
+/// Get trending categories
+#[utoipa::path(
+    get,
+    path = "/products/trending-categories",
+    tag = "products",
+    params(TrendingQuery),
+    responses(
+        (status = 200, description = "Categories with the most products", body = Vec<CategoryStats>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_trending_categories(
     State(state): State<AppState>,
     Query(params): Query<TrendingQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     let limit = params.limit.unwrap_or(10);
+    let conversion = currency_conversion(&state, params.currency.as_deref()).await?;
 
-    let product_repository = Arc::new(ProductRepository::new(state.db.clone()));
+    let product_repository = Arc::new(cached_product_repository(&state));
     let product_service = ProductService::new(product_repository);
 
-    let response = product_service.get_trending_categories(limit).await?;
+    let response = product_service
+        .get_trending_categories(limit, &conversion)
+        .await?;
 
     Ok((StatusCode::OK, Json(response)))
 }
+
+/// Bulk import from a CSV or NDJSON upload
+#[utoipa::path(
+    post,
+    path = "/products/import",
+    tag = "products",
+    params(ImportQuery),
+    request_body(content = String, content_type = "text/csv", description = "CSV, or NDJSON with an application/x-ndjson Content-Type"),
+    responses(
+        (status = 200, description = "Per-row results", body = ProductImportReport),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 422, description = "Atomic import rolled back", body = ProductImportReport),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_products(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Query(params): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    // An explicit ?format= wins over the Content-Type header
+    let format = match params.format.as_deref() {
+        Some(name) => TransferFormat::from_name(name),
+        None => headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(TransferFormat::from_content_type),
+    }
+    .ok_or_else(|| {
+        AppError::validation_error(
+            Some("format".to_string()),
+            "Format must be csv or ndjson (use ?format= or a text/csv or application/x-ndjson Content-Type)".to_string(),
+        )
+    })?;
+
+    let rows = parse_import(&body, format);
+    if rows.is_empty() {
+        return Err(AppError::validation_error(
+            None,
+            "Import file contains no rows".to_string(),
+        ));
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::validation_error(
+            None,
+            format!("Import is limited to {MAX_IMPORT_ROWS} rows per request"),
+        ));
+    }
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let report = product_service
+        .import_products(
+            rows,
+            format,
+            params.atomic.unwrap_or(false),
+            user.user_uuid(),
+            &state.config.base_currency,
+        )
+        .await?;
+
+    // A rolled back atomic import wrote nothing, so don't report success
+    let status = if report.committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(report)))
+}
+
+/// Stream products matching the search filters as CSV or NDJSON
+#[utoipa::path(
+    get,
+    path = "/products/export",
+    tag = "products",
+    params(
+        ExportQuery,
+        ProductSearchRequest,
+    ),
+    responses(
+        (status = 200, description = "CSV or NDJSON file", body = String, content_type = ["text/csv", "application/x-ndjson"]),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_products(
+    State(state): State<AppState>,
+    Query(params): Query<ExportQuery>,
+    Query(search_request): Query<ProductSearchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    search_request.validate()?;
+
+    let format =
+        TransferFormat::from_name(params.format.as_deref().unwrap_or("csv")).ok_or_else(|| {
+            AppError::validation_error(
+                Some("format".to_string()),
+                "Format must be csv or ndjson".to_string(),
+            )
+        })?;
+    // Only the price filters are converted; rows keep their stored price and currency
+    let conversion = currency_conversion(&state, search_request.currency.as_deref()).await?;
+
+    let product_repository = Arc::new(cached_product_repository(&state));
+    let product_service = ProductService::new(product_repository);
+
+    let stream = product_service
+        .export_products(search_request, format, conversion)
+        .await?;
+
+    let disposition = format!("attachment; filename=\"products.{}\"", format.name());
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(stream),
+    ))
+}
@@ -0,0 +1,149 @@
+use crate::{
+    error::AppError,
+    middleware::rbac::UserContext,
+    models::ReorderImagesRequest,
+    repository::product_image::ProductImageRepository,
+    services::{ImageUpload, ProductImageService},
+    AppState,
+};
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+/// List the images of a product in display order
+#[utoipa::path(
+    get,
+    path = "/products/{id}/images",
+    tag = "images",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Images of the product", body = ProductImageListResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_images(
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let image_repository = Arc::new(ProductImageRepository::new(state.db.clone()));
+    let image_service = ProductImageService::new(image_repository, state.blob_store.clone());
+
+    let response = image_service.list_images(product_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Multipart upload; every part is an image and they are appended in upload order
+#[utoipa::path(
+    post,
+    path = "/products/{id}/images",
+    tag = "images",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body(content = ImageUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Images stored", body = ProductImageListResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 422, description = "Too many images for the product", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn upload_images(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Path(product_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let multipart_error = |e: axum::extract::multipart::MultipartError| AppError::BadRequest {
+        message: format!("Invalid multipart body: {e}"),
+        error_id: uuid::Uuid::new_v4(),
+    };
+
+    let mut uploads = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        let content_type = field.content_type().map(str::to_string);
+        let bytes = field.bytes().await.map_err(multipart_error)?;
+        uploads.push(ImageUpload {
+            content_type,
+            bytes,
+        });
+    }
+
+    let image_repository = Arc::new(ProductImageRepository::new(state.db.clone()));
+    let image_service = ProductImageService::new(image_repository, state.blob_store.clone());
+
+    let response = image_service
+        .upload_images(
+            product_id,
+            uploads,
+            state.config.max_image_bytes,
+            user.user_uuid(),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Set the display order of all images of a product
+#[utoipa::path(
+    put,
+    path = "/products/{id}/images/order",
+    tag = "images",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body = ReorderImagesRequest,
+    responses(
+        (status = 200, description = "Images reordered", body = ProductImageListResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reorder_images(
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ReorderImagesRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let image_repository = Arc::new(ProductImageRepository::new(state.db.clone()));
+    let image_service = ProductImageService::new(image_repository, state.blob_store.clone());
+
+    let response = image_service.reorder_images(product_id, request).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Delete an image
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/images/{image_id}",
+    tag = "images",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("image_id" = Uuid, Path, description = "Image ID"),
+    ),
+    responses(
+        (status = 204, description = "Image deleted"),
+        (status = 404, description = "Image not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_image(
+    State(state): State<AppState>,
+    Path((product_id, image_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let image_repository = Arc::new(ProductImageRepository::new(state.db.clone()));
+    let image_service = ProductImageService::new(image_repository, state.blob_store.clone());
+
+    image_service.delete_image(product_id, image_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
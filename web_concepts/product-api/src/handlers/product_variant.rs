@@ -0,0 +1,165 @@
+use crate::{
+    error::AppError,
+    models::{CreateVariantRequest, UpdateVariantRequest},
+    repository::product_variant::ProductVariantRepository,
+    services::ProductVariantService,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+/// List the variants of a product
+#[utoipa::path(
+    get,
+    path = "/products/{id}/variants",
+    tag = "variants",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Variants of the product", body = VariantListResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_variants(
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let variant_repository = Arc::new(ProductVariantRepository::new(state.db.clone()));
+    let variant_service = ProductVariantService::new(variant_repository);
+
+    let response = variant_service.list_variants(product_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a variant
+#[utoipa::path(
+    get,
+    path = "/products/{id}/variants/{variant_id}",
+    tag = "variants",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("variant_id" = Uuid, Path, description = "Variant ID"),
+    ),
+    responses(
+        (status = 200, description = "The variant", body = VariantResponse),
+        (status = 404, description = "Variant not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_variant(
+    State(state): State<AppState>,
+    Path((product_id, variant_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let variant_repository = Arc::new(ProductVariantRepository::new(state.db.clone()));
+    let variant_service = ProductVariantService::new(variant_repository);
+
+    let response = variant_service.get_variant(product_id, variant_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Add a variant to a product
+#[utoipa::path(
+    post,
+    path = "/products/{id}/variants",
+    tag = "variants",
+    params(("id" = Uuid, Path, description = "Product ID")),
+    request_body = CreateVariantRequest,
+    responses(
+        (status = 201, description = "Variant created", body = VariantResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse),
+        (status = 409, description = "SKU or option combination already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_variant(
+    State(state): State<AppState>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<CreateVariantRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let variant_repository = Arc::new(ProductVariantRepository::new(state.db.clone()));
+    let variant_service = ProductVariantService::new(variant_repository);
+
+    let response = variant_service.create_variant(product_id, request).await?;
+    // Stats count variants and their stock value
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Update a variant
+#[utoipa::path(
+    put,
+    path = "/products/{id}/variants/{variant_id}",
+    tag = "variants",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("variant_id" = Uuid, Path, description = "Variant ID"),
+    ),
+    request_body = UpdateVariantRequest,
+    responses(
+        (status = 200, description = "Variant updated", body = VariantResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Variant not found", body = ErrorResponse),
+        (status = 409, description = "SKU or option combination already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_variant(
+    State(state): State<AppState>,
+    Path((product_id, variant_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateVariantRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let variant_repository = Arc::new(ProductVariantRepository::new(state.db.clone()));
+    let variant_service = ProductVariantService::new(variant_repository);
+
+    let response = variant_service
+        .update_variant(product_id, variant_id, request)
+        .await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Delete a variant
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/variants/{variant_id}",
+    tag = "variants",
+    params(
+        ("id" = Uuid, Path, description = "Product ID"),
+        ("variant_id" = Uuid, Path, description = "Variant ID"),
+    ),
+    responses(
+        (status = 204, description = "Variant deleted"),
+        (status = 404, description = "Variant not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_variant(
+    State(state): State<AppState>,
+    Path((product_id, variant_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    let variant_repository = Arc::new(ProductVariantRepository::new(state.db.clone()));
+    let variant_service = ProductVariantService::new(variant_repository);
+
+    variant_service
+        .delete_variant(product_id, variant_id)
+        .await?;
+    state.product_cache.invalidate_aggregates().await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
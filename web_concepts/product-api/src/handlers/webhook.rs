@@ -0,0 +1,177 @@
+use crate::{
+    entities::webhook_delivery::DeliveryStatus,
+    error::AppError,
+    middleware::rbac::UserContext,
+    models::{CreateWebhookRequest, UpdateWebhookRequest},
+    repository::{
+        webhook_delivery::WebhookDeliveryRepository,
+        webhook_subscription::WebhookSubscriptionRepository,
+    },
+    services::WebhookService,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryLogQuery {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+    pub status: Option<DeliveryStatus>,
+}
+
+fn webhook_service(
+    state: &AppState,
+) -> WebhookService<WebhookSubscriptionRepository, WebhookDeliveryRepository> {
+    WebhookService::new(
+        Arc::new(WebhookSubscriptionRepository::new(state.db.clone())),
+        Arc::new(WebhookDeliveryRepository::new(state.db.clone())),
+    )
+}
+
+/// List webhook subscriptions
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, description = "All subscriptions", body = WebhookListResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_webhooks(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let response = webhook_service(&state).list_webhooks().await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a webhook subscription
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "The subscription", body = WebhookResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let response = webhook_service(&state).get_webhook(id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Subscribe a URL to product events
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Subscription created", body = WebhookResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserContext>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let response = webhook_service(&state)
+        .create_webhook(request, user.user_uuid())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Update a webhook subscription
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, description = "Subscription updated", body = WebhookResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    request.validate()?;
+
+    let response = webhook_service(&state).update_webhook(id, request).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Delete a webhook subscription
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = Uuid, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Subscription deleted"),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    webhook_service(&state).delete_webhook(id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delivery log of a webhook subscription, newest first
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("id" = Uuid, Path, description = "Webhook ID"),
+        DeliveryLogQuery,
+    ),
+    responses(
+        (status = 200, description = "One page of deliveries", body = WebhookDeliveryListResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeliveryLogQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let response = webhook_service(&state)
+        .list_deliveries(id, query.status, query.page, query.per_page)
+        .await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
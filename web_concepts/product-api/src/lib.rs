@@ -1,4 +1,6 @@
 // src/lib.rs
+pub mod app;
+pub mod cache;
 pub mod config;
 pub mod entities;
 pub mod error;
@@ -6,13 +8,21 @@ pub mod handlers;
 pub mod logging;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
+pub mod rate_limit;
 pub mod repository;
 pub mod services;
+pub mod storage;
 pub mod utils;
 
 pub use crate::error::AppError;
 
-// move AppState struct here:
+use crate::{
+    cache::ProductCache,
+    middleware::{rate_limit::RateLimiter, rbac::RbacConfig},
+    services::TokenDenylist,
+    storage::BlobStore,
+};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 
@@ -20,4 +30,9 @@ use std::sync::Arc;
 pub struct AppState {
     pub db: DatabaseConnection,
     pub config: Arc<crate::config::Config>,
+    pub rate_limiter: RateLimiter,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub product_cache: Arc<dyn ProductCache>,
+    pub token_denylist: Arc<TokenDenylist>,
+    pub rbac: Arc<RbacConfig>,
 }
@@ -1,47 +1,39 @@
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse},
-    routing::{delete, get, post, put},
-    Router,
+use product_api::{
+    app::create_app,
+    cache::{DisabledProductCache, InMemoryProductCache, ProductCache},
+    config::Config,
+    error::AppError,
+    middleware::{rate_limit::RateLimiter, rbac::RbacConfig},
+    rate_limit::{
+        spawn_rate_limit_eviction, InMemoryRateLimitStore, Quota, RateLimitPolicies,
+        RateLimitStore, RedisRateLimitStore,
+    },
+    repository::{auth::AuthRepository, exchange_rate::ensure_base_currency},
+    services::{
+        spawn_idempotency_purge, spawn_reservation_expiry, spawn_token_denylist_sync,
+        spawn_token_purge, spawn_webhook_dispatcher, TokenDenylist, WebhookDispatchConfig,
+    },
+    storage::LocalBlobStore,
+    AppState,
 };
 use sea_orm::{Database, DatabaseConnection};
 use std::sync::Arc;
-use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{error, info, instrument, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
-mod config;
-mod entities;
-mod error;
-mod handlers;
-mod logging;
-mod middleware;
-mod models;
-mod repository;
-mod services;
-mod utils;
-
-use config::Config;
-use error::AppError;
-use handlers::{auth, product};
-use middleware::{
-    auth::auth_middleware,
-    logging::request_logging_middleware,
-    rate_limit::{ip_rate_limit_middleware, user_rate_limit_middleware, RateLimiter},
-    rbac::{
-        require_create_permission, require_delete_permission, require_read_permission,
-        require_update_permission,
-    },
-};
-
-#[derive(Clone)]
-pub struct AppState {
-    pub db: DatabaseConnection,
-    pub config: Arc<Config>,
-    pub rate_limiter: RateLimiter,
-}
+/// How often expired idempotency keys are deleted
+const IDEMPOTENCY_PURGE_INTERVAL_SECONDS: u64 = 3600;
+/// How often revoked access tokens and expired refresh tokens are dropped
+const TOKEN_PURGE_INTERVAL_SECONDS: u64 = 300;
+/// How often the token denylist picks up revocations made by other replicas
+const TOKEN_DENYLIST_SYNC_SECONDS: u64 = 5;
+/// Webhook deliveries claimed per dispatcher pass
+const WEBHOOK_BATCH_SIZE: u64 = 50;
+/// Independently locked shards of the in-memory rate limit store
+const RATE_LIMIT_SHARDS: usize = 16;
+/// Prefix of the rate limit keys in Redis
+const RATE_LIMIT_KEY_PREFIX: &str = "product_api:rate_limit:";
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
@@ -65,9 +57,98 @@ async fn main() -> Result<(), AppError> {
     tracing::info!("Connecting to database: {}", config.database_url);
     let db = connect_with_retry(&config.database_url).await?;
 
+    // Products default to the base currency, which needs its exchange rate row
+    ensure_base_currency(&db, &config.base_currency).await?;
+
+    // Read-through cache for product lookups and the stats dashboards
+    let product_cache: Arc<dyn ProductCache> = if config.product_cache_enabled {
+        tracing::info!(
+            "Caching product reads for {}s, up to {} entries",
+            config.product_cache_ttl_seconds,
+            config.product_cache_capacity
+        );
+        Arc::new(InMemoryProductCache::new(
+            std::time::Duration::from_secs(config.product_cache_ttl_seconds),
+            config.product_cache_capacity,
+        ))
+    } else {
+        tracing::info!("Product cache disabled");
+        Arc::new(DisabledProductCache)
+    };
+
+    // Expire stale stock reservations in the background
+    tracing::info!("Starting reservation expiry task...");
+    spawn_reservation_expiry(
+        db.clone(),
+        product_cache.clone(),
+        std::time::Duration::from_secs(config.reservation_sweep_interval_seconds.max(1)),
+    );
+
+    // Drop idempotency keys past their retention window
+    spawn_idempotency_purge(
+        db.clone(),
+        std::time::Duration::from_secs(IDEMPOTENCY_PURGE_INTERVAL_SECONDS),
+    );
+
+    // Revoked access tokens, checked by the auth middleware until they expire.
+    // Loaded before serving so a restart doesn't forget them.
+    let token_denylist = Arc::new(TokenDenylist::new());
+    let revocations = token_denylist
+        .reload(&AuthRepository::new(db.clone()))
+        .await?;
+    tracing::info!("Loaded {revocations} access token revocations");
+    spawn_token_denylist_sync(
+        db.clone(),
+        token_denylist.clone(),
+        std::time::Duration::from_secs(TOKEN_DENYLIST_SYNC_SECONDS),
+    );
+    spawn_token_purge(
+        db.clone(),
+        token_denylist.clone(),
+        std::time::Duration::from_secs(TOKEN_PURGE_INTERVAL_SECONDS),
+    );
+
+    // Deliver queued webhook events and retry failed ones
+    tracing::info!("Starting webhook dispatcher...");
+    spawn_webhook_dispatcher(
+        db.clone(),
+        WebhookDispatchConfig {
+            max_attempts: config.webhook_max_attempts.max(1),
+            retry_base: std::time::Duration::from_secs(config.webhook_retry_base_seconds),
+            timeout: std::time::Duration::from_secs(config.webhook_timeout_seconds.max(1)),
+            batch_size: WEBHOOK_BATCH_SIZE,
+        },
+        std::time::Duration::from_secs(config.webhook_poll_interval_seconds.max(1)),
+    )?;
+
     // Create rate limiter
     tracing::info!("Initializing rate limiter...");
-    let rate_limiter = RateLimiter::new(config.rate_limit_per_ip, config.rate_limit_per_user);
+    let rate_limit_store: Arc<dyn RateLimitStore> = if config.rate_limit_backend == "redis" {
+        tracing::info!("Sharing rate limits through Redis");
+        Arc::new(RedisRateLimitStore::connect(&config.redis_url, RATE_LIMIT_KEY_PREFIX).await?)
+    } else {
+        let store = Arc::new(InMemoryRateLimitStore::new(RATE_LIMIT_SHARDS));
+        spawn_rate_limit_eviction(
+            store.clone(),
+            std::time::Duration::from_secs(config.rate_limit_eviction_interval_seconds.max(1)),
+        );
+        store
+    };
+    let rate_limiter = RateLimiter::new(
+        rate_limit_store,
+        config.rate_limit_per_ip,
+        RateLimitPolicies::new(
+            Quota::per_minute(config.rate_limit_per_user),
+            config.rate_limit_policies.clone(),
+        ),
+    );
+
+    // Product images live on the local filesystem
+    tracing::info!("Using local blob store at {}", config.media_root);
+    let blob_store = Arc::new(LocalBlobStore::new(
+        &config.media_root,
+        &config.media_base_url,
+    ));
 
     // Create app state
     tracing::info!("Creating application state...");
@@ -75,18 +156,28 @@ async fn main() -> Result<(), AppError> {
         db,
         config,
         rate_limiter,
+        blob_store,
+        product_cache,
+        token_denylist,
+        rbac: Arc::new(RbacConfig::new()),
     };
 
     // Build the application router
     tracing::info!("Building application router...");
-    let app = create_app(state).await;
+    let app = create_app(state)?;
 
     // Start the server
     tracing::info!("Binding to address 0.0.0.0:8080");
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
     tracing::info!("Server starting successfully on http://0.0.0.0:8080");
 
-    axum::serve(listener, app).await?;
+    // The peer address is the client IP unless it's a trusted proxy
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
@@ -168,83 +259,3 @@ async fn shutdown_signal() {
         },
     }
 }
-
-async fn create_app(state: AppState) -> Router {
-    // Public routes (no authentication required)
-    let public_routes = Router::new()
-        .route("/health", get(health_check))
-        .route("/auth/login", post(auth::login))
-        .route("/auth/register", post(auth::register))
-        .layer(axum::middleware::from_fn_with_state(
-            state.rate_limiter.clone(),
-            ip_rate_limit_middleware,
-        ));
-
-    // Read-only routes (all authenticated users can access)
-    let read_only_routes = Router::new()
-        .route("/products", get(product::get_all_products))
-        .route("/products/:id", get(product::get_product))
-        .route("/products/search", get(product::search_products))
-        .route("/products/category", get(product::get_products_by_category))
-        .route(
-            "/products/price-range",
-            get(product::get_products_by_price_range),
-        )
-        .route("/products/low-stock", get(product::get_low_stock_products))
-        .route("/products/similar", get(product::get_similar_products))
-        .route("/products/stats", get(product::get_product_stats))
-        .route(
-            "/products/trending-categories",
-            get(product::get_trending_categories),
-        )
-        .layer(axum::middleware::from_fn(require_read_permission));
-
-    // Create routes (Admin and Manager can access)
-    let create_routes = Router::new()
-        .route("/products", post(product::create_product))
-        .layer(axum::middleware::from_fn(require_create_permission));
-
-    // Update routes (Admin and Manager can access)
-    let update_routes = Router::new()
-        .route("/products/:id", put(product::update_product))
-        .layer(axum::middleware::from_fn(require_update_permission));
-
-    // Delete routes (Admin only)
-    let delete_routes = Router::new()
-        .route("/products/:id", delete(product::delete_product))
-        .layer(axum::middleware::from_fn(require_delete_permission));
-
-    // Combine all protected routes
-    let protected_routes = Router::new()
-        .merge(read_only_routes)
-        .merge(create_routes)
-        .merge(update_routes)
-        .merge(delete_routes)
-        // Apply authentication and rate limiting to all protected routes
-        .layer(axum::middleware::from_fn_with_state(
-            state.rate_limiter.clone(),
-            user_rate_limit_middleware,
-        ))
-        .layer(axum::middleware::from_fn_with_state(
-            state.clone(),
-            auth_middleware,
-        ));
-
-    Router::new()
-        .merge(public_routes)
-        .merge(protected_routes)
-        .layer(
-            ServiceBuilder::new()
-                // Add request logging middleware (outermost layer for full request/response logging)
-                .layer(axum::middleware::from_fn(request_logging_middleware))
-                // Add HTTP tracing for internal spans
-                .layer(TraceLayer::new_for_http())
-                // Add CORS support
-                .layer(CorsLayer::permissive()),
-        )
-        .with_state(state)
-}
-
-async fn health_check() -> impl IntoResponse {
-    (StatusCode::OK, "Service is healthy")
-}
@@ -30,6 +30,13 @@ pub async fn auth_middleware(
 
     let claims = verify_jwt(token, &state.config.jwt_secret)?;
 
+    // Logged out, or issued before the user was deactivated
+    if state.token_denylist.is_revoked(&claims) {
+        return Err(AppError::unauthorized_with_context(
+            "Token has been revoked".to_string(),
+        ));
+    }
+
     // Add user info to request extensions for handlers to access
     request.extensions_mut().insert(claims);
 
@@ -0,0 +1,204 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8` or `fd00::/8`. A bare
+/// address is a single-host network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(net).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(net.into(), ip.into(), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+
+    /// Parse a comma separated list, as in `TRUSTED_PROXIES`
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|net| !net.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = u32::from(bits - prefix_len);
+    net >> shift == ip >> shift
+}
+
+impl FromStr for IpNet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid address in '{s}'"))?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("Invalid prefix length in '{s}'"))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// Works out which client a request came from. The TCP peer is the client
+/// unless it is one of our trusted proxies; then the forwarding headers are
+/// walked right to left, skipping further trusted proxies, and the first
+/// untrusted hop is the client. Entries left of it may be forged by the
+/// client, so they are never used.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpResolver {
+    trusted_proxies: Arc<Vec<IpNet>>,
+}
+
+impl ClientIpResolver {
+    pub fn new(trusted_proxies: Vec<IpNet>) -> Self {
+        Self {
+            trusted_proxies: Arc::new(trusted_proxies),
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    /// Client address from the TCP peer and the request headers. `None` only
+    /// when the server wasn't given the peer address.
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?.to_canonical();
+        if !self.is_trusted(peer) {
+            return Some(peer);
+        }
+
+        // RFC 7239 supersedes X-Forwarded-For when a proxy sends both
+        let hops = forwarded_for(headers).or_else(|| x_forwarded_for(headers));
+        let Some(hops) = hops else {
+            let real_ip = header_values(headers, "x-real-ip")
+                .next()
+                .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+            return Some(real_ip.map_or(peer, |ip| ip.to_canonical()));
+        };
+
+        let mut client = peer;
+        for hop in hops.into_iter().rev() {
+            // An obfuscated or malformed hop ends the chain we can vouch for
+            let Some(ip) = hop else {
+                break;
+            };
+            client = ip.to_canonical();
+            if !self.is_trusted(client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> impl Iterator<Item = &'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+}
+
+/// `for=` of every element of every `Forwarded` header, in order
+fn forwarded_for(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let hops: Vec<_> = header_values(headers, "forwarded")
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_forwarded_node(value.trim()))
+            })
+        })
+        .collect();
+    (!hops.is_empty()).then_some(hops)
+}
+
+/// A `for=` node: `192.0.2.60`, `"192.0.2.60:4711"`, `"[2001:db8::1]:4711"`,
+/// or `unknown` / `_hidden` identifiers, which yield `None`
+fn parse_forwarded_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    let host = match node.split_once(':') {
+        Some((host, _port)) => host,
+        None => node,
+    };
+    host.parse().ok()
+}
+
+fn x_forwarded_for(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let hops: Vec<_> = header_values(headers, "x-forwarded-for")
+        .flat_map(|value| value.split(','))
+        .map(|hop| hop.trim().parse().ok())
+        .collect();
+    (!hops.is_empty()).then_some(hops)
+}
+
+/// The resolved client address, stored in the request extensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Resolve the client address once per request for the middlewares and
+/// handlers behind it. Needs the server to be started with `ConnectInfo`.
+pub async fn client_ip_middleware(
+    State(resolver): State<ClientIpResolver>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(ip) = resolver.resolve(peer, request.headers()) {
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+
+    next.run(request).await
+}
+
+/// The address `client_ip_middleware` resolved for this request
+pub fn client_ip(request: &Request) -> Option<IpAddr> {
+    request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| *ip)
+}
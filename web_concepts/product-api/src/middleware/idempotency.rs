@@ -0,0 +1,258 @@
+use crate::{
+    error::{conflict_error, validation_error},
+    models::Claims,
+    repository::idempotency_key::{
+        IdempotencyKeyRepository, IdempotencyKeyRepositoryTrait, NewIdempotencyKey,
+    },
+    AppError, AppState,
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, sync::Arc};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses that were replayed from an earlier request
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+/// Body limit of routes that do not set their own; axum's default
+pub const DEFAULT_BODY_LIMIT_BYTES: usize = 2 * 1024 * 1024;
+/// Response headers stored with the body and sent back on a replay
+const REPLAYED_HEADERS: [&str; 3] = ["content-type", "etag", "location"];
+
+/// Body limits of the routes that set their own, by method and pattern. Keyed
+/// requests are buffered for fingerprinting up to their route's limit, so the
+/// key never turns away a body the handler would accept.
+#[derive(Debug, Clone, Default)]
+pub struct RouteBodyLimits(Arc<HashMap<(Method, String), usize>>);
+
+impl RouteBodyLimits {
+    pub fn new(limits: impl IntoIterator<Item = (Method, &'static str, usize)>) -> Self {
+        let limits = limits
+            .into_iter()
+            .map(|(method, pattern, limit)| ((method, pattern.to_string()), limit))
+            .collect();
+        Self(Arc::new(limits))
+    }
+
+    /// The body limit of the route matching `pattern`, in axum's `:param` form
+    pub fn limit(&self, method: &Method, pattern: &str) -> usize {
+        self.0
+            .get(&(method.clone(), pattern.to_string()))
+            .copied()
+            .unwrap_or(DEFAULT_BODY_LIMIT_BYTES)
+    }
+}
+
+/// Keys are opaque to us; clients usually send a UUID
+pub fn is_valid_idempotency_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= 255 && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// SHA-256 over method, path with query and body, hex encoded. A retry has to
+/// match all three to be replayed.
+pub fn request_fingerprint(method: &Method, path_and_query: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path_and_query.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn is_idempotent_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// Uploads can be far larger than any JSON body, so they are never buffered
+pub fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/"))
+}
+
+/// Replay the stored response for a retried write that carries an
+/// `Idempotency-Key` header, instead of running it again. Keys are scoped to
+/// the authenticated user, so this has to run inside `auth_middleware`.
+pub async fn idempotency_middleware(
+    State((state, body_limits)): State<(AppState, RouteBodyLimits)>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !is_idempotent_method(request.method()) || is_multipart(request.headers()) {
+        return Ok(next.run(request).await);
+    }
+    let Some(header) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = header
+        .to_str()
+        .ok()
+        .filter(|key| is_valid_idempotency_key(key))
+        .ok_or_else(|| {
+            validation_error(
+                "Idempotency-Key",
+                "Idempotency-Key must be 1 to 255 printable ASCII characters",
+            )
+        })?
+        .to_string();
+
+    let user_id = request
+        .extensions()
+        .get::<Claims>()
+        .and_then(|claims| uuid::Uuid::parse_str(&claims.sub).ok())
+        .ok_or_else(|| AppError::Unauthorized {
+            context: Some("Idempotency keys need an authenticated user".to_string()),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+    let body_limit = match request.extensions().get::<MatchedPath>() {
+        Some(matched) => body_limits.limit(request.method(), matched.as_str()),
+        None => DEFAULT_BODY_LIMIT_BYTES,
+    };
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, body_limit)
+        .await
+        .map_err(|e| AppError::BadRequest {
+            message: format!(
+                "Failed to read request body: {e}. This route accepts bodies up to {} MiB",
+                body_limit / (1024 * 1024)
+            ),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| parts.uri.path())
+        .to_string();
+    let fingerprint = request_fingerprint(&parts.method, &path, &body);
+
+    let repository = IdempotencyKeyRepository::new(state.db.clone());
+    let claimed_at = repository
+        .try_claim(NewIdempotencyKey {
+            user_id,
+            idempotency_key: key.clone(),
+            request_method: parts.method.to_string(),
+            request_path: path,
+            fingerprint: fingerprint.clone(),
+            expires_at: chrono::Utc::now()
+                + chrono::Duration::seconds(state.config.idempotency_ttl_seconds),
+        })
+        .await?;
+
+    let Some(claimed_at) = claimed_at else {
+        // Someone else holds the key; it may also have just expired, in which
+        // case the client can simply retry
+        let existing = repository.find(user_id, &key).await?.ok_or_else(|| {
+            conflict_error(
+                "IdempotencyKey",
+                "Idempotency-Key expired, retry the request",
+            )
+        })?;
+        if existing.fingerprint != fingerprint {
+            return Err(AppError::idempotency_key_reused(key));
+        }
+        return match existing.status_code {
+            Some(status_code) => replay(
+                status_code,
+                existing.response_headers,
+                existing.response_body.unwrap_or_default(),
+            ),
+            None => Err(conflict_error(
+                "IdempotencyKey",
+                "A request with this Idempotency-Key is still being processed",
+            )),
+        };
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Failures that say nothing about the request itself are not worth
+    // replaying; let the client retry them with the same key
+    let status = response.status();
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        if let Err(e) = repository.release(user_id, &key, claimed_at).await {
+            tracing::warn!(error = %e, "Failed to release idempotency key");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| AppError::InternalServerError {
+            context: Some(format!("Failed to read response body: {e}")),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+    let headers: serde_json::Map<String, serde_json::Value> = REPLAYED_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = parts.headers.get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.into()))
+        })
+        .collect();
+    if let Err(e) = repository
+        .complete(
+            user_id,
+            &key,
+            claimed_at,
+            status.as_u16(),
+            headers.into(),
+            body.to_vec(),
+        )
+        .await
+    {
+        // Without a stored response the claim would block retries until the
+        // lock times out
+        tracing::warn!(error = %e, "Failed to store idempotent response");
+        let _ = repository.release(user_id, &key, claimed_at).await;
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn replay(
+    status_code: i16,
+    headers: Option<serde_json::Value>,
+    body: Vec<u8>,
+) -> Result<Response, AppError> {
+    let status = u16::try_from(status_code)
+        .ok()
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .ok_or_else(|| AppError::InternalServerError {
+            context: Some(format!(
+                "Stored idempotent response has invalid status {status_code}"
+            )),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+    let mut header_map = HeaderMap::new();
+    if let Some(serde_json::Value::Object(headers)) = headers {
+        for (name, value) in headers {
+            let (Ok(name), Some(Ok(value))) = (
+                HeaderName::try_from(name),
+                value.as_str().map(HeaderValue::from_str),
+            ) else {
+                continue;
+            };
+            header_map.insert(name, value);
+        }
+    }
+    header_map.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    *response.headers_mut() = header_map;
+    Ok(response)
+}
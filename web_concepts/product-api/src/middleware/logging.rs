@@ -1,3 +1,4 @@
+use crate::middleware::client_ip::client_ip;
 use axum::{
     extract::{MatchedPath, Request},
     middleware::Next,
@@ -31,8 +32,8 @@ pub async fn request_logging_middleware(request: Request, next: Next) -> Respons
         .and_then(|h| h.to_str().ok())
         .unwrap_or("unknown");
 
-    // Extract IP address (considering proxies)
-    let client_ip = extract_client_ip(&request);
+    // Resolved by client_ip_middleware, considering trusted proxies
+    let client_ip = client_ip(&request).map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
 
     // Create a span for this request with structured fields
     let request_span = tracing::info_span!(
@@ -115,39 +116,6 @@ pub async fn request_logging_middleware(request: Request, next: Next) -> Respons
     response
 }
 
-// Extract client IP considering various proxy headers
-fn extract_client_ip(request: &Request) -> String {
-    // Try X-Forwarded-For header first (most common)
-    if let Some(forwarded_for) = request.headers().get("x-forwarded-for") {
-        if let Ok(forwarded_str) = forwarded_for.to_str() {
-            if let Some(ip) = forwarded_str.split(',').next() {
-                return ip.trim().to_string();
-            }
-        }
-    }
-
-    // Try X-Real-IP header
-    if let Some(real_ip) = request.headers().get("x-real-ip") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            return ip_str.to_string();
-        }
-    }
-
-    // Try X-Forwarded header
-    if let Some(forwarded) = request.headers().get("x-forwarded") {
-        if let Ok(forwarded_str) = forwarded.to_str() {
-            if let Some(ip_part) = forwarded_str.split("for=").nth(1) {
-                if let Some(ip) = ip_part.split(';').next() {
-                    return ip.trim().to_string();
-                }
-            }
-        }
-    }
-
-    // Fallback to connection info (would need to be passed from connection)
-    "unknown".to_string()
-}
-
 // Database operation logging utilities
 pub fn log_database_operation<'a, T>(
     operation: &'a str,
@@ -1,4 +1,6 @@
 pub mod auth;
+pub mod client_ip;
+pub mod idempotency;
 pub mod logging;
 pub mod rate_limit;
 pub mod rbac;
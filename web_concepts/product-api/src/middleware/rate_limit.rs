@@ -1,105 +1,86 @@
-use crate::{models::Claims};
+use crate::{
+    entities::user::UserRole,
+    error::AppError,
+    middleware::client_ip::client_ip,
+    models::Claims,
+    rate_limit::{Quota, RateLimitDecision, RateLimitPolicies, RateLimitStore, RouteGroup},
+};
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::HeaderMap,
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use std::{
-    collections::HashMap,
-    net::IpAddr,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
 };
 
 #[derive(Clone)]
 pub struct RateLimiter {
-    // IP-based rate limiting
-    ip_requests: Arc<Mutex<HashMap<IpAddr, Vec<Instant>>>>,
-    // User-based rate limiting
-    user_requests: Arc<Mutex<HashMap<String, Vec<Instant>>>>,
-    // Configuration
-    max_requests_per_minute: u32,
-    max_requests_per_user_per_minute: u32,
-    window_duration: Duration,
+    store: Arc<dyn RateLimitStore>,
+    // Budget for anonymous callers, per IP
+    ip_quota: Quota,
+    // Budgets for authenticated callers, per user and route group
+    user_policies: Arc<RateLimitPolicies>,
 }
 
 impl RateLimiter {
-    pub fn new(max_requests_per_minute: u32, max_requests_per_user_per_minute: u32) -> Self {
+    pub fn new(
+        store: Arc<dyn RateLimitStore>,
+        max_requests_per_minute: u32,
+        user_policies: RateLimitPolicies,
+    ) -> Self {
         Self {
-            ip_requests: Arc::new(Mutex::new(HashMap::new())),
-            user_requests: Arc::new(Mutex::new(HashMap::new())),
-            max_requests_per_minute,
-            max_requests_per_user_per_minute,
-            window_duration: Duration::from_secs(60),
+            store,
+            ip_quota: Quota::per_minute(max_requests_per_minute),
+            user_policies: Arc::new(user_policies),
         }
     }
 
-    pub fn check_ip_rate_limit(&self, ip: IpAddr) -> bool {
-        let mut requests = self.ip_requests.lock().unwrap();
-        let now = Instant::now();
-
-        // Clean old requests
-        let cutoff = now - self.window_duration;
-
-        let ip_requests = requests.entry(ip).or_default();
-        ip_requests.retain(|&time| time > cutoff);
-
-        // Check if under limit
-        if ip_requests.len() < self.max_requests_per_minute as usize {
-            ip_requests.push(now);
-            true
-        } else {
-            false
-        }
+    /// Count a request against an IP. `None` if the store is unavailable.
+    pub async fn check_ip(&self, ip: IpAddr) -> Option<RateLimitDecision> {
+        self.check(&format!("ip:{ip}"), self.ip_quota).await
     }
 
-    pub fn check_user_rate_limit(&self, user_id: &str) -> bool {
-        let mut requests = self.user_requests.lock().unwrap();
-        let now = Instant::now();
-
-        // Clean old requests
-        let cutoff = now - self.window_duration;
-
-        let user_requests = requests.entry(user_id.to_string()).or_default();
-        user_requests.retain(|&time| time > cutoff);
-
-        // Check if under limit
-        if user_requests.len() < self.max_requests_per_user_per_minute as usize {
-            user_requests.push(now);
-            true
-        } else {
-            false
-        }
+    /// Count a request against a user's bucket for one route group, sized by
+    /// the policy for that group and the user's role. `None` if the store is
+    /// unavailable.
+    pub async fn check_user(
+        &self,
+        user_id: &str,
+        role: &UserRole,
+        group: RouteGroup,
+    ) -> Option<RateLimitDecision> {
+        let quota = self.user_policies.quota_for(group, role);
+        self.check(&format!("user:{user_id}:{}", group.name()), quota)
+            .await
     }
 
-    pub fn get_ip_remaining_requests(&self, ip: IpAddr) -> u32 {
-        let requests = self.ip_requests.lock().unwrap();
-        let now = Instant::now();
-        let cutoff = now - self.window_duration;
-
-        if let Some(ip_requests) = requests.get(&ip) {
-            let recent_requests = ip_requests.iter().filter(|&&time| time > cutoff).count();
-            self.max_requests_per_minute
-                .saturating_sub(recent_requests as u32)
-        } else {
-            self.max_requests_per_minute
+    async fn check(&self, key: &str, quota: Quota) -> Option<RateLimitDecision> {
+        match self.store.check(key, quota).await {
+            Ok(decision) => Some(decision),
+            Err(e) => {
+                // Fail open: an outage of a shared store must not take the API down with it
+                tracing::warn!(error = %e, key, "Rate limit store unavailable, request not limited");
+                None
+            }
         }
     }
+}
 
-    pub fn get_user_remaining_requests(&self, user_id: &str) -> u32 {
-        let requests = self.user_requests.lock().unwrap();
-        let now = Instant::now();
-        let cutoff = now - self.window_duration;
-
-        if let Some(user_requests) = requests.get(user_id) {
-            let recent_requests = user_requests.iter().filter(|&&time| time > cutoff).count();
-            self.max_requests_per_user_per_minute
-                .saturating_sub(recent_requests as u32)
-        } else {
-            self.max_requests_per_user_per_minute
-        }
-    }
+fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("X-RateLimit-Limit", decision.limit.into());
+    headers.insert("X-RateLimit-Remaining", decision.remaining.into());
+    headers.insert("X-RateLimit-Reset", decision.reset_seconds().into());
+}
+
+/// The standard `RATE_LIMIT_EXCEEDED` error envelope, with the bucket's headers
+fn too_many_requests(decision: &RateLimitDecision, limit_type: String) -> Response {
+    let retry_after = decision.retry_after_seconds().max(1);
+    let mut response = AppError::rate_limit_exceeded(limit_type, Some(retry_after)).into_response();
+    insert_rate_limit_headers(response.headers_mut(), decision);
+    response
 }
 
 // Middleware for IP-based rate limiting (for public endpoints)
@@ -108,50 +89,28 @@ pub async fn ip_rate_limit_middleware(
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    // Extract IP address
-    let ip = extract_ip_from_request(&request);
-
-    if !rate_limiter.check_ip_rate_limit(ip) {
-        let remaining = rate_limiter.get_ip_remaining_requests(ip);
-
-        return Err((
-            StatusCode::TOO_MANY_REQUESTS,
-            [
-                (
-                    "X-RateLimit-Limit",
-                    rate_limiter.max_requests_per_minute.to_string(),
-                ),
-                ("X-RateLimit-Remaining", remaining.to_string()),
-                ("X-RateLimit-Reset", "60".to_string()),
-            ],
-            "Rate limit exceeded. Too many requests from this IP.",
-        )
-            .into_response());
+    // Without a resolved address (no ConnectInfo) all such requests share a bucket
+    let ip = client_ip(&request).unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    let Some(decision) = rate_limiter.check_ip(ip).await else {
+        return Ok(next.run(request).await);
+    };
+    if !decision.allowed {
+        return Err(too_many_requests(&decision, "ip".to_string()));
     }
 
-    let remaining = rate_limiter.get_ip_remaining_requests(ip);
     let mut response = next.run(request).await;
 
     // Add rate limit headers
-    response.headers_mut().insert(
-        "X-RateLimit-Limit",
-        rate_limiter
-            .max_requests_per_minute
-            .to_string()
-            .parse()
-            .unwrap(),
-    );
-    response.headers_mut().insert(
-        "X-RateLimit-Remaining",
-        remaining.to_string().parse().unwrap(),
-    );
+    insert_rate_limit_headers(response.headers_mut(), &decision);
 
     Ok(response)
 }
 
-// Middleware for user-based rate limiting (for authenticated endpoints)
+// Middleware for user-based rate limiting (for authenticated endpoints).
+// Each route group layers it with its own `RouteGroup`.
 pub async fn user_rate_limit_middleware(
-    State(rate_limiter): State<RateLimiter>,
+    State((rate_limiter, group)): State<(RateLimiter, RouteGroup)>,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
@@ -159,40 +118,23 @@ pub async fn user_rate_limit_middleware(
     let claims = request.extensions().get::<Claims>().cloned();
 
     if let Some(claims) = claims {
-        if !rate_limiter.check_user_rate_limit(&claims.sub) {
-            let remaining = rate_limiter.get_user_remaining_requests(&claims.sub);
-
-            return Err((
-                StatusCode::TOO_MANY_REQUESTS,
-                [
-                    (
-                        "X-RateLimit-Limit",
-                        rate_limiter.max_requests_per_user_per_minute.to_string(),
-                    ),
-                    ("X-RateLimit-Remaining", remaining.to_string()),
-                    ("X-RateLimit-Reset", "60".to_string()),
-                ],
-                "Rate limit exceeded. Too many requests for this user.",
-            )
-                .into_response());
+        let Some(decision) = rate_limiter
+            .check_user(&claims.sub, &claims.role, group)
+            .await
+        else {
+            return Ok(next.run(request).await);
+        };
+        if !decision.allowed {
+            return Err(too_many_requests(
+                &decision,
+                format!("user:{}", group.name()),
+            ));
         }
 
-        let remaining = rate_limiter.get_user_remaining_requests(&claims.sub);
         let mut response = next.run(request).await;
 
         // Add rate limit headers
-        response.headers_mut().insert(
-            "X-RateLimit-Limit",
-            rate_limiter
-                .max_requests_per_user_per_minute
-                .to_string()
-                .parse()
-                .unwrap(),
-        );
-        response.headers_mut().insert(
-            "X-RateLimit-Remaining",
-            remaining.to_string().parse().unwrap(),
-        );
+        insert_rate_limit_headers(response.headers_mut(), &decision);
 
         Ok(response)
     } else {
@@ -200,29 +142,3 @@ pub async fn user_rate_limit_middleware(
         ip_rate_limit_middleware(State(rate_limiter), request, next).await
     }
 }
-
-fn extract_ip_from_request(request: &Request) -> IpAddr {
-    // Try to get IP from X-Forwarded-For header (if behind proxy)
-    if let Some(forwarded_for) = request.headers().get("X-Forwarded-For") {
-        if let Ok(forwarded_str) = forwarded_for.to_str() {
-            if let Some(ip_str) = forwarded_str.split(',').next() {
-                if let Ok(ip) = ip_str.trim().parse::<IpAddr>() {
-                    return ip;
-                }
-            }
-        }
-    }
-
-    // Try to get IP from X-Real-IP header
-    if let Some(real_ip) = request.headers().get("X-Real-IP") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            if let Ok(ip) = ip_str.parse::<IpAddr>() {
-                return ip;
-            }
-        }
-    }
-
-    // Fallback to a default IP if we can't determine the real IP
-    // In a real application, you might want to extract this from the connection info
-    "127.0.0.1".parse().unwrap()
-}
@@ -4,128 +4,277 @@ use crate::{
     models::Claims,
 };
 use axum::{
-    extract::{Request},
+    extract::{MatchedPath, Request, State},
+    http::Method,
     middleware::Next,
     response::Response,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tracing::{info, warn};
 
-// RBAC configuration for different endpoints
+/// One segment of a route pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    /// `:id` as axum writes it, or `{id}` as OpenAPI does
+    Param,
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Segment> {
+    pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if segment.starts_with(':') || (segment.starts_with('{') && segment.ends_with('}')) {
+                Segment::Param
+            } else {
+                Segment::Literal(segment.to_string())
+            }
+        })
+        .collect()
+}
+
+/// The permissions each method of one route pattern requires
+#[derive(Debug, Clone)]
+struct RoutePolicy {
+    segments: Vec<Segment>,
+    methods: HashMap<Method, Vec<Permission>>,
+}
+
+impl RoutePolicy {
+    /// `None` if the path doesn't match, otherwise which segments matched
+    /// literally. Comparing those left to right makes `/products/search`
+    /// win over `/products/:id`.
+    fn match_path(&self, path: &[Segment]) -> Option<Vec<bool>> {
+        if self.segments.len() != path.len() {
+            return None;
+        }
+        self.segments
+            .iter()
+            .zip(path)
+            .map(|(pattern, segment)| match (pattern, segment) {
+                (Segment::Param, _) => Some(false),
+                (Segment::Literal(a), Segment::Literal(b)) if a == b => Some(true),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Which permissions each protected route requires, per method. Routes and
+/// methods without a policy are denied, and `check_routes` refuses to start
+/// the server while any registered route lacks one.
 #[derive(Debug, Clone, Default)]
 pub struct RbacConfig {
-    route_permissions: HashMap<String, Vec<Permission>>,
+    policies: Vec<RoutePolicy>,
 }
 
 impl RbacConfig {
+    /// The policies of every protected route in `create_app`
     pub fn new() -> Self {
-        let mut route_permissions = HashMap::new();
-
-        // Product endpoints permissions
-        route_permissions.insert("/products".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/create".to_string(), vec![Permission::Create]);
-        route_permissions.insert("/products/update".to_string(), vec![Permission::Update]);
-        route_permissions.insert("/products/delete".to_string(), vec![Permission::Delete]);
-
-        // Search endpoints (read-only)
-        route_permissions.insert("/products/search".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/category".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/price-range".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/low-stock".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/similar".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/stats".to_string(), vec![Permission::Read]);
-        route_permissions.insert("/products/trending-categories".to_string(), vec![Permission::Read]);
-
-        Self { route_permissions }
+        use Permission::{Admin, Create, Delete, Read, Update};
+
+        Self::default()
+            // Sessions
+            .route("/auth/logout", &[(Method::POST, Read)])
+            // Products
+            .route("/products", &[(Method::GET, Read), (Method::POST, Create)])
+            .route(
+                "/products/:id",
+                &[
+                    (Method::GET, Read),
+                    (Method::PUT, Update),
+                    (Method::DELETE, Delete),
+                ],
+            )
+            .route("/products/:id/history", &[(Method::GET, Read)])
+            .route("/products/:id/stock/movements", &[(Method::GET, Read)])
+            .route("/products/:id/stock/adjust", &[(Method::POST, Update)])
+            .route("/products/:id/reservations", &[(Method::POST, Update)])
+            .route("/products/lookup", &[(Method::GET, Read)])
+            .route("/products/search", &[(Method::GET, Read)])
+            .route("/products/category", &[(Method::GET, Read)])
+            .route("/products/price-range", &[(Method::GET, Read)])
+            .route("/products/low-stock", &[(Method::GET, Read)])
+            .route("/products/similar", &[(Method::GET, Read)])
+            .route("/products/stats", &[(Method::GET, Read)])
+            .route("/products/trending-categories", &[(Method::GET, Read)])
+            .route("/products/export", &[(Method::GET, Read)])
+            .route("/products/import", &[(Method::POST, Create)])
+            .route("/products/batch", &[(Method::PATCH, Update)])
+            .route("/products/batch-delete", &[(Method::POST, Delete)])
+            // Soft-deleted products
+            .route("/products/trash", &[(Method::GET, Admin)])
+            .route("/products/:id/restore", &[(Method::POST, Admin)])
+            .route("/products/:id/purge", &[(Method::DELETE, Admin)])
+            // Variants, images and price schedules
+            .route(
+                "/products/:id/variants",
+                &[(Method::GET, Read), (Method::POST, Create)],
+            )
+            .route(
+                "/products/:id/variants/:variant_id",
+                &[
+                    (Method::GET, Read),
+                    (Method::PUT, Update),
+                    (Method::DELETE, Delete),
+                ],
+            )
+            .route(
+                "/products/:id/images",
+                &[(Method::GET, Read), (Method::POST, Create)],
+            )
+            .route("/products/:id/images/order", &[(Method::PUT, Update)])
+            .route(
+                "/products/:id/images/:image_id",
+                &[(Method::DELETE, Delete)],
+            )
+            .route(
+                "/products/:id/price-schedules",
+                &[(Method::GET, Read), (Method::POST, Create)],
+            )
+            .route(
+                "/products/:id/price-schedules/:schedule_id",
+                &[(Method::PUT, Update), (Method::DELETE, Delete)],
+            )
+            // Reservations
+            .route("/reservations/:id", &[(Method::GET, Read)])
+            .route("/reservations/:id/confirm", &[(Method::POST, Update)])
+            .route("/reservations/:id/release", &[(Method::POST, Update)])
+            // Categories
+            .route(
+                "/categories",
+                &[(Method::GET, Read), (Method::POST, Create)],
+            )
+            .route(
+                "/categories/:id",
+                &[
+                    (Method::GET, Read),
+                    (Method::PUT, Update),
+                    (Method::DELETE, Delete),
+                ],
+            )
+            // Exchange rates
+            .route("/exchange-rates", &[(Method::GET, Read)])
+            .route(
+                "/exchange-rates/:currency",
+                &[(Method::PUT, Admin), (Method::DELETE, Admin)],
+            )
+            // Webhooks, metrics and user administration
+            .route("/webhooks", &[(Method::GET, Admin), (Method::POST, Admin)])
+            .route(
+                "/webhooks/:id",
+                &[
+                    (Method::GET, Admin),
+                    (Method::PUT, Admin),
+                    (Method::DELETE, Admin),
+                ],
+            )
+            .route("/webhooks/:id/deliveries", &[(Method::GET, Admin)])
+            .route("/metrics/cache", &[(Method::GET, Admin)])
+            .route("/users/:id/status", &[(Method::PATCH, Admin)])
     }
 
-    /// Get required permissions for a route and method
-    pub fn get_required_permissions(&self, path: &str, method: &str) -> Vec<Permission> {
-        // If a specific mapping exists in route_permissions, use it
-        if let Some(perms) = self.route_permissions.get(path) {
-            return perms.clone();
+    /// Require each `(method, permission)` on `pattern`. Patterns take path
+    /// parameters as `:name`; listing a method twice requires both
+    /// permissions.
+    pub fn route(mut self, pattern: &str, permissions: &[(Method, Permission)]) -> Self {
+        let segments = parse_pattern(pattern);
+        let index = match self.policies.iter().position(|p| p.segments == segments) {
+            Some(index) => index,
+            None => {
+                self.policies.push(RoutePolicy {
+                    segments,
+                    methods: HashMap::new(),
+                });
+                self.policies.len() - 1
+            }
+        };
+
+        let methods = &mut self.policies[index].methods;
+        for (method, permission) in permissions {
+            methods
+                .entry(method.clone())
+                .or_default()
+                .push(permission.clone());
         }
+        self
+    }
+
+    /// Permissions `method` on `path` requires, or `None` if no policy covers
+    /// it. `path` may be a concrete path or a route pattern.
+    pub fn required_permissions(&self, method: &Method, path: &str) -> Option<&[Permission]> {
+        let path = parse_pattern(path);
+        self.policies
+            .iter()
+            .filter_map(|policy| Some((policy.match_path(&path)?, policy)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .and_then(|(_, policy)| policy.methods.get(method))
+            .map(Vec::as_slice)
+    }
 
-        // Otherwise, infer from HTTP method
-        match method {
-            "POST" => vec![Permission::Create],
-            "GET" => vec![Permission::Read],
-            "PUT" => vec![Permission::Update],
-            "DELETE" => vec![Permission::Delete],
-            _ => vec![Permission::Read],
+    /// Fail unless every `(method, pattern)` has a policy of its own, not just
+    /// one whose parameters happen to match it
+    pub fn check_routes<S: AsRef<str>>(
+        &self,
+        routes: impl IntoIterator<Item = (Method, S)>,
+    ) -> Result<(), AppError> {
+        let missing: Vec<String> = routes
+            .into_iter()
+            .filter(|(method, pattern)| {
+                let segments = parse_pattern(pattern.as_ref());
+                !self
+                    .policies
+                    .iter()
+                    .any(|p| p.segments == segments && p.methods.contains_key(method))
+            })
+            .map(|(method, pattern)| format!("{method} {}", pattern.as_ref()))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
         }
+        Err(AppError::ConfigurationError {
+            parameter: "rbac".to_string(),
+            message: format!("Routes without an RBAC policy: {}", missing.join(", ")),
+            error_id: uuid::Uuid::new_v4(),
+        })
     }
 }
 
-/// RBAC middleware that checks user permissions
-// pub async fn rbac_middleware(mut request: Request, next: Next) -> Result<Response, AppError> {
-//     // Extract user claims from request extensions (set by auth middleware)
-//     let claims = request
-//         .extensions()
-//         .get::<Claims>()
-//         .cloned()
-//         .ok_or_else(|| {
-//             error!("RBAC middleware called without authentication context");
-//             AppError::unauthorized_with_context(
-//                 "Authentication required for this endpoint".to_string(),
-//             )
-//         })?;
-
-//     // Get request path and method
-//     let path = request.uri().path();
-//     let method = request.method().as_str();
-
-//     // Create RBAC config
-//     let rbac_config = RbacConfig::new();
-
-//     // Get required permissions for this endpoint
-//     let required_permissions = rbac_config.get_required_permissions(path, method);
-
-//     // Parse user role from claims
-//     let user_role = parse_user_role(&claims.username).await?;
-
-//     // Check if user has required permissions
-//     for permission in &required_permissions {
-//         if !user_role.has_permission(permission) {
-//             warn!(
-//                 user_id = %claims.sub,
-//                 username = %claims.username,
-//                 role = ?user_role,
-//                 required_permission = ?permission,
-//                 path = %path,
-//                 method = %method,
-//                 "Access denied: Insufficient permissions"
-//             );
-
-//             return Err(AppError::InsufficientPrivileges {
-//                 required_role: format!("{permission:?}"),
-//                 current_role: Some(format!("{user_role:?}")),
-//                 error_id: uuid::Uuid::new_v4(),
-//             });
-//         }
-//     }
-
-//     // Log successful authorization
-//     info!(
-//         user_id = %claims.sub,
-//         username = %claims.username,
-//         role = ?user_role,
-//         permissions = ?required_permissions,
-//         path = %path,
-//         method = %method,
-//         "Access granted: User has required permissions"
-//     );
-
-//     // Add user role to request extensions for handlers to use
-//     request.extensions_mut().insert(user_role.clone());
-//     request.extensions_mut().insert(UserContext {
-//         user_id: claims.sub.clone(),
-//         username: claims.username.clone(),
-//         role: user_role,
-//     });
-
-//     Ok(next.run(request).await)
-// }
+/// Authorize every protected route from `RbacConfig`. The role comes from the
+/// verified token, so it can't be changed without a new login; deactivated
+/// users are cut off by the token denylist.
+pub async fn rbac_middleware(
+    State(rbac): State<Arc<RbacConfig>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    // The pattern axum routed to, so policies line up with create_app exactly
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(matched) => matched.as_str().to_string(),
+        None => request.uri().path().to_string(),
+    };
+
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(AppError::unauthorized)?;
+
+    let Some(required_permissions) = rbac.required_permissions(request.method(), &path) else {
+        warn!(
+            user_id = %claims.sub,
+            path = %path,
+            method = %request.method(),
+            "Access denied: No RBAC policy for route"
+        );
+        return Err(AppError::forbidden(format!("{} {path}", request.method())));
+    };
+    let required_permissions = required_permissions.to_vec();
+
+    authorize(request, next, claims, &required_permissions).await
+}
 
 /// User context for handlers
 #[derive(Debug, Clone)]
@@ -136,6 +285,11 @@ pub struct UserContext {
 }
 
 impl UserContext {
+    /// The caller's user id, for `created_by`/`updated_by` and audit columns
+    pub fn user_uuid(&self) -> Option<uuid::Uuid> {
+        uuid::Uuid::parse_str(&self.user_id).ok()
+    }
+
     pub fn can_perform(&self, permission: &Permission) -> bool {
         self.role.has_permission(permission)
     }
@@ -153,22 +307,6 @@ impl UserContext {
     }
 }
 
-/// Helper function to get user role from database or cache
-/// In a real implementation, you'd cache this or get it from the JWT claims
-async fn parse_user_role(username: &str) -> Result<UserRole, AppError> {
-    // For now, parse from username (in real app, get from database or JWT)
-    // This is a simplified version - in production, you'd:
-    // 1. Store role in JWT claims during login
-    // 2. Or query database with caching
-    // 3. Or use a user service
-
-    match username {
-        name if name.contains("admin") => Ok(UserRole::Admin),
-        name if name.contains("manager") => Ok(UserRole::Manager),
-        _ => Ok(UserRole::User),
-    }
-}
-
 /// Middleware that requires CREATE permission
 pub async fn require_create_permission(request: Request, next: Next) -> Result<Response, AppError> {
     check_permission(request, next, Permission::Create).await
@@ -184,6 +322,11 @@ pub async fn require_delete_permission(request: Request, next: Next) -> Result<R
     check_permission(request, next, Permission::Delete).await
 }
 
+/// Middleware that requires ADMIN permission
+pub async fn require_admin_permission(request: Request, next: Next) -> Result<Response, AppError> {
+    check_permission(request, next, Permission::Admin).await
+}
+
 /// Middleware that requires READ permission (least restrictive)
 pub async fn require_read_permission(request: Request, next: Next) -> Result<Response, AppError> {
     check_permission(request, next, Permission::Read).await
@@ -191,7 +334,7 @@ pub async fn require_read_permission(request: Request, next: Next) -> Result<Res
 
 /// Helper function to check specific permission
 async fn check_permission(
-    mut request: Request,
+    request: Request,
     next: Next,
     required_permission: Permission,
 ) -> Result<Response, AppError> {
@@ -202,21 +345,35 @@ async fn check_permission(
         .cloned()
         .ok_or_else(AppError::unauthorized)?;
 
-    // Get user role
-    let user_role = parse_user_role(&claims.username).await?;
+    authorize(request, next, claims, &[required_permission]).await
+}
+
+/// Check the role in `claims` against `required_permissions` and hand the
+/// caller's `UserContext` to the handler
+async fn authorize(
+    mut request: Request,
+    next: Next,
+    claims: Claims,
+    required_permissions: &[Permission],
+) -> Result<Response, AppError> {
+    let user_role = claims.role.clone();
 
-    // Check permission
-    if !user_role.has_permission(&required_permission) {
+    if let Some(missing) = required_permissions
+        .iter()
+        .find(|permission| !user_role.has_permission(permission))
+    {
         warn!(
             user_id = %claims.sub,
             username = %claims.username,
             role = ?user_role,
-            required_permission = ?required_permission,
+            required_permission = ?missing,
+            path = %request.uri().path(),
+            method = %request.method(),
             "Access denied: Missing required permission"
         );
 
         return Err(AppError::InsufficientPrivileges {
-            required_role: format!("{required_permission:?}"),
+            required_role: format!("{missing:?}"),
             current_role: Some(format!("{user_role:?}")),
             error_id: uuid::Uuid::new_v4(),
         });
@@ -232,51 +389,9 @@ async fn check_permission(
     info!(
         user_id = %claims.sub,
         username = %claims.username,
-        permission = ?required_permission,
+        permissions = ?required_permissions,
         "Permission check passed"
     );
 
     Ok(next.run(request).await)
 }
-
-// Admin-only middleware (convenience function)
-// pub async fn require_admin(request: Request, next: Next) -> Result<Response, AppError> {
-//     let claims = request
-//         .extensions()
-//         .get::<Claims>()
-//         .cloned()
-//         .ok_or_else(AppError::unauthorized)?;
-
-//     let user_role = parse_user_role(&claims.username).await?;
-
-//     if !matches!(user_role, UserRole::Admin) {
-//         return Err(AppError::InsufficientPrivileges {
-//             required_role: "Admin".to_string(),
-//             current_role: Some(format!("{user_role:?}")),
-//             error_id: uuid::Uuid::new_v4(),
-//         });
-//     }
-
-//     Ok(next.run(request).await)
-// }
-
-// Manager or Admin middleware (convenience function)
-// pub async fn require_manager_or_admin(request: Request, next: Next) -> Result<Response, AppError> {
-//     let claims = request
-//         .extensions()
-//         .get::<Claims>()
-//         .cloned()
-//         .ok_or_else(AppError::unauthorized)?;
-
-//     let user_role = parse_user_role(&claims.username).await?;
-
-//     if !matches!(user_role, UserRole::Admin | UserRole::Manager) {
-//         return Err(AppError::InsufficientPrivileges {
-//             required_role: "Manager or Admin".to_string(),
-//             current_role: Some(format!("{user_role:?}")),
-//             error_id: uuid::Uuid::new_v4(),
-//         });
-//     }
-
-//     Ok(next.run(request).await)
-// }
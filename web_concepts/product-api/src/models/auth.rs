@@ -1,9 +1,10 @@
 use crate::entities::user::UserRole;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginRequest {
     #[validate(length(min = 1, message = "Username is required"))]
     pub username: String,
@@ -11,7 +12,7 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct RegisterRequest {
     #[validate(length(
         min = 3,
@@ -28,13 +29,36 @@ pub struct RegisterRequest {
     pub role: Option<UserRole>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
+    /// Short-lived access token, sent as `Authorization: Bearer <token>`
     pub token: String,
+    /// Seconds until `token` expires
+    pub expires_in: i64,
+    /// Single-use token for `POST /auth/refresh`; every refresh returns a new one
+    pub refresh_token: String,
     pub user: UserResponse,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RefreshTokenRequest {
+    #[validate(length(min = 1, message = "Refresh token is required"))]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct LogoutRequest {
+    /// Also revoke this refresh token and every token rotated from the same login
+    pub refresh_token: Option<String>,
+}
+
+/// Activate or deactivate a user account (admin only)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateUserStatusRequest {
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
@@ -49,17 +73,21 @@ pub struct Claims {
     pub username: String,
     pub role: UserRole, // Add role to JWT claims
     pub exp: usize,
+    /// Issued at, in seconds since the Unix epoch
+    pub iat: usize,
+    /// Unique token ID, used to revoke this token on logout
+    pub jti: String,
 }
 
 /// Request to update user role (admin only)
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateUserRoleRequest {
     #[validate(length(min = 1, message = "User ID is required"))]
     pub user_id: String,
 }
 
 /// Response for user role update
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UpdateUserRoleResponse {
     pub user_id: Uuid,
     pub username: String,
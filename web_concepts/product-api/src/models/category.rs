@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCategoryRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+    /// Derived from the name when left out
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Slug must be between 1 and 100 characters"
+    ))]
+    pub slug: Option<String>,
+    /// Leave out to create a root category
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateCategoryRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: Option<String>,
+    /// Slugs stay stable on rename unless changed explicitly
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Slug must be between 1 and 100 characters"
+    ))]
+    pub slug: Option<String>,
+    /// Move the category under another parent
+    pub parent_id: Option<Uuid>,
+    /// Move the category to the top level
+    #[serde(default)]
+    pub make_root: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryListResponse {
+    pub categories: Vec<CategoryResponse>,
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Body of `PUT /exchange-rates/:currency`
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpsertExchangeRateRequest {
+    /// Units of the currency per one unit of the base currency
+    #[validate(custom = "validate_rate")]
+    pub rate: Decimal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExchangeRateResponse {
+    pub currency: String,
+    pub rate: Decimal,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExchangeRateListResponse {
+    pub base_currency: String,
+    pub rates: Vec<ExchangeRateResponse>,
+}
+
+fn validate_rate(rate: &Decimal) -> Result<(), ValidationError> {
+    // Matches NUMERIC(18,8) in the exchange_rates table
+    if rate.is_sign_negative()
+        || rate.is_zero()
+        || rate.normalize().scale() > 8
+        || *rate >= Decimal::from(10_000_000_000i64)
+    {
+        let mut error = ValidationError::new("rate_range");
+        error.message =
+            Some("Rate must be positive, below 10^10 and have at most 8 decimal places".into());
+        return Err(error);
+    }
+    Ok(())
+}
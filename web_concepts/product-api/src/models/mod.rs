@@ -1,4 +1,16 @@
 pub mod auth;
+pub mod category;
+pub mod exchange_rate;
+pub mod price_schedule;
 pub mod product;
+pub mod product_image;
+pub mod product_variant;
+pub mod webhook;
 pub use auth::*;
+pub use category::*;
+pub use exchange_rate::*;
+pub use price_schedule::*;
 pub use product::*;
+pub use product_image::*;
+pub use product_variant::*;
+pub use webhook::*;
@@ -0,0 +1,62 @@
+use crate::entities::price_schedule::PriceScheduleStatus;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreatePriceScheduleRequest {
+    #[validate(custom = "validate_scheduled_price")]
+    pub price: Decimal,
+    pub effective_from: DateTime<Utc>,
+    /// Leave out for an open-ended price change
+    pub effective_to: Option<DateTime<Utc>>,
+    #[validate(length(max = 100, message = "Label must be at most 100 characters"))]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdatePriceScheduleRequest {
+    #[validate(custom = "validate_scheduled_price")]
+    pub price: Option<Decimal>,
+    pub effective_from: Option<DateTime<Utc>>,
+    /// `null` keeps the current end; use `clear_effective_to` to make the schedule open-ended
+    pub effective_to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub clear_effective_to: bool,
+    #[validate(length(max = 100, message = "Label must be at most 100 characters"))]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceScheduleResponse {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub price: Decimal,
+    pub effective_from: DateTime<Utc>,
+    pub effective_to: Option<DateTime<Utc>>,
+    pub label: Option<String>,
+    pub status: PriceScheduleStatus,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceScheduleListResponse {
+    pub product_id: Uuid,
+    pub base_price: Decimal,
+    /// Price the product sells at right now
+    pub effective_price: Decimal,
+    pub schedules: Vec<PriceScheduleResponse>,
+}
+
+fn validate_scheduled_price(price: &Decimal) -> Result<(), ValidationError> {
+    if price.is_sign_negative() {
+        let mut error = ValidationError::new("price_negative");
+        error.message = Some("Price must be non-negative".into());
+        return Err(error);
+    }
+    Ok(())
+}
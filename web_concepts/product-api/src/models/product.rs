@@ -1,9 +1,18 @@
+use crate::{
+    entities::{
+        product_history::HistoryAction, stock_movement::StockMovementReason,
+        stock_reservation::ReservationStatus,
+    },
+    models::ProductImageResponse,
+    utils::{is_valid_barcode, is_valid_currency_code},
+};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateProductRequest {
     #[validate(length(
         min = 1,
@@ -13,87 +22,435 @@ pub struct CreateProductRequest {
     pub name: String,
     pub description: Option<String>,
     pub price: Decimal,
+    /// ISO 4217 code of `price`; defaults to `BASE_CURRENCY`
+    #[validate(custom = "validate_currency")]
+    pub currency: Option<String>,
     #[validate(range(min = 0, message = "Quantity must be non-negative"))]
     pub quantity: i32,
+    /// Category name or slug; matched case-insensitively against the category tree
     pub category: Option<String>,
+    /// Alternative to `category`
+    pub category_id: Option<Uuid>,
+    /// Stock keeping unit, unique among live products
+    #[validate(custom = "validate_sku")]
+    pub sku: Option<String>,
+    /// EAN-13 or UPC-A, unique among live products
+    #[validate(custom = "validate_barcode")]
+    pub barcode: Option<String>,
     pub created_by: Option<Uuid>,
     pub updated_by: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateProductRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub price: Option<Decimal>,
+    #[validate(custom = "validate_currency")]
+    pub currency: Option<String>,
+    /// Always rejected: stock changes go through `POST /products/:id/stock/adjust`
+    /// so they are booked with a reason
+    #[validate(custom = "reject_quantity_update")]
     pub quantity: Option<i32>,
+    /// Category name or slug; matched case-insensitively against the category tree
     pub category: Option<String>,
+    /// Alternative to `category`
+    pub category_id: Option<Uuid>,
+    #[validate(custom = "validate_sku")]
+    pub sku: Option<String>,
+    #[validate(custom = "validate_barcode")]
+    pub barcode: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Validate)]
+/// Query of `GET /products/lookup`; exactly one identifier must be given
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProductLookupQuery {
+    #[validate(custom = "validate_sku")]
+    pub sku: Option<String>,
+    #[validate(custom = "validate_barcode")]
+    pub barcode: Option<String>,
+}
+
+fn validate_sku(sku: &str) -> Result<(), ValidationError> {
+    if sku.is_empty() || sku.len() > 64 || sku.chars().any(char::is_whitespace) {
+        let mut error = ValidationError::new("sku_format");
+        error.message = Some("SKU must be between 1 and 64 characters without whitespace".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_barcode(barcode: &str) -> Result<(), ValidationError> {
+    if !is_valid_barcode(barcode) {
+        let mut error = ValidationError::new("barcode_check_digit");
+        error.message = Some(
+            "Barcode must be a 13-digit EAN-13 or 12-digit UPC-A with a valid check digit".into(),
+        );
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn reject_quantity_update(_quantity: i32) -> Result<(), ValidationError> {
+    let mut error = ValidationError::new("quantity_read_only");
+    error.message = Some(
+        "Quantity can't be set by an update; use POST /products/:id/stock/adjust instead".into(),
+    );
+    Err(error)
+}
+
+fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if !is_valid_currency_code(currency) {
+        let mut error = ValidationError::new("currency_format");
+        error.message = Some("Currency must be a three-letter ISO 4217 code such as USD".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Body of `POST /products/:id/stock/adjust`
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct StockAdjustmentRequest {
+    /// Signed change: positive adds stock, negative removes it
+    pub quantity_delta: i32,
+    pub reason: StockMovementReason,
+    /// External document such as an order or delivery number
+    #[validate(length(max = 100, message = "Reference must be at most 100 characters"))]
+    pub reference: Option<String>,
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+/// Body of `POST /products/:id/reservations`
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateReservationRequest {
+    #[validate(range(min = 1, message = "Quantity must be at least 1"))]
+    pub quantity: i32,
+    /// Hold duration; defaults to `RESERVATION_TTL_SECONDS`
+    #[validate(range(
+        min = 1,
+        max = 86400,
+        message = "ttl_seconds must be between 1 and 86400"
+    ))]
+    pub ttl_seconds: Option<i64>,
+    /// Caller's identifier, e.g. a checkout or order ID
+    #[validate(length(max = 100, message = "Reference must be at most 100 characters"))]
+    pub reference: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ProductSearchRequest {
-    pub query: Option<String>,      // General text search
-    pub name: Option<String>,       // Search by name
-    pub category: Option<String>,   // Filter by category
-    pub min_price: Option<Decimal>, // Price range filter
+    pub query: Option<String>,       // General text search
+    pub search_mode: Option<String>, // "simple" (substring match, default) or "fulltext"
+    pub name: Option<String>,        // Search by name
+    pub category: Option<String>,    // Filter by category name or slug
+    pub min_price: Option<Decimal>,  // Price range filter
     pub max_price: Option<Decimal>,
     pub min_quantity: Option<i32>, // Quantity range filter
     pub max_quantity: Option<i32>,
-    pub in_stock: Option<bool>,     // Filter by stock availability
-    pub sort_by: Option<String>,    // Sort field (name, price, created_at)
+    pub in_stock: Option<bool>, // Filter by available-to-sell stock
+    pub sku: Option<String>,    // Products with a variant of this SKU
+    pub variant_options: Option<String>, // Products with a variant matching all options, e.g. "size:M,colour:red"
+    pub sort_by: Option<String>, // Sort field (name, price, quantity, created_at, relevance)
     pub sort_order: Option<String>, // Sort order (asc, desc)
     pub page: Option<u64>,
     pub per_page: Option<u64>,
+    pub cursor: Option<String>, // Opaque keyset cursor from a previous `next_cursor`
+    pub include_total: Option<bool>, // Run the COUNT query (defaults to true for offset paging)
+    pub include_facets: Option<bool>, // Compute facet counts over the filtered result set
+    pub price_buckets: Option<String>, // Price histogram edges, e.g. "0,50,100,500"
+    #[validate(custom = "validate_currency")]
+    pub currency: Option<String>, // Report prices in this currency; price filters and buckets default to BASE_CURRENCY
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProductResponse {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    /// Base price, used outside of any price schedule
     pub price: Decimal,
+    /// Price the product sells at right now, taking active price schedules into account
+    pub effective_price: Decimal,
+    /// Currency of both prices: the product's own, or the one requested with `currency=`
+    pub currency: String,
     pub quantity: i32,
+    /// Units held by active reservations
+    pub reserved_quantity: i32,
+    /// `quantity` minus active reservations
+    pub available_quantity: i32,
     pub category: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    /// Images in display order
+    pub images: Vec<ProductImageResponse>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub version: i32,
+    /// Only present for products in the trash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProductListResponse {
     pub products: Vec<ProductResponse>,
-    pub total: u64,
-    pub page: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
     pub per_page: u64,
+    pub next_cursor: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProductSearchResponse {
     pub products: Vec<ProductResponse>,
-    pub total: u64,
-    pub page: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
     pub per_page: u64,
+    pub next_cursor: Option<String>,
+    /// Full-text rank and highlighted snippet per product (fulltext mode only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<ProductSearchHighlight>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<ProductSearchFacets>,
     pub filters_applied: ProductSearchFilters,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProductSearchHighlight {
+    pub product_id: Uuid,
+    pub rank: f32,
+    /// Matching fragment of name/description with hits wrapped in `<mark>` tags
+    pub snippet: Option<String>,
+}
+
+/// Facet counts computed over the same filters as the search itself
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProductSearchFacets {
+    pub categories: Vec<CategoryFacet>,
+    pub price_buckets: Vec<PriceBucketFacet>,
+    pub stock: StockFacet,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CategoryFacet {
+    /// `None` for uncategorized products
+    pub category_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub count: u64,
+}
+
+/// Products with `min <= price < max`; an open end is `None`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PriceBucketFacet {
+    pub min: Option<Decimal>,
+    pub max: Option<Decimal>,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StockFacet {
+    pub in_stock: u64,
+    pub out_of_stock: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProductSearchFilters {
     pub query: Option<String>,
+    pub search_mode: String,
     pub category: Option<String>,
     pub price_range: Option<(Decimal, Decimal)>,
     pub quantity_range: Option<(i32, i32)>,
     pub in_stock: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_options: Option<String>,
+    /// Currency of `price_range`: the requested one, or `BASE_CURRENCY`
+    pub currency: String,
+}
+
+/// One entry of a product's audit trail
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductHistoryEntry {
+    pub id: Uuid,
+    pub action: HistoryAction,
+    pub changed_by: Option<Uuid>,
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+    /// Field name -> `{"old": .., "new": ..}`
+    pub changes: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductHistoryResponse {
+    pub product_id: Uuid,
+    pub entries: Vec<ProductHistoryEntry>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StockMovementResponse {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub reason: StockMovementReason,
+    pub quantity_delta: i32,
+    pub quantity_after: i32,
+    pub reference: Option<String>,
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StockAdjustmentResponse {
+    pub product: ProductResponse,
+    pub movement: StockMovementResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReservationResponse {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: i32,
+    pub status: ReservationStatus,
+    pub reference: Option<String>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Outcome of `POST /reservations/:id/confirm`: the sale booked for the held units
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReservationConfirmationResponse {
+    pub reservation: ReservationResponse,
+    pub product: ProductResponse,
+    pub movement: StockMovementResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StockMovementListResponse {
+    pub product_id: Uuid,
+    pub movements: Vec<StockMovementResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+/// One entry of `PATCH /products/batch`: a product id plus the fields of a
+/// single-product update
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchUpdateItem {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub changes: UpdateProductRequest,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchUpdateProductsRequest {
+    pub items: Vec<BatchUpdateItem>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchDeleteProductsRequest {
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Updated,
+    Deleted,
+    Failed,
+    /// Succeeded, then undone because a later item failed an atomic batch
+    RolledBack,
+    /// Not attempted because an atomic batch had already failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchItemError {
+    /// Registered error code, as in error responses
+    pub code: String,
+    /// HTTP status the item would have failed with on its own
+    pub status: u16,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchItemResult {
+    pub id: Uuid,
+    pub status: BatchItemStatus,
+    /// The updated product (committed batch updates only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<ProductResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchItemError>,
+}
+
+/// Outcome of `PATCH /products/batch` and `POST /products/batch-delete`,
+/// with one result per item in request order
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchWriteReport {
+    /// All-or-nothing mode: any failing item rolls back the whole batch
+    pub atomic: bool,
+    pub committed: bool,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+/// Outcome of `POST /products/import`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductImportReport {
+    pub format: String,
+    /// All-or-nothing mode: any failing row rolls back the whole import
+    pub atomic: bool,
+    pub committed: bool,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub failed: usize,
+    pub errors: Vec<ProductImportRowError>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProductImportRowError {
+    /// 1-based line in the uploaded file
+    pub line: usize,
+    pub message: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct ProductStatsResponse {
+    /// Currency of all amounts: the requested one, or `BASE_CURRENCY`
+    pub currency: String,
     pub total_products: u64,
+    /// Stock value of products and their variants
     pub total_value: Decimal,
     pub avg_price: Option<Decimal>,
+    pub total_variants: u64,
+    /// Stock value of variants alone, at their effective price
+    pub variant_value: Decimal,
     pub categories: Vec<CategoryStats>,
 }
 
-#[derive(Debug, Serialize)]
+/// Totals for a category, rolled up over all of its descendants
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct CategoryStats {
+    pub category_id: Uuid,
     pub category: String,
+    pub slug: String,
+    pub parent_id: Option<Uuid>,
     pub count: u64,
     pub total_value: Decimal,
 }
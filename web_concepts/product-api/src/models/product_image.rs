@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Upper bound on images per product
+pub const MAX_IMAGES_PER_PRODUCT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProductImageResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub position: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductImageListResponse {
+    pub product_id: Uuid,
+    pub images: Vec<ProductImageResponse>,
+}
+
+/// Body of `PUT /products/:id/images/order`: every image of the product, in the new order
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReorderImagesRequest {
+    #[validate(length(min = 1, message = "image_ids must not be empty"))]
+    pub image_ids: Vec<Uuid>,
+}
+
+/// Multipart body of `POST /products/:id/images`; every part is one image
+#[derive(ToSchema)]
+pub struct ImageUploadForm {
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub files: Vec<Vec<u8>>,
+}
@@ -0,0 +1,93 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Variants are distinguished by a handful of options such as size and colour
+pub const MAX_VARIANT_OPTIONS: usize = 10;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateVariantRequest {
+    #[validate(length(min = 1, max = 64, message = "SKU must be between 1 and 64 characters"))]
+    pub sku: String,
+    #[validate(custom = "validate_options")]
+    pub options: BTreeMap<String, String>,
+    /// Leave out to sell at the product's price
+    #[validate(custom = "validate_price_override")]
+    pub price: Option<Decimal>,
+    #[validate(range(min = 0, message = "Quantity must be non-negative"))]
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateVariantRequest {
+    #[validate(length(min = 1, max = 64, message = "SKU must be between 1 and 64 characters"))]
+    pub sku: Option<String>,
+    #[validate(custom = "validate_options")]
+    pub options: Option<BTreeMap<String, String>>,
+    /// `null` keeps the current override; use `clear_price` to fall back to the product's price
+    #[validate(custom = "validate_price_override")]
+    pub price: Option<Decimal>,
+    #[serde(default)]
+    pub clear_price: bool,
+    #[validate(range(min = 0, message = "Quantity must be non-negative"))]
+    pub quantity: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VariantResponse {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    pub options: BTreeMap<String, String>,
+    /// Price override, `None` when the variant sells at the product's price
+    pub price: Option<Decimal>,
+    pub effective_price: Decimal,
+    pub quantity: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VariantListResponse {
+    pub product_id: Uuid,
+    pub variants: Vec<VariantResponse>,
+}
+
+fn validate_options(options: &BTreeMap<String, String>) -> Result<(), ValidationError> {
+    if options.is_empty() || options.len() > MAX_VARIANT_OPTIONS {
+        let mut error = ValidationError::new("options_count");
+        error.message =
+            Some(format!("A variant needs between 1 and {MAX_VARIANT_OPTIONS} options").into());
+        return Err(error);
+    }
+    if options
+        .iter()
+        .any(|(name, value)| name.trim().is_empty() || value.trim().is_empty())
+    {
+        let mut error = ValidationError::new("options_blank");
+        error.message = Some("Option names and values must not be blank".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_price_override(price: &Decimal) -> Result<(), ValidationError> {
+    if price.is_sign_negative() {
+        let mut error = ValidationError::new("price_negative");
+        error.message = Some("Price must be non-negative".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Canonical form of variant options: trimmed, lowercase option names and
+/// trimmed values, so `{" Size ": "M"}` and `{"size": "M"}` are the same variant
+pub fn normalize_options(options: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    options
+        .into_iter()
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect()
+}
@@ -0,0 +1,109 @@
+use crate::entities::{webhook_delivery::DeliveryStatus, webhook_event::WebhookEventType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookRequest {
+    #[validate(
+        url(message = "URL must be a valid URL"),
+        custom = "validate_webhook_url"
+    )]
+    pub url: String,
+    /// Key for the HMAC-SHA256 signature; only the admin and the receiver know it
+    #[validate(length(
+        min = 16,
+        max = 255,
+        message = "Secret must be between 16 and 255 characters"
+    ))]
+    pub secret: String,
+    #[validate(length(min = 1, message = "Subscribe to at least one event type"))]
+    pub event_types: Vec<WebhookEventType>,
+    /// Defaults to 10, like `/products/low-stock`
+    #[validate(range(min = 0, message = "Low stock threshold must be non-negative"))]
+    pub low_stock_threshold: Option<i32>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateWebhookRequest {
+    #[validate(
+        url(message = "URL must be a valid URL"),
+        custom = "validate_webhook_url"
+    )]
+    pub url: Option<String>,
+    /// Rotate the signing secret
+    #[validate(length(
+        min = 16,
+        max = 255,
+        message = "Secret must be between 16 and 255 characters"
+    ))]
+    pub secret: Option<String>,
+    #[validate(length(min = 1, message = "Subscribe to at least one event type"))]
+    pub event_types: Option<Vec<WebhookEventType>>,
+    #[validate(range(min = 0, message = "Low stock threshold must be non-negative"))]
+    pub low_stock_threshold: Option<i32>,
+    #[validate(length(max = 255, message = "Description must be at most 255 characters"))]
+    pub description: Option<String>,
+    /// Paused subscriptions get no new events; queued deliveries wait until resumed
+    pub is_active: Option<bool>,
+}
+
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return Ok(());
+    }
+    let mut error = ValidationError::new("webhook_url");
+    error.message = Some("URL must use http or https".into());
+    Err(error)
+}
+
+/// A subscription without its secret
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub low_stock_threshold: i32,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: Option<WebhookEventType>,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the next attempt is due (pending deliveries only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i16>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// The body that was (or will be) sent
+    pub payload: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryListResponse {
+    pub subscription_id: Uuid,
+    pub deliveries: Vec<WebhookDeliveryResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+}
@@ -0,0 +1,244 @@
+use crate::{
+    app,
+    cache::CacheMetrics,
+    entities::{
+        price_schedule::PriceScheduleStatus, product_history::HistoryAction,
+        stock_movement::StockMovementReason, stock_reservation::ReservationStatus, user::UserRole,
+        webhook_delivery::DeliveryStatus, webhook_event::WebhookEventType,
+    },
+    error::{DebugInfo, ErrorInfo, ErrorResponse},
+    handlers::{
+        auth, category, exchange_rate, metrics, price_schedule, product, product_image,
+        product_variant, webhook,
+    },
+    models::*,
+};
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Content, Ref, RefOr, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+
+/// OpenAPI document of the whole API, served at `/api-docs/openapi.json`
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Product API"),
+    paths(
+        app::health_check,
+        auth::login,
+        auth::register,
+        auth::refresh,
+        auth::logout,
+        auth::update_user_status,
+        product::get_all_products,
+        product::create_product,
+        product::get_product,
+        product::update_product,
+        product::delete_product,
+        product::lookup_product,
+        product::search_products,
+        product::get_products_by_category,
+        product::get_products_by_price_range,
+        product::get_low_stock_products,
+        product::get_similar_products,
+        product::get_product_stats,
+        product::get_trending_categories,
+        product::export_products,
+        product::import_products,
+        product::batch_update_products,
+        product::batch_delete_products,
+        product::get_product_history,
+        product::adjust_stock,
+        product::get_stock_movements,
+        product::create_reservation,
+        product::get_reservation,
+        product::confirm_reservation,
+        product::release_reservation,
+        product::get_trash,
+        product::restore_product,
+        product::purge_product,
+        category::list_categories,
+        category::create_category,
+        category::get_category,
+        category::update_category,
+        category::delete_category,
+        product_variant::list_variants,
+        product_variant::create_variant,
+        product_variant::get_variant,
+        product_variant::update_variant,
+        product_variant::delete_variant,
+        product_image::list_images,
+        product_image::upload_images,
+        product_image::reorder_images,
+        product_image::delete_image,
+        price_schedule::list_price_schedules,
+        price_schedule::create_price_schedule,
+        price_schedule::update_price_schedule,
+        price_schedule::delete_price_schedule,
+        exchange_rate::list_exchange_rates,
+        exchange_rate::upsert_exchange_rate,
+        exchange_rate::delete_exchange_rate,
+        webhook::list_webhooks,
+        webhook::create_webhook,
+        webhook::get_webhook,
+        webhook::update_webhook,
+        webhook::delete_webhook,
+        webhook::list_deliveries,
+        metrics::cache_metrics,
+    ),
+    components(schemas(
+        ErrorResponse,
+        ErrorInfo,
+        DebugInfo,
+        LoginRequest,
+        RegisterRequest,
+        AuthResponse,
+        RefreshTokenRequest,
+        LogoutRequest,
+        UpdateUserStatusRequest,
+        UserResponse,
+        UserRole,
+        CreateProductRequest,
+        UpdateProductRequest,
+        ProductResponse,
+        ProductListResponse,
+        ProductSearchResponse,
+        ProductSearchHighlight,
+        ProductSearchFacets,
+        CategoryFacet,
+        PriceBucketFacet,
+        StockFacet,
+        ProductSearchFilters,
+        ProductStatsResponse,
+        CategoryStats,
+        ProductHistoryEntry,
+        ProductHistoryResponse,
+        HistoryAction,
+        StockAdjustmentRequest,
+        StockAdjustmentResponse,
+        StockMovementResponse,
+        StockMovementListResponse,
+        StockMovementReason,
+        CreateReservationRequest,
+        ReservationResponse,
+        ReservationConfirmationResponse,
+        ReservationStatus,
+        BatchUpdateItem,
+        BatchUpdateProductsRequest,
+        BatchDeleteProductsRequest,
+        BatchItemStatus,
+        BatchItemError,
+        BatchItemResult,
+        BatchWriteReport,
+        ProductImportReport,
+        ProductImportRowError,
+        CreateCategoryRequest,
+        UpdateCategoryRequest,
+        CategoryResponse,
+        CategoryListResponse,
+        CreateVariantRequest,
+        UpdateVariantRequest,
+        VariantResponse,
+        VariantListResponse,
+        ProductImageResponse,
+        ProductImageListResponse,
+        ReorderImagesRequest,
+        ImageUploadForm,
+        CreatePriceScheduleRequest,
+        UpdatePriceScheduleRequest,
+        PriceScheduleResponse,
+        PriceScheduleListResponse,
+        PriceScheduleStatus,
+        UpsertExchangeRateRequest,
+        ExchangeRateResponse,
+        ExchangeRateListResponse,
+        CreateWebhookRequest,
+        UpdateWebhookRequest,
+        WebhookResponse,
+        WebhookListResponse,
+        WebhookDeliveryResponse,
+        WebhookDeliveryListResponse,
+        WebhookEventType,
+        DeliveryStatus,
+        CacheMetrics,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "health", description = "Service status"),
+        (name = "auth", description = "Login, registration and token refresh"),
+        (name = "users", description = "User administration"),
+        (name = "products", description = "Product catalogue, search, import and export"),
+        (name = "stock", description = "Stock movements and reservations"),
+        (name = "trash", description = "Soft-deleted products"),
+        (name = "categories", description = "Category tree"),
+        (name = "variants", description = "Product variants"),
+        (name = "images", description = "Product images"),
+        (name = "price-schedules", description = "Scheduled price changes"),
+        (name = "exchange-rates", description = "Currency exchange rates"),
+        (name = "webhooks", description = "Webhook subscriptions and deliveries"),
+        (name = "metrics", description = "Operational metrics"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the JWT bearer scheme and the error responses every protected
+/// operation can return, so the handlers only document their own errors
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+
+        let error_response = |description: &str| {
+            ResponseBuilder::new()
+                .description(description)
+                .content(
+                    "application/json",
+                    Content::new(Ref::from_schema_name("ErrorResponse")),
+                )
+                .build()
+        };
+        components.responses.insert(
+            "Unauthorized".to_string(),
+            RefOr::T(error_response("Missing, invalid or expired token")),
+        );
+        components.responses.insert(
+            "Forbidden".to_string(),
+            RefOr::T(error_response("The caller's role lacks the permission")),
+        );
+        // Sent with Retry-After and the bucket's X-RateLimit-* headers
+        components.responses.insert(
+            "TooManyRequests".to_string(),
+            RefOr::T(error_response("Rate limit exceeded")),
+        );
+
+        for path_item in openapi.paths.paths.values_mut() {
+            for operation in path_item.operations.values_mut() {
+                let responses = &mut operation.responses.responses;
+                if operation.security.is_some() {
+                    responses
+                        .entry("401".to_string())
+                        .or_insert_with(|| RefOr::Ref(Ref::from_response_name("Unauthorized")));
+                    responses
+                        .entry("403".to_string())
+                        .or_insert_with(|| RefOr::Ref(Ref::from_response_name("Forbidden")));
+                }
+                // Public routes are limited per IP, protected ones per user
+                responses
+                    .entry("429".to_string())
+                    .or_insert_with(|| RefOr::Ref(Ref::from_response_name("TooManyRequests")));
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    error::AppError,
+    rate_limit::store::{gcra, Quota, RateLimitDecision, RateLimitStore},
+};
+use async_trait::async_trait;
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Per-process GCRA store. Keys are spread over independently locked shards
+/// so concurrent requests for different clients rarely contend, and each key
+/// only holds its theoretical arrival time.
+pub struct InMemoryRateLimitStore {
+    shards: Vec<Mutex<HashMap<String, u64>>>,
+    hasher: RandomState,
+    started: Instant,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+            started: Instant::now(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn shard(&self, key: &str) -> MutexGuard<'_, HashMap<String, u64>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        // An entry is a single number, so a poisoned shard is still consistent
+        self.shards[index].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Drop keys whose bucket has refilled completely; they behave exactly like
+    /// keys that were never seen. Returns how many were dropped.
+    pub fn evict_expired(&self) -> usize {
+        let now = self.now_ms();
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut entries = shard.lock().unwrap_or_else(|e| e.into_inner());
+                let before = entries.len();
+                entries.retain(|_, tat| *tat > now);
+                before - entries.len()
+            })
+            .sum()
+    }
+
+    /// Number of keys currently tracked
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap_or_else(|e| e.into_inner()).len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn check(&self, key: &str, quota: Quota) -> Result<RateLimitDecision, AppError> {
+        let now = self.now_ms();
+        let mut entries = self.shard(key);
+
+        let (decision, new_tat) = gcra(now, entries.get(key).copied(), quota);
+        if let Some(new_tat) = new_tat {
+            entries.insert(key.to_string(), new_tat);
+        }
+
+        Ok(decision)
+    }
+}
+
+/// Evict refilled buckets every `interval`, so memory tracks the number of
+/// recently active clients rather than every client ever seen
+pub fn spawn_rate_limit_eviction(
+    store: Arc<InMemoryRateLimitStore>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            let evicted = store.evict_expired();
+            if evicted > 0 {
+                tracing::debug!(evicted, "Evicted idle rate limit buckets");
+            }
+        }
+    })
+}
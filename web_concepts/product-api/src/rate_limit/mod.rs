@@ -0,0 +1,9 @@
+pub mod memory;
+pub mod policy;
+pub mod redis_store;
+pub mod store;
+
+pub use memory::*;
+pub use policy::*;
+pub use redis_store::*;
+pub use store::*;
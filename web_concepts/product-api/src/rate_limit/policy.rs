@@ -0,0 +1,171 @@
+use crate::{entities::user::UserRole, rate_limit::store::Quota};
+use std::time::Duration;
+
+/// The route groups `create_app` registers, each limited with its own bucket
+/// so a burst in one group doesn't lock the caller out of the others
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteGroup {
+    Read,
+    Create,
+    Update,
+    Delete,
+    /// Trash, exchange rates, metrics and webhooks
+    Admin,
+}
+
+impl RouteGroup {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouteGroup::Read => "read",
+            RouteGroup::Create => "create",
+            RouteGroup::Update => "update",
+            RouteGroup::Delete => "delete",
+            RouteGroup::Admin => "admin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "read" => Some(RouteGroup::Read),
+            "create" => Some(RouteGroup::Create),
+            "update" => Some(RouteGroup::Update),
+            "delete" => Some(RouteGroup::Delete),
+            "admin" => Some(RouteGroup::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// One rule of `RATE_LIMIT_POLICIES`; `None` matches any group or role
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    pub group: Option<RouteGroup>,
+    pub role: Option<UserRole>,
+    pub quota: Quota,
+}
+
+impl RateLimitPolicy {
+    /// Parse a comma separated list of `<group>:<role>=<limit>/<window>[+<burst>]`
+    /// rules, e.g. `read:*=300/1m+50,create:user=20/1m,*:admin=1000/1m`.
+    /// Groups are read, create, update, delete and admin; roles are user,
+    /// manager and admin; `*` matches any. Windows are a number of seconds,
+    /// minutes or hours (`30s`, `1m`, `1h`). The burst defaults to the limit.
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(Self::parse)
+            .collect()
+    }
+
+    fn parse(rule: &str) -> Result<Self, String> {
+        let (scope, quota) = rule
+            .split_once('=')
+            .ok_or_else(|| format!("'{rule}' is missing '='"))?;
+        let (group, role) = scope
+            .split_once(':')
+            .ok_or_else(|| format!("'{scope}' must be <group>:<role>"))?;
+
+        let group = match group.trim() {
+            "*" => None,
+            name => Some(
+                RouteGroup::from_name(name)
+                    .ok_or_else(|| format!("Unknown route group '{name}'"))?,
+            ),
+        };
+        let role = match role.trim() {
+            "*" => None,
+            "user" => Some(UserRole::User),
+            "manager" => Some(UserRole::Manager),
+            "admin" => Some(UserRole::Admin),
+            name => return Err(format!("Unknown role '{name}'")),
+        };
+
+        Ok(Self {
+            group,
+            role,
+            quota: parse_quota(quota.trim())?,
+        })
+    }
+
+    /// Higher is more specific: an exact group beats an exact role, which
+    /// beats a wildcard
+    fn specificity(&self) -> u8 {
+        u8::from(self.group.is_some()) * 2 + u8::from(self.role.is_some())
+    }
+
+    fn matches(&self, group: RouteGroup, role: &UserRole) -> bool {
+        self.group.is_none_or(|g| g == group) && self.role.as_ref().is_none_or(|r| r == role)
+    }
+}
+
+fn parse_quota(quota: &str) -> Result<Quota, String> {
+    let (rate, burst) = match quota.split_once('+') {
+        Some((rate, burst)) => (rate, Some(burst)),
+        None => (quota, None),
+    };
+    let (limit, window) = rate
+        .split_once('/')
+        .ok_or_else(|| format!("'{rate}' must be <limit>/<window>"))?;
+
+    let limit: u32 = limit
+        .parse()
+        .map_err(|_| format!("Invalid limit '{limit}'"))?;
+    let burst: u32 = match burst {
+        Some(burst) => burst
+            .parse()
+            .map_err(|_| format!("Invalid burst '{burst}'"))?,
+        None => limit,
+    };
+    if limit == 0 || burst == 0 {
+        return Err(format!("'{quota}' must allow at least one request"));
+    }
+
+    let unit = match window.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 3600,
+        _ => return Err(format!("Window '{window}' must end in s, m or h")),
+    };
+    let count = &window[..window.len() - 1];
+    let count: u64 = if count.is_empty() {
+        1
+    } else {
+        count
+            .parse()
+            .map_err(|_| format!("Invalid window '{window}'"))?
+    };
+    if count == 0 {
+        return Err(format!("Window '{window}' must not be empty"));
+    }
+
+    Ok(Quota {
+        limit,
+        period: Duration::from_secs(count * unit),
+        burst,
+    })
+}
+
+/// Resolves the quota of an authenticated request from its route group and
+/// the caller's role. Requests no policy matches get `default`.
+#[derive(Debug, Clone)]
+pub struct RateLimitPolicies {
+    default: Quota,
+    policies: Vec<RateLimitPolicy>,
+}
+
+impl RateLimitPolicies {
+    pub fn new(default: Quota, policies: Vec<RateLimitPolicy>) -> Self {
+        Self { default, policies }
+    }
+
+    /// The most specific matching policy; among equally specific ones the
+    /// last wins, so later rules can override earlier ones
+    pub fn quota_for(&self, group: RouteGroup, role: &UserRole) -> Quota {
+        self.policies
+            .iter()
+            .filter(|policy| policy.matches(group, role))
+            .max_by_key(|policy| policy.specificity())
+            .map_or(self.default, |policy| policy.quota)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    error::AppError,
+    rate_limit::store::{Quota, RateLimitDecision, RateLimitStore},
+};
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, Script};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The same GCRA step as `store::gcra`, run atomically inside Redis. It uses
+/// the server clock so replicas with skewed clocks still agree, and each key
+/// expires as soon as its bucket is full again.
+const GCRA_SCRIPT: &str = r"
+local emission = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local tat = tonumber(redis.call('GET', KEYS[1]) or now)
+if tat < now then
+    tat = now
+end
+local new_tat = tat + emission
+local allow_at = new_tat - burst
+if now < allow_at then
+    return {0, 0, allow_at - now, tat - now}
+end
+redis.call('SET', KEYS[1], new_tat, 'PX', new_tat - now)
+return {1, math.floor((burst - (new_tat - now)) / emission), 0, new_tat - now}
+";
+
+/// Store shared by every replica through Redis (or anything speaking its
+/// protocol and Lua scripting, such as Valkey or KeyDB)
+pub struct RedisRateLimitStore {
+    connection: ConnectionManager,
+    key_prefix: String,
+    script: Script,
+}
+
+impl RedisRateLimitStore {
+    /// Connect to `url`, e.g. `redis://127.0.0.1:6379`. Keys are stored as
+    /// `<key_prefix><key>`. The connection reconnects by itself after errors.
+    pub async fn connect(url: &str, key_prefix: &str) -> Result<Self, AppError> {
+        let client = redis::Client::open(url).map_err(|e| redis_error("connect", e))?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .map_err(|e| redis_error("connect", e))?;
+
+        Ok(Self {
+            connection,
+            key_prefix: key_prefix.to_string(),
+            script: Script::new(GCRA_SCRIPT),
+        })
+    }
+}
+
+fn redis_error(operation: &str, error: redis::RedisError) -> AppError {
+    tracing::warn!(error = %error, operation, "Redis rate limit store failed");
+    AppError::ExternalServiceError {
+        service: "redis".to_string(),
+        operation: operation.to_string(),
+        status_code: None,
+        error_id: Uuid::new_v4(),
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn check(&self, key: &str, quota: Quota) -> Result<RateLimitDecision, AppError> {
+        // ConnectionManager is a cheap handle onto one multiplexed connection
+        let mut connection = self.connection.clone();
+
+        let (allowed, remaining, retry_after_ms, reset_after_ms): (i64, i64, i64, i64) = self
+            .script
+            .key(format!("{}{key}", self.key_prefix))
+            .arg(quota.emission_interval_ms())
+            .arg(quota.burst_ms())
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| redis_error("rate_limit_check", e))?;
+
+        Ok(RateLimitDecision {
+            allowed: allowed == 1,
+            limit: quota.burst,
+            remaining: remaining.max(0) as u32,
+            retry_after: Duration::from_millis(retry_after_ms.max(0) as u64),
+            reset_after: Duration::from_millis(reset_after_ms.max(0) as u64),
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// At most `limit` requests per `period`. Enforced with GCRA: requests are
+/// spaced `period / limit` apart on average, and a full bucket allows a burst
+/// of `burst` requests at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub limit: u32,
+    pub period: Duration,
+    pub burst: u32,
+}
+
+impl Quota {
+    pub fn per_minute(limit: u32) -> Self {
+        Self {
+            limit,
+            period: Duration::from_secs(60),
+            burst: limit,
+        }
+    }
+
+    /// Milliseconds between two requests at the sustained rate
+    pub fn emission_interval_ms(&self) -> u64 {
+        (self.period.as_millis() as u64 / u64::from(self.limit.max(1))).max(1)
+    }
+
+    /// Milliseconds a full bucket lasts, i.e. `burst` emission intervals
+    pub fn burst_ms(&self) -> u64 {
+        self.emission_interval_ms() * u64::from(self.burst.max(1))
+    }
+}
+
+/// Outcome of counting one request against a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Size of the bucket, i.e. the quota's burst
+    pub limit: u32,
+    /// Requests that would still be allowed right now
+    pub remaining: u32,
+    /// Wait before the next request is allowed; zero when this one was
+    pub retry_after: Duration,
+    /// Wait until the bucket is full again
+    pub reset_after: Duration,
+}
+
+impl RateLimitDecision {
+    /// `Retry-After` value: whole seconds, rounded up so clients don't retry early
+    pub fn retry_after_seconds(&self) -> u64 {
+        ceil_seconds(self.retry_after)
+    }
+
+    /// `X-RateLimit-Reset` value: whole seconds until the bucket is full again
+    pub fn reset_seconds(&self) -> u64 {
+        ceil_seconds(self.reset_after)
+    }
+}
+
+fn ceil_seconds(duration: Duration) -> u64 {
+    duration.as_millis().div_ceil(1000) as u64
+}
+
+/// GCRA step on millisecond timestamps. `tat` is the key's stored theoretical
+/// arrival time, if any. Returns the decision and, when the request was
+/// allowed, the new arrival time to store; the key can expire once `now`
+/// passes it.
+pub fn gcra(now: u64, tat: Option<u64>, quota: Quota) -> (RateLimitDecision, Option<u64>) {
+    let emission = quota.emission_interval_ms();
+    let burst = quota.burst_ms();
+
+    let tat = tat.unwrap_or(now).max(now);
+    let new_tat = tat + emission;
+    let allow_at = new_tat.saturating_sub(burst);
+
+    if now < allow_at {
+        let decision = RateLimitDecision {
+            allowed: false,
+            limit: quota.burst,
+            remaining: 0,
+            retry_after: Duration::from_millis(allow_at - now),
+            reset_after: Duration::from_millis(tat - now),
+        };
+        return (decision, None);
+    }
+
+    let decision = RateLimitDecision {
+        allowed: true,
+        limit: quota.burst,
+        remaining: ((burst - (new_tat - now)) / emission) as u32,
+        retry_after: Duration::ZERO,
+        reset_after: Duration::from_millis(new_tat - now),
+    };
+    (decision, Some(new_tat))
+}
+
+/// Where rate limit state lives. The in-memory store limits each replica on
+/// its own; the Redis store shares one budget across all of them.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Count one request against `key` and decide whether it may proceed
+    async fn check(&self, key: &str, quota: Quota) -> Result<RateLimitDecision, AppError>;
+}
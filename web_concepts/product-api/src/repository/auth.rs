@@ -1,9 +1,17 @@
 use crate::{
-    entities::{prelude::*, user, user::UserRole},
+    entities::{
+        prelude::*, refresh_token, revoked_access_token, user, user::UserRole,
+        user_token_revocation,
+    },
     error::AppError,
 };
 use async_trait::async_trait;
-use sea_orm::{prelude::*, ActiveModelTrait, Set};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait, Set, TransactionTrait,
+};
 use uuid::Uuid;
 
 #[async_trait]
@@ -21,6 +29,58 @@ pub trait AuthRepositoryTrait {
         password_hash: String,
         role: UserRole,
     ) -> Result<user::Model, AppError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<user::Model>, AppError>;
+    /// `None` if the user doesn't exist
+    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<Option<user::Model>, AppError>;
+
+    async fn create_refresh_token(&self, token: refresh_token::Model) -> Result<(), AppError>;
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<refresh_token::Model>, AppError>;
+    /// Revoke `current` and store `next` as its replacement, atomically.
+    /// `false` if `current` was already revoked, i.e. another request used it
+    /// first; nothing is stored then.
+    async fn rotate_refresh_token(
+        &self,
+        current: Uuid,
+        next: refresh_token::Model,
+    ) -> Result<bool, AppError>;
+    /// Revoke every live token of a family; returns how many were revoked
+    async fn revoke_refresh_token_family(&self, family_id: Uuid) -> Result<u64, AppError>;
+    /// Revoke every live token of a user; returns how many were revoked
+    async fn revoke_user_refresh_tokens(&self, user_id: Uuid) -> Result<u64, AppError>;
+    /// Delete tokens past their expiry; returns how many were deleted
+    async fn purge_expired_refresh_tokens(&self) -> Result<u64, AppError>;
+
+    /// Revoke one access token until it expires at `expires_at`
+    async fn revoke_access_token(
+        &self,
+        jti: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError>;
+    /// Revoke every access token of a user issued at or before `revoked_at`,
+    /// replacing an earlier revocation of the same user
+    async fn revoke_user_access_tokens(
+        &self,
+        user_id: Uuid,
+        revoked_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError>;
+    /// Lift a user's revocation, so tokens issued from now on work again
+    async fn clear_user_access_token_revocation(&self, user_id: Uuid) -> Result<(), AppError>;
+    /// Access token and user revocations that still cover unexpired tokens
+    async fn find_access_token_revocations(
+        &self,
+    ) -> Result<
+        (
+            Vec<revoked_access_token::Model>,
+            Vec<user_token_revocation::Model>,
+        ),
+        AppError,
+    >;
+    /// Delete revocations whose tokens have all expired; returns how many
+    async fn purge_expired_access_token_revocations(&self) -> Result<u64, AppError>;
 }
 
 #[derive(Clone)]
@@ -87,4 +147,181 @@ impl AuthRepositoryTrait for AuthRepository {
         let user = new_user.insert(&self.db).await?;
         Ok(user)
     }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<user::Model>, AppError> {
+        Ok(User::find_by_id(id).one(&self.db).await?)
+    }
+
+    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<Option<user::Model>, AppError> {
+        let Some(user) = User::find_by_id(id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        let mut active_user: user::ActiveModel = user.into();
+        active_user.is_active = Set(is_active);
+        active_user.updated_at = Set(Utc::now());
+        Ok(Some(active_user.update(&self.db).await?))
+    }
+
+    async fn create_refresh_token(&self, token: refresh_token::Model) -> Result<(), AppError> {
+        refresh_token::ActiveModel::from(token)
+            .insert(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<refresh_token::Model>, AppError> {
+        let token = RefreshToken::find()
+            .filter(refresh_token::Column::TokenHash.eq(token_hash))
+            .one(&self.db)
+            .await?;
+        Ok(token)
+    }
+
+    //  **Transactions** - The conditional revoke decides which of two concurrent
+    //  refreshes with the same token wins; the loser inserts nothing
+    async fn rotate_refresh_token(
+        &self,
+        current: Uuid,
+        next: refresh_token::Model,
+    ) -> Result<bool, AppError> {
+        let txn = self.db.begin().await?;
+
+        let revoked = RefreshToken::update_many()
+            .col_expr(refresh_token::Column::RevokedAt, Expr::value(Utc::now()))
+            .col_expr(refresh_token::Column::ReplacedBy, Expr::value(next.id))
+            .filter(refresh_token::Column::Id.eq(current))
+            .filter(refresh_token::Column::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if revoked.rows_affected == 0 {
+            txn.rollback().await?;
+            return Ok(false);
+        }
+
+        refresh_token::ActiveModel::from(next).insert(&txn).await?;
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_refresh_token_family(&self, family_id: Uuid) -> Result<u64, AppError> {
+        let result = RefreshToken::update_many()
+            .col_expr(refresh_token::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(refresh_token::Column::FamilyId.eq(family_id))
+            .filter(refresh_token::Column::RevokedAt.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn revoke_user_refresh_tokens(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let result = RefreshToken::update_many()
+            .col_expr(refresh_token::Column::RevokedAt, Expr::value(Utc::now()))
+            .filter(refresh_token::Column::UserId.eq(user_id))
+            .filter(refresh_token::Column::RevokedAt.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn purge_expired_refresh_tokens(&self) -> Result<u64, AppError> {
+        let result = RefreshToken::delete_many()
+            .filter(refresh_token::Column::ExpiresAt.lte(Utc::now()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn revoke_access_token(
+        &self,
+        jti: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let model = revoked_access_token::ActiveModel {
+            jti: Set(jti.to_string()),
+            expires_at: Set(expires_at),
+            created_at: Set(Utc::now()),
+        };
+
+        // Logging out twice with the same token is not an error
+        RevokedAccessToken::insert(model)
+            .on_conflict(
+                OnConflict::column(revoked_access_token::Column::Jti)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_user_access_tokens(
+        &self,
+        user_id: Uuid,
+        revoked_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let model = user_token_revocation::ActiveModel {
+            user_id: Set(user_id),
+            revoked_at: Set(revoked_at),
+            expires_at: Set(expires_at),
+        };
+
+        UserTokenRevocation::insert(model)
+            .on_conflict(
+                OnConflict::column(user_token_revocation::Column::UserId)
+                    .update_columns([
+                        user_token_revocation::Column::RevokedAt,
+                        user_token_revocation::Column::ExpiresAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn clear_user_access_token_revocation(&self, user_id: Uuid) -> Result<(), AppError> {
+        UserTokenRevocation::delete_by_id(user_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_access_token_revocations(
+        &self,
+    ) -> Result<
+        (
+            Vec<revoked_access_token::Model>,
+            Vec<user_token_revocation::Model>,
+        ),
+        AppError,
+    > {
+        let now = Utc::now();
+        let tokens = RevokedAccessToken::find()
+            .filter(revoked_access_token::Column::ExpiresAt.gt(now))
+            .all(&self.db)
+            .await?;
+        let users = UserTokenRevocation::find()
+            .filter(user_token_revocation::Column::ExpiresAt.gt(now))
+            .all(&self.db)
+            .await?;
+        Ok((tokens, users))
+    }
+
+    async fn purge_expired_access_token_revocations(&self) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let tokens = RevokedAccessToken::delete_many()
+            .filter(revoked_access_token::Column::ExpiresAt.lte(now))
+            .exec(&self.db)
+            .await?;
+        let users = UserTokenRevocation::delete_many()
+            .filter(user_token_revocation::Column::ExpiresAt.lte(now))
+            .exec(&self.db)
+            .await?;
+        Ok(tokens.rows_affected + users.rows_affected)
+    }
 }
@@ -0,0 +1,364 @@
+use crate::{
+    cache::{CacheKey, CachedValue, ProductCache},
+    entities::{product, product_history, product_image, stock_movement, stock_reservation},
+    error::AppError,
+    models::{
+        CategoryStats, CreateProductRequest, ProductSearchFacets, ProductSearchRequest,
+        ProductStatsResponse, UpdateProductRequest,
+    },
+    repository::{
+        product::{BatchWriteResult, BulkInsertResult, ProductPage, ProductRepositoryTrait},
+        stock_movement::NewStockMovement,
+    },
+    utils::{CurrencyConversion, IfMatch},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+/// Read-through cache in front of another product repository. Product lookups
+/// by id, the stats and the trending categories are served from `cache`;
+/// every write invalidates the products it touched and all aggregates.
+pub struct CachedProductRepository<T: ProductRepositoryTrait> {
+    inner: T,
+    cache: Arc<dyn ProductCache>,
+}
+
+impl<T: ProductRepositoryTrait> CachedProductRepository<T> {
+    pub fn new(inner: T, cache: Arc<dyn ProductCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl<T: ProductRepositoryTrait + Send + Sync> ProductRepositoryTrait
+    for CachedProductRepository<T>
+{
+    async fn create(&self, request: CreateProductRequest) -> Result<product::Model, AppError> {
+        let result = self.inner.create(request).await;
+        self.cache.invalidate_aggregates().await;
+        result
+    }
+
+    async fn create_many(
+        &self,
+        rows: Vec<(usize, CreateProductRequest)>,
+        atomic: bool,
+    ) -> Result<BulkInsertResult, AppError> {
+        let result = self.inner.create_many(rows, atomic).await;
+        self.cache.invalidate_aggregates().await;
+        result
+    }
+
+    async fn find_all(
+        &self,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError> {
+        self.inner
+            .find_all(page, per_page, cursor, include_total)
+            .await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<product::Model>, AppError> {
+        let key = CacheKey::Product(id);
+        if let Some(CachedValue::Product(product)) = self.cache.get(&key).await {
+            return Ok(Some(product));
+        }
+
+        // A write landing while we read would otherwise be undone by our put
+        let generation = self.cache.generation(&key).await;
+        let product = self.inner.find_by_id(id).await?;
+        if let Some(product) = &product {
+            self.cache
+                .put(key, CachedValue::Product(product.clone()), generation)
+                .await;
+        }
+        Ok(product)
+    }
+
+    async fn find_by_sku(&self, sku: &str) -> Result<Option<product::Model>, AppError> {
+        self.inner.find_by_sku(sku).await
+    }
+
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<product::Model>, AppError> {
+        self.inner.find_by_barcode(barcode).await
+    }
+
+    async fn find_images(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<Vec<product_image::Model>, AppError> {
+        self.inner.find_images(product_ids).await
+    }
+
+    async fn find_current_prices(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Decimal>, AppError> {
+        self.inner.find_current_prices(product_ids).await
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        request: UpdateProductRequest,
+        if_match: Option<IfMatch>,
+        actor: Option<Uuid>,
+    ) -> Result<product::Model, AppError> {
+        let result = self.inner.update(id, request, if_match, actor).await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn delete(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError> {
+        let result = self.inner.delete(id, actor).await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn update_many(
+        &self,
+        items: Vec<(Uuid, UpdateProductRequest)>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<product::Model>, AppError> {
+        let ids: Vec<Uuid> = items.iter().map(|(id, _)| *id).collect();
+        let result = self.inner.update_many(items, atomic, actor).await;
+        for id in ids {
+            self.cache.invalidate_product(id).await;
+        }
+        result
+    }
+
+    async fn delete_many(
+        &self,
+        ids: Vec<Uuid>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<()>, AppError> {
+        let result = self.inner.delete_many(ids.clone(), atomic, actor).await;
+        for id in ids {
+            self.cache.invalidate_product(id).await;
+        }
+        result
+    }
+
+    async fn find_deleted(
+        &self,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError> {
+        self.inner
+            .find_deleted(page, per_page, cursor, include_total)
+            .await
+    }
+
+    async fn restore(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<Option<product::Model>, AppError> {
+        let result = self.inner.restore(id, actor).await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn purge(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError> {
+        let result = self.inner.purge(id, actor).await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn find_history(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<product_history::Model>, u64), AppError> {
+        self.inner.find_history(product_id, page, per_page).await
+    }
+
+    async fn exists_including_deleted(&self, id: Uuid) -> Result<bool, AppError> {
+        self.inner.exists_including_deleted(id).await
+    }
+
+    async fn adjust_stock(
+        &self,
+        id: Uuid,
+        movement: NewStockMovement,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_movement::Model), AppError> {
+        let result = self.inner.adjust_stock(id, movement, actor).await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn find_stock_movements(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<stock_movement::Model>, u64), AppError> {
+        self.inner
+            .find_stock_movements(product_id, page, per_page)
+            .await
+    }
+
+    async fn reserve_stock(
+        &self,
+        id: Uuid,
+        quantity: i32,
+        expires_at: DateTime<Utc>,
+        reference: Option<String>,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_reservation::Model), AppError> {
+        let result = self
+            .inner
+            .reserve_stock(id, quantity, expires_at, reference, actor)
+            .await;
+        self.cache.invalidate_product(id).await;
+        result
+    }
+
+    async fn find_reservation(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<stock_reservation::Model>, AppError> {
+        self.inner.find_reservation(id).await
+    }
+
+    async fn confirm_reservation(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<
+        (
+            stock_reservation::Model,
+            product::Model,
+            stock_movement::Model,
+        ),
+        AppError,
+    > {
+        let result = self.inner.confirm_reservation(id, actor).await?;
+        self.cache.invalidate_product(result.1.id).await;
+        Ok(result)
+    }
+
+    async fn release_reservation(&self, id: Uuid) -> Result<stock_reservation::Model, AppError> {
+        let reservation = self.inner.release_reservation(id).await?;
+        self.cache.invalidate_product(reservation.product_id).await;
+        Ok(reservation)
+    }
+
+    async fn expire_reservations(&self) -> Result<u64, AppError> {
+        let expired = self.inner.expire_reservations().await?;
+        if expired > 0 {
+            // The expired holds may span any number of products
+            self.cache.invalidate_all().await;
+        }
+        Ok(expired)
+    }
+
+    async fn search(
+        &self,
+        search_request: ProductSearchRequest,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductPage, AppError> {
+        self.inner.search(search_request, conversion).await
+    }
+
+    async fn search_facets(
+        &self,
+        search_request: ProductSearchRequest,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductSearchFacets, AppError> {
+        self.inner.search_facets(search_request, conversion).await
+    }
+
+    async fn find_by_category(
+        &self,
+        category: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<product::Model>, AppError> {
+        self.inner
+            .find_by_category(category, include_descendants)
+            .await
+    }
+
+    async fn find_by_price_range(
+        &self,
+        min_price: Decimal,
+        max_price: Decimal,
+        conversion: &CurrencyConversion,
+    ) -> Result<Vec<product::Model>, AppError> {
+        self.inner
+            .find_by_price_range(min_price, max_price, conversion)
+            .await
+    }
+
+    async fn find_low_stock(&self, threshold: i32) -> Result<Vec<product::Model>, AppError> {
+        self.inner.find_low_stock(threshold).await
+    }
+
+    async fn get_product_stats(
+        &self,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductStatsResponse, AppError> {
+        let key = CacheKey::Stats {
+            currency: conversion.currency().to_string(),
+        };
+        if let Some(CachedValue::Stats(stats)) = self.cache.get(&key).await {
+            return Ok(stats);
+        }
+
+        let generation = self.cache.generation(&key).await;
+        let stats = self.inner.get_product_stats(conversion).await?;
+        self.cache
+            .put(key, CachedValue::Stats(stats.clone()), generation)
+            .await;
+        Ok(stats)
+    }
+
+    async fn find_similar_products(
+        &self,
+        product_name: &str,
+        limit: u64,
+    ) -> Result<Vec<product::Model>, AppError> {
+        self.inner.find_similar_products(product_name, limit).await
+    }
+
+    async fn get_trending_categories(
+        &self,
+        limit: u64,
+        conversion: &CurrencyConversion,
+    ) -> Result<Vec<CategoryStats>, AppError> {
+        let key = CacheKey::TrendingCategories {
+            limit,
+            currency: conversion.currency().to_string(),
+        };
+        if let Some(CachedValue::TrendingCategories(categories)) = self.cache.get(&key).await {
+            return Ok(categories);
+        }
+
+        let generation = self.cache.generation(&key).await;
+        let categories = self
+            .inner
+            .get_trending_categories(limit, conversion)
+            .await?;
+        self.cache
+            .put(
+                key,
+                CachedValue::TrendingCategories(categories.clone()),
+                generation,
+            )
+            .await;
+        Ok(categories)
+    }
+}
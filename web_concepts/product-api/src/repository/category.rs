@@ -0,0 +1,304 @@
+use crate::{
+    entities::{category, prelude::*, product},
+    error::{conflict_error, validation_error, AppError},
+    utils::slugify,
+};
+use async_trait::async_trait;
+use sea_orm::{
+    prelude::*, ActiveModelTrait, DatabaseBackend, FromQueryResult, QueryOrder, QuerySelect, Set,
+    SqlErr, Statement, TransactionTrait,
+};
+use uuid::Uuid;
+
+/// Postgres name of the unique constraint on `categories.slug`
+const SLUG_UNIQUE_CONSTRAINT: &str = "categories_slug_key";
+
+/// Changes to an existing category; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct CategoryChanges {
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    /// `Some(None)` makes the category a root
+    pub parent_id: Option<Option<Uuid>>,
+}
+
+#[async_trait]
+pub trait CategoryRepositoryTrait {
+    async fn find_all(&self) -> Result<Vec<category::Model>, AppError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<category::Model>, AppError>;
+    async fn create(
+        &self,
+        name: String,
+        slug: String,
+        parent_id: Option<Uuid>,
+    ) -> Result<category::Model, AppError>;
+    async fn update(&self, id: Uuid, changes: CategoryChanges)
+        -> Result<category::Model, AppError>;
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError>;
+}
+
+#[derive(Clone)]
+pub struct CategoryRepository {
+    db: DatabaseConnection,
+}
+
+impl CategoryRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl CategoryRepositoryTrait for CategoryRepository {
+    async fn find_all(&self) -> Result<Vec<category::Model>, AppError> {
+        let categories = Category::find()
+            .order_by_asc(category::Column::Name)
+            .all(&self.db)
+            .await?;
+
+        Ok(categories)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<category::Model>, AppError> {
+        Ok(Category::find_by_id(id).one(&self.db).await?)
+    }
+
+    async fn create(
+        &self,
+        name: String,
+        slug: String,
+        parent_id: Option<Uuid>,
+    ) -> Result<category::Model, AppError> {
+        ensure_slug_free(&self.db, &slug, None).await?;
+        if let Some(parent_id) = parent_id {
+            ensure_parent_exists(&self.db, parent_id).await?;
+        }
+
+        let now = chrono::Utc::now();
+        let category = category::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(name),
+            slug: Set(slug),
+            parent_id: Set(parent_id),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(slug_conflict)?;
+
+        Ok(category)
+    }
+
+    //  **Transactions** - A rename is copied to products.category in the same transaction
+    async fn update(
+        &self,
+        id: Uuid,
+        changes: CategoryChanges,
+    ) -> Result<category::Model, AppError> {
+        let txn = self.db.begin().await?;
+
+        let category = Category::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Category".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        if let Some(slug) = &changes.slug {
+            ensure_slug_free(&txn, slug, Some(id)).await?;
+        }
+        if let Some(Some(parent_id)) = changes.parent_id {
+            ensure_parent_exists(&txn, parent_id).await?;
+            // Moving a category below itself would cut its subtree off the tree
+            if category_subtree_ids(&txn, id).await?.contains(&parent_id) {
+                return Err(validation_error(
+                    "parent_id",
+                    "A category can't be moved below itself or one of its descendants",
+                ));
+            }
+        }
+
+        let renamed = changes
+            .name
+            .as_ref()
+            .is_some_and(|name| *name != category.name);
+
+        let mut active_category: category::ActiveModel = category.into();
+        if let Some(name) = changes.name {
+            active_category.name = Set(name);
+        }
+        if let Some(slug) = changes.slug {
+            active_category.slug = Set(slug);
+        }
+        if let Some(parent_id) = changes.parent_id {
+            active_category.parent_id = Set(parent_id);
+        }
+        active_category.updated_at = Set(chrono::Utc::now());
+        let category = active_category.update(&txn).await.map_err(slug_conflict)?;
+
+        if renamed {
+            Product::update_many()
+                .col_expr(
+                    product::Column::Category,
+                    Expr::value(category.name.clone()),
+                )
+                .filter(product::Column::CategoryId.eq(id))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        Ok(category)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let children = Category::find()
+            .filter(category::Column::ParentId.eq(id))
+            .count(&self.db)
+            .await?;
+        if children > 0 {
+            return Err(conflict_error(
+                "Category",
+                &format!("Category has {children} subcategories; move or delete them first"),
+            ));
+        }
+
+        // Trashed products still reference their category so they can be restored
+        let products = Product::find()
+            .filter(product::Column::CategoryId.eq(id))
+            .count(&self.db)
+            .await?;
+        if products > 0 {
+            return Err(conflict_error(
+                "Category",
+                &format!("Category is used by {products} products"),
+            ));
+        }
+
+        let result = Category::delete_by_id(id).exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+#[derive(FromQueryResult)]
+struct CategoryIdRaw {
+    id: Uuid,
+}
+
+/// Ids of a category and all of its descendants
+pub async fn category_subtree_ids<C: ConnectionTrait>(
+    db: &C,
+    root_id: Uuid,
+) -> Result<Vec<Uuid>, DbErr> {
+    //  **Raw SQL Integration** - Recursive CTE walking down the parent_id links
+    let subtree_query = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id FROM categories WHERE id = $1
+            UNION
+            SELECT c.id FROM categories c JOIN subtree s ON c.parent_id = s.id
+        )
+        SELECT id FROM subtree
+        "#,
+        [root_id.into()],
+    );
+
+    let rows = CategoryIdRaw::find_by_statement(subtree_query)
+        .all(db)
+        .await?;
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+/// Category a product write refers to, by id or by name/slug. Names are matched
+/// through their slug, so "Electronics" and "electronics " are the same category.
+pub async fn resolve_category<C: ConnectionTrait>(
+    db: &C,
+    category_id: Option<Uuid>,
+    category: Option<&str>,
+) -> Result<Option<category::Model>, AppError> {
+    let by_id = match category_id {
+        Some(id) => Some(
+            Category::find_by_id(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| validation_error("category_id", "Unknown category id"))?,
+        ),
+        None => None,
+    };
+
+    let by_name = match category {
+        Some(name) => Some(find_by_slug(db, &slugify(name)).await?.ok_or_else(|| {
+            validation_error(
+                "category",
+                &format!(
+                    "Unknown category '{}'; create it under /categories first",
+                    name.trim()
+                ),
+            )
+        })?),
+        None => None,
+    };
+
+    match (by_id, by_name) {
+        (Some(by_id), Some(by_name)) if by_id.id != by_name.id => Err(validation_error(
+            "category",
+            "category and category_id refer to different categories",
+        )),
+        (by_id, by_name) => Ok(by_id.or(by_name)),
+    }
+}
+
+pub async fn find_by_slug<C: ConnectionTrait>(
+    db: &C,
+    slug: &str,
+) -> Result<Option<category::Model>, DbErr> {
+    if slug.is_empty() {
+        return Ok(None);
+    }
+    Category::find()
+        .filter(category::Column::Slug.eq(slug))
+        .one(db)
+        .await
+}
+
+async fn ensure_slug_free<C: ConnectionTrait>(
+    db: &C,
+    slug: &str,
+    except: Option<Uuid>,
+) -> Result<(), AppError> {
+    let mut query = Category::find().filter(category::Column::Slug.eq(slug));
+    if let Some(id) = except {
+        query = query.filter(category::Column::Id.ne(id));
+    }
+
+    if query.count(db).await? > 0 {
+        return Err(conflict_error(
+            "Category",
+            &format!("Slug '{slug}' is already in use"),
+        ));
+    }
+    Ok(())
+}
+
+/// A concurrent writer can still win the race past `ensure_slug_free`; its
+/// unique violation becomes the same conflict
+fn slug_conflict(err: DbErr) -> AppError {
+    if let Some(SqlErr::UniqueConstraintViolation(message)) = err.sql_err() {
+        if message.contains(SLUG_UNIQUE_CONSTRAINT) {
+            return conflict_error("Category", "Slug is already in use");
+        }
+    }
+    err.into()
+}
+
+async fn ensure_parent_exists<C: ConnectionTrait>(db: &C, parent_id: Uuid) -> Result<(), AppError> {
+    if Category::find_by_id(parent_id).count(db).await? == 0 {
+        return Err(validation_error("parent_id", "Unknown parent category"));
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use crate::{
+    entities::{exchange_rate, prelude::*, product},
+    error::{conflict_error, validation_error, AppError},
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use sea_orm::{prelude::*, sea_query::OnConflict, QueryOrder, Set};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ExchangeRateRepositoryTrait {
+    async fn find_all(&self) -> Result<Vec<exchange_rate::Model>, AppError>;
+    /// Every rate keyed by currency, for building a `CurrencyConversion`
+    async fn find_rates(&self) -> Result<BTreeMap<String, Decimal>, AppError>;
+    async fn upsert(
+        &self,
+        currency: &str,
+        rate: Decimal,
+        actor: Option<Uuid>,
+    ) -> Result<exchange_rate::Model, AppError>;
+    async fn delete(&self, currency: &str) -> Result<bool, AppError>;
+}
+
+#[derive(Clone)]
+pub struct ExchangeRateRepository {
+    db: DatabaseConnection,
+}
+
+impl ExchangeRateRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ExchangeRateRepositoryTrait for ExchangeRateRepository {
+    async fn find_all(&self) -> Result<Vec<exchange_rate::Model>, AppError> {
+        let rates = ExchangeRate::find()
+            .order_by_asc(exchange_rate::Column::Currency)
+            .all(&self.db)
+            .await?;
+
+        Ok(rates)
+    }
+
+    async fn find_rates(&self) -> Result<BTreeMap<String, Decimal>, AppError> {
+        let rates = self
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|rate| (rate.currency, rate.rate))
+            .collect();
+        Ok(rates)
+    }
+
+    //  **Upsert** - INSERT ... ON CONFLICT (currency) DO UPDATE
+    async fn upsert(
+        &self,
+        currency: &str,
+        rate: Decimal,
+        actor: Option<Uuid>,
+    ) -> Result<exchange_rate::Model, AppError> {
+        let model = exchange_rate::ActiveModel {
+            currency: Set(currency.to_string()),
+            rate: Set(rate),
+            updated_by: Set(actor),
+            updated_at: Set(chrono::Utc::now()),
+        };
+
+        let rate = ExchangeRate::insert(model)
+            .on_conflict(
+                OnConflict::column(exchange_rate::Column::Currency)
+                    .update_columns([
+                        exchange_rate::Column::Rate,
+                        exchange_rate::Column::UpdatedBy,
+                        exchange_rate::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_with_returning(&self.db)
+            .await?;
+
+        Ok(rate)
+    }
+
+    async fn delete(&self, currency: &str) -> Result<bool, AppError> {
+        // Trashed products count too, so a restore never brings back an
+        // unconvertible price
+        let in_use = Product::find()
+            .filter(product::Column::Currency.eq(currency))
+            .count(&self.db)
+            .await?;
+        if in_use > 0 {
+            return Err(conflict_error(
+                "ExchangeRate",
+                &format!("{in_use} products are priced in {currency}"),
+            ));
+        }
+
+        let result = ExchangeRate::delete_by_id(currency.to_string())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+/// Products can only be priced in a currency that has a rate
+pub async fn ensure_currency_supported<C: ConnectionTrait>(
+    db: &C,
+    currency: &str,
+) -> Result<(), AppError> {
+    let known = ExchangeRate::find_by_id(currency.to_string())
+        .count(db)
+        .await?;
+    if known == 0 {
+        return Err(validation_error(
+            "currency",
+            &format!("No exchange rate is configured for {currency}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Give the base currency its fixed rate of 1 if it has no row yet, e.g. after
+/// changing `BASE_CURRENCY`
+pub async fn ensure_base_currency<C: ConnectionTrait>(
+    db: &C,
+    base_currency: &str,
+) -> Result<(), AppError> {
+    let model = exchange_rate::ActiveModel {
+        currency: Set(base_currency.to_string()),
+        rate: Set(Decimal::ONE),
+        updated_by: Set(None),
+        updated_at: Set(chrono::Utc::now()),
+    };
+
+    ExchangeRate::insert(model)
+        .on_conflict(
+            OnConflict::column(exchange_rate::Column::Currency)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use crate::{
+    entities::{idempotency_key, prelude::*},
+    error::AppError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{prelude::*, sea_query::Expr, DatabaseBackend, Statement};
+use uuid::Uuid;
+
+/// A first request still running after this long is taken to have died (a
+/// dropped connection, a crash) and its key can be claimed again
+pub const IDEMPOTENCY_LOCK_TIMEOUT_SECONDS: i64 = 60;
+
+/// What the first request made with a key needs to record
+#[derive(Debug, Clone)]
+pub struct NewIdempotencyKey {
+    pub user_id: Uuid,
+    pub idempotency_key: String,
+    pub request_method: String,
+    pub request_path: String,
+    pub fingerprint: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait IdempotencyKeyRepositoryTrait {
+    /// Claim the key for a new request. Returns the time of the claim, which
+    /// `complete` and `release` need, or `None` if a live row already holds
+    /// it; expired rows and abandoned in-flight rows are taken over.
+    async fn try_claim(&self, key: NewIdempotencyKey) -> Result<Option<DateTime<Utc>>, AppError>;
+    async fn find(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+    ) -> Result<Option<idempotency_key::Model>, AppError>;
+    /// Store the response of the request that claimed the key at
+    /// `claimed_at`. Does nothing if the claim was taken over since.
+    async fn complete(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+        claimed_at: DateTime<Utc>,
+        status_code: u16,
+        headers: Json,
+        body: Vec<u8>,
+    ) -> Result<(), AppError>;
+    /// Forget the claim made at `claimed_at`, so the key can be retried from
+    /// scratch. A claim taken over since is left alone.
+    async fn release(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+        claimed_at: DateTime<Utc>,
+    ) -> Result<(), AppError>;
+    async fn purge_expired(&self) -> Result<u64, AppError>;
+}
+
+#[derive(Clone)]
+pub struct IdempotencyKeyRepository {
+    db: DatabaseConnection,
+}
+
+impl IdempotencyKeyRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl IdempotencyKeyRepositoryTrait for IdempotencyKeyRepository {
+    //  **Claim** - INSERT ... ON CONFLICT DO UPDATE ... WHERE, so two racing
+    //  requests with the same key cannot both win. The claim's created_at
+    //  tells a takeover apart from the request it took over.
+    async fn try_claim(&self, key: NewIdempotencyKey) -> Result<Option<DateTime<Utc>>, AppError> {
+        let claim_query = Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+            INSERT INTO idempotency_keys
+                (user_id, idempotency_key, request_method, request_path, fingerprint, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, NOW(), $6)
+            ON CONFLICT (user_id, idempotency_key) DO UPDATE
+            SET request_method = EXCLUDED.request_method,
+                request_path = EXCLUDED.request_path,
+                fingerprint = EXCLUDED.fingerprint,
+                status_code = NULL,
+                response_headers = NULL,
+                response_body = NULL,
+                created_at = EXCLUDED.created_at,
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= NOW()
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.created_at <= NOW() - make_interval(secs => $7))
+            RETURNING created_at
+            "#,
+            [
+                key.user_id.into(),
+                key.idempotency_key.into(),
+                key.request_method.into(),
+                key.request_path.into(),
+                key.fingerprint.into(),
+                key.expires_at.into(),
+                (IDEMPOTENCY_LOCK_TIMEOUT_SECONDS as f64).into(),
+            ],
+        );
+
+        let claimed = self.db.query_one(claim_query).await?;
+        let claimed_at = claimed
+            .map(|row| row.try_get::<DateTime<Utc>>("", "created_at"))
+            .transpose()?;
+        Ok(claimed_at)
+    }
+
+    async fn find(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+    ) -> Result<Option<idempotency_key::Model>, AppError> {
+        let key = IdempotencyKey::find_by_id((user_id, idempotency_key.to_string()))
+            .filter(idempotency_key::Column::ExpiresAt.gt(Utc::now()))
+            .one(&self.db)
+            .await?;
+        Ok(key)
+    }
+
+    async fn complete(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+        claimed_at: DateTime<Utc>,
+        status_code: u16,
+        headers: Json,
+        body: Vec<u8>,
+    ) -> Result<(), AppError> {
+        IdempotencyKey::update_many()
+            .col_expr(
+                idempotency_key::Column::StatusCode,
+                Expr::value(status_code as i16),
+            )
+            .col_expr(
+                idempotency_key::Column::ResponseHeaders,
+                Expr::value(headers),
+            )
+            .col_expr(idempotency_key::Column::ResponseBody, Expr::value(body))
+            .filter(idempotency_key::Column::UserId.eq(user_id))
+            .filter(idempotency_key::Column::IdempotencyKey.eq(idempotency_key))
+            .filter(idempotency_key::Column::CreatedAt.eq(claimed_at))
+            .filter(idempotency_key::Column::StatusCode.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn release(
+        &self,
+        user_id: Uuid,
+        idempotency_key: &str,
+        claimed_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        IdempotencyKey::delete_many()
+            .filter(idempotency_key::Column::UserId.eq(user_id))
+            .filter(idempotency_key::Column::IdempotencyKey.eq(idempotency_key))
+            .filter(idempotency_key::Column::CreatedAt.eq(claimed_at))
+            .filter(idempotency_key::Column::StatusCode.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = IdempotencyKey::delete_many()
+            .filter(idempotency_key::Column::ExpiresAt.lte(Utc::now()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -1,4 +1,26 @@
 pub mod auth;
+pub mod cached_product;
+pub mod category;
+pub mod exchange_rate;
+pub mod idempotency_key;
+pub mod price_schedule;
 pub mod product;
+pub mod product_history;
+pub mod product_image;
+pub mod product_variant;
+pub mod stock_movement;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
 pub use auth::*;
+pub use cached_product::*;
+pub use category::*;
+pub use exchange_rate::*;
+pub use idempotency_key::*;
+pub use price_schedule::*;
 pub use product::*;
+pub use product_history::*;
+pub use product_image::*;
+pub use product_variant::*;
+pub use stock_movement::*;
+pub use webhook_delivery::*;
+pub use webhook_subscription::*;
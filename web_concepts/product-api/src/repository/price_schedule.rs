@@ -0,0 +1,220 @@
+use crate::{
+    entities::{prelude::*, price_schedule, product},
+    error::AppError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{
+    prelude::*, ActiveModelTrait, DatabaseBackend, FromQueryResult, QueryOrder, Set, Statement,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Schedule `ps` covers the current time
+const SCHEDULE_IS_ACTIVE: &str =
+    "ps.effective_from <= now() AND (ps.effective_to IS NULL OR ps.effective_to > now())";
+/// When windows overlap, the schedule that started last wins
+const SCHEDULE_PRECEDENCE: &str = "ps.effective_from DESC, ps.created_at DESC";
+
+/// SQL expression for the current price of the `products` row referenced as
+/// `table`: the winning active schedule's price, else the base price. Filters,
+/// facets and stats all use this so they agree with `ProductResponse.effective_price`.
+pub fn effective_price_sql(table: &str) -> String {
+    format!(
+        "COALESCE((SELECT ps.price FROM price_schedules ps \
+         WHERE ps.product_id = {table}.id AND {SCHEDULE_IS_ACTIVE} \
+         ORDER BY {SCHEDULE_PRECEDENCE} LIMIT 1), {table}.price)"
+    )
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CurrentPriceRaw {
+    product_id: Uuid,
+    price: Decimal,
+}
+
+/// Scheduled prices in effect right now, for the products that have one
+pub async fn find_current_prices<C: ConnectionTrait>(
+    db: &C,
+    product_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Decimal>, AppError> {
+    if product_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = (1..=product_ids.len())
+        .map(|index| format!("${index}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    //  **DISTINCT ON** - First row per product in precedence order
+    let statement = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        format!(
+            "SELECT DISTINCT ON (ps.product_id) ps.product_id, ps.price \
+             FROM price_schedules ps \
+             WHERE ps.product_id IN ({placeholders}) AND {SCHEDULE_IS_ACTIVE} \
+             ORDER BY ps.product_id, {SCHEDULE_PRECEDENCE}"
+        ),
+        product_ids.iter().map(|id| Value::from(*id)),
+    );
+
+    let prices = CurrentPriceRaw::find_by_statement(statement)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.product_id, row.price))
+        .collect();
+    Ok(prices)
+}
+
+/// Changes to an existing schedule; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct PriceScheduleChanges {
+    pub price: Option<Decimal>,
+    pub effective_from: Option<DateTime<Utc>>,
+    /// `Some(None)` makes the schedule open-ended
+    pub effective_to: Option<Option<DateTime<Utc>>>,
+    pub label: Option<String>,
+}
+
+#[async_trait]
+pub trait PriceScheduleRepositoryTrait {
+    /// The live parent product, `None` if it does not exist or is in the trash
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError>;
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<price_schedule::Model>, AppError>;
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        schedule_id: Uuid,
+    ) -> Result<Option<price_schedule::Model>, AppError>;
+    async fn find_current_price(&self, product_id: Uuid) -> Result<Option<Decimal>, AppError>;
+    async fn create(
+        &self,
+        product_id: Uuid,
+        price: Decimal,
+        effective_from: DateTime<Utc>,
+        effective_to: Option<DateTime<Utc>>,
+        label: Option<String>,
+        actor: Option<Uuid>,
+    ) -> Result<price_schedule::Model, AppError>;
+    async fn update(
+        &self,
+        schedule: price_schedule::Model,
+        changes: PriceScheduleChanges,
+    ) -> Result<price_schedule::Model, AppError>;
+    async fn delete(&self, schedule: price_schedule::Model) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct PriceScheduleRepository {
+    db: DatabaseConnection,
+}
+
+impl PriceScheduleRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PriceScheduleRepositoryTrait for PriceScheduleRepository {
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError> {
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(product_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(product)
+    }
+
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<price_schedule::Model>, AppError> {
+        let schedules = PriceSchedule::find()
+            .filter(price_schedule::Column::ProductId.eq(product_id))
+            .order_by_asc(price_schedule::Column::EffectiveFrom)
+            .order_by_asc(price_schedule::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(schedules)
+    }
+
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        schedule_id: Uuid,
+    ) -> Result<Option<price_schedule::Model>, AppError> {
+        let schedule = PriceSchedule::find_by_id(schedule_id)
+            .filter(price_schedule::Column::ProductId.eq(product_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(schedule)
+    }
+
+    async fn find_current_price(&self, product_id: Uuid) -> Result<Option<Decimal>, AppError> {
+        let prices = find_current_prices(&self.db, &[product_id]).await?;
+        Ok(prices.get(&product_id).copied())
+    }
+
+    async fn create(
+        &self,
+        product_id: Uuid,
+        price: Decimal,
+        effective_from: DateTime<Utc>,
+        effective_to: Option<DateTime<Utc>>,
+        label: Option<String>,
+        actor: Option<Uuid>,
+    ) -> Result<price_schedule::Model, AppError> {
+        let schedule = price_schedule::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            product_id: Set(product_id),
+            price: Set(price),
+            effective_from: Set(effective_from),
+            effective_to: Set(effective_to),
+            label: Set(label),
+            created_by: Set(actor),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn update(
+        &self,
+        schedule: price_schedule::Model,
+        changes: PriceScheduleChanges,
+    ) -> Result<price_schedule::Model, AppError> {
+        let mut active_schedule: price_schedule::ActiveModel = schedule.into();
+
+        if let Some(price) = changes.price {
+            active_schedule.price = Set(price);
+        }
+        if let Some(effective_from) = changes.effective_from {
+            active_schedule.effective_from = Set(effective_from);
+        }
+        if let Some(effective_to) = changes.effective_to {
+            active_schedule.effective_to = Set(effective_to);
+        }
+        if let Some(label) = changes.label {
+            active_schedule.label = Set(Some(label));
+        }
+
+        Ok(active_schedule.update(&self.db).await?)
+    }
+
+    async fn delete(&self, schedule: price_schedule::Model) -> Result<(), AppError> {
+        PriceSchedule::delete_by_id(schedule.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
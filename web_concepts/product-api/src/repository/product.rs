@@ -1,27 +1,56 @@
 use crate::{
-    entities::{prelude::*, product},
-    error::AppError,
+    entities::{
+        prelude::*,
+        product,
+        product_history::{self, HistoryAction},
+        product_image,
+        stock_movement::{self, StockMovementReason},
+        stock_reservation::{self, ReservationStatus},
+        webhook_event::WebhookEventType,
+    },
+    error::{
+        business_rule_error, conflict_error, field_conflict_error, validation_error, AppError,
+    },
     models::{
-        CategoryStats, CreateProductRequest, ProductSearchRequest, ProductStatsResponse,
-        UpdateProductRequest,
+        normalize_options, CategoryFacet, CategoryStats, CreateProductRequest, PriceBucketFacet,
+        ProductImportRowError, ProductSearchFacets, ProductSearchHighlight, ProductSearchRequest,
+        ProductStatsResponse, StockFacet, UpdateProductRequest,
+    },
+    repository::{
+        category::{category_subtree_ids, find_by_slug as find_category_by_slug, resolve_category},
+        exchange_rate::ensure_currency_supported,
+        price_schedule::{effective_price_sql, find_current_prices},
+        product_history::{diff_products, record_change},
+        stock_movement::{record_movement, NewStockMovement},
+        webhook_delivery::{record_product_event, record_stock_level},
+    },
+    utils::{
+        build_tsquery, decode_cursor, encode_cursor, normalize_barcode, slugify, version_etag,
+        CurrencyConversion, Cursor, IfMatch,
     },
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use sea_orm::{
-    prelude::*, ActiveModelTrait, DatabaseBackend, FromQueryResult,
-    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, Statement,
+    prelude::*, sea_query::SimpleExpr, ActiveModelTrait, Condition, DatabaseBackend,
+    FromQueryResult, NotSet, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+    Set, SqlErr, Statement, TransactionTrait,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
 };
 use uuid::Uuid;
 
 //  **Custom Result Mapping** - FromQueryResult derive macro
 // These structs map raw SQL query results to Rust types
-#[derive(FromQueryResult)]
-struct ProductCount {}
-
 #[derive(FromQueryResult)]
 struct CategoryStatsRaw {
-    category: Option<String>,
+    category_id: Uuid,
+    category: String,
+    slug: String,
+    parent_id: Option<Uuid>,
     count: i64,
     total_value: Decimal,
 }
@@ -31,42 +60,494 @@ struct ProductStatsRaw {
     total_products: i64,
     total_value: Decimal,
     avg_price: Option<Decimal>,
+    total_variants: i64,
+    variant_value: Decimal,
+}
+
+#[derive(FromQueryResult)]
+struct CategoryFacetRaw {
+    category_id: Option<Uuid>,
+    category: Option<String>,
+    count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct PriceBucketRaw {
+    bucket: i32,
+    count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ExpiredReservationsRaw {
+    expired: i64,
+}
+
+#[derive(FromQueryResult)]
+struct StockFacetRaw {
+    in_stock: i64,
+    out_of_stock: i64,
+}
+
+#[derive(FromQueryResult)]
+struct SearchHighlightRaw {
+    id: Uuid,
+    rank: f32,
+    snippet: Option<String>,
+}
+
+/// Generated `tsvector` over name (weight A) and description (weight B),
+/// see migrations/20251017000001_products_full_text_search.sql
+const TS_MATCH: &str = "search_vector @@ to_tsquery('english', $1)";
+/// Available-to-sell stock, mirrored by `product::Model::available_quantity`
+const AVAILABLE_QUANTITY: &str = "(quantity - reserved_quantity)";
+/// Per-product variant count and stock value at the variants' effective price,
+/// used by the stats queries
+const VARIANT_TOTALS_CTE: &str = r#"
+            variant_totals AS (
+                SELECT
+                    v.product_id,
+                    COUNT(*) AS variant_count,
+                    SUM(COALESCE(v.price, p.price) * v.quantity) AS variant_value
+                FROM product_variants v
+                JOIN products p ON p.id = v.product_id
+                GROUP BY v.product_id
+            )"#;
+/// Every (category, descendant-or-self) pair, so aggregations grouped by
+/// `root_id` roll up the whole subtree. Needs `WITH RECURSIVE`.
+const CATEGORY_TREE_CTE: &str = r#"
+            category_tree AS (
+                SELECT id AS root_id, id AS category_id FROM categories
+                UNION
+                SELECT t.root_id, c.id
+                FROM categories c
+                JOIN category_tree t ON c.parent_id = t.category_id
+            )"#;
+const TS_RANK: &str = "ts_rank(search_vector, to_tsquery('english', $1))";
+const TS_HEADLINE: &str = "ts_headline('english', name || ' ' || COALESCE(description, ''), \
+     to_tsquery('english', $1), 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2')";
+
+/// Partial unique index on the SKU of live products, see
+/// migrations/20251017000009_product_identifiers.sql
+const SKU_UNIQUE_INDEX: &str = "products_sku_key";
+/// Partial unique index on the barcode of live products, over its EAN-13 spelling
+const BARCODE_UNIQUE_INDEX: &str = "products_barcode_key";
+/// UPC-A and EAN-13 spellings of a code share one key, matching the barcode index
+const BARCODE_KEY_MATCH: &str = "lpad(barcode, 13, '0') = $1";
+
+/// Price histogram edges used when the request does not specify `price_buckets`
+const DEFAULT_PRICE_BUCKETS: [i64; 8] = [0, 10, 25, 50, 100, 250, 500, 1000];
+const MAX_PRICE_BUCKETS: usize = 20;
+
+/// One page of products, either offset- or cursor-based
+#[derive(Debug, Clone)]
+pub struct ProductPage {
+    pub products: Vec<product::Model>,
+    /// Only populated when the caller asked for a total count
+    pub total: Option<u64>,
+    /// Opaque cursor for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+    /// Rank and snippet per product, in page order (full-text searches only)
+    pub highlights: Vec<ProductSearchHighlight>,
+}
+
+/// Result of inserting a batch of rows keyed by their line in the source file
+#[derive(Debug, Clone, Default)]
+pub struct BulkInsertResult {
+    pub inserted: usize,
+    pub errors: Vec<ProductImportRowError>,
+    pub committed: bool,
+}
+
+/// Per-item outcomes of a batch write, in request order. An atomic batch stops
+/// at its first failure, so later items have no outcome.
+#[derive(Debug)]
+pub struct BatchWriteResult<T> {
+    pub outcomes: Vec<(Uuid, Result<T, AppError>)>,
+    pub committed: bool,
+}
+
+impl<T> Default for BatchWriteResult<T> {
+    fn default() -> Self {
+        Self {
+            outcomes: Vec::new(),
+            committed: false,
+        }
+    }
+}
+
+/// Offset or cursor position requested by the client
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PageRequest<'a> {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+    pub cursor: Option<&'a str>,
+    pub include_total: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProductSortField {
+    Name,
+    Price,
+    Quantity,
+    CreatedAt,
+    /// `ts_rank` against the full-text query
+    Relevance,
+}
+
+/// Sort applied to product listings. Rows are always tie-broken by `id` so that
+/// `(sort column, id)` is a unique key usable for keyset pagination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductSort {
+    pub field: ProductSortField,
+    pub descending: bool,
+    /// tsquery the relevance rank is computed against
+    ts_query: Option<String>,
+    /// Currency prices are compared in, `None` for each product's own
+    currency: Option<String>,
+    /// Listed price the price sort orders and seeks by, the same expression the
+    /// price filters use
+    listed_price: String,
+}
+
+impl Default for ProductSort {
+    fn default() -> Self {
+        Self::new(ProductSortField::CreatedAt, true)
+    }
+}
+
+impl ProductSort {
+    /// Map the `sort_by`/`sort_order` query parameters to a sort.
+    /// created_at defaults to newest first, every other field to ascending.
+    /// Relevance needs a tsquery, see [`ProductSort::relevance`].
+    pub fn from_params(sort_by: Option<&str>, sort_order: Option<&str>) -> Self {
+        match (sort_by, sort_order) {
+            (Some("name"), order) => Self::new(ProductSortField::Name, order == Some("desc")),
+            (Some("price"), order) => Self::new(ProductSortField::Price, order == Some("desc")),
+            (Some("quantity"), order) => {
+                Self::new(ProductSortField::Quantity, order == Some("desc"))
+            }
+            (Some("created_at"), Some("asc")) => Self::new(ProductSortField::CreatedAt, false),
+            _ => Self::default(), // Default sorting
+        }
+    }
+
+    /// Best matches first unless `sort_order=asc`
+    pub fn relevance(ts_query: String, sort_order: Option<&str>) -> Self {
+        Self {
+            ts_query: Some(ts_query),
+            ..Self::new(ProductSortField::Relevance, sort_order != Some("asc"))
+        }
+    }
+
+    /// Order and page by prices as listed in the conversion's currency
+    pub fn in_currency(self, conversion: &CurrencyConversion) -> Self {
+        Self {
+            currency: Some(conversion.currency().to_string()),
+            listed_price: listed_price_sql("products", conversion),
+            ..self
+        }
+    }
+
+    fn new(field: ProductSortField, descending: bool) -> Self {
+        Self {
+            field,
+            descending,
+            ts_query: None,
+            currency: None,
+            listed_price: effective_price_sql("products"),
+        }
+    }
+
+    /// Stable identifier embedded in cursors, e.g. "price:asc" or "price:asc:EUR"
+    pub fn key(&self) -> String {
+        let field = match self.field {
+            ProductSortField::Name => "name",
+            ProductSortField::Price => "price",
+            ProductSortField::Quantity => "quantity",
+            ProductSortField::CreatedAt => "created_at",
+            ProductSortField::Relevance => "relevance",
+        };
+        let order = if self.descending { "desc" } else { "asc" };
+        match (self.field, &self.currency) {
+            // A price cursor only makes sense in the currency it was issued in
+            (ProductSortField::Price, Some(currency)) => format!("{field}:{order}:{currency}"),
+            _ => format!("{field}:{order}"),
+        }
+    }
+
+    fn expr(&self) -> SimpleExpr {
+        let column = match self.field {
+            ProductSortField::Name => product::Column::Name,
+            ProductSortField::Price => return Expr::cust(self.listed_price.clone()),
+            ProductSortField::Quantity => product::Column::Quantity,
+            ProductSortField::CreatedAt => product::Column::CreatedAt,
+            ProductSortField::Relevance => {
+                let ts_query = self.ts_query.clone().unwrap_or_default();
+                return Expr::cust_with_values(TS_RANK, [ts_query]);
+            }
+        };
+        Expr::col((Product, column)).into()
+    }
+
+    fn order(&self) -> Order {
+        if self.descending {
+            Order::Desc
+        } else {
+            Order::Asc
+        }
+    }
+
+    fn apply(&self, query: Select<Product>) -> Select<Product> {
+        query
+            .order_by(self.expr(), self.order())
+            .order_by(product::Column::Id, self.order())
+    }
+
+    /// Build the cursor pointing just after `product`. `rank` is only used when
+    /// sorting by relevance, `listed_price` when sorting by price.
+    pub fn cursor_for(
+        &self,
+        product: &product::Model,
+        rank: Option<f32>,
+        listed_price: Option<Decimal>,
+    ) -> Cursor {
+        let value = match self.field {
+            ProductSortField::Name => serde_json::Value::from(product.name.clone()),
+            ProductSortField::Price => {
+                serde_json::Value::from(listed_price.unwrap_or(product.price).to_string())
+            }
+            ProductSortField::Quantity => serde_json::Value::from(product.quantity),
+            ProductSortField::CreatedAt => serde_json::Value::from(product.created_at.to_rfc3339()),
+            ProductSortField::Relevance => serde_json::Value::from(rank.unwrap_or_default()),
+        };
+
+        Cursor {
+            sort: self.key(),
+            value,
+            id: product.id,
+        }
+    }
+
+    /// `WHERE (sort, id) > (value, id)` (or `<` when descending), spelled out so
+    /// it works with mixed column types
+    fn after(&self, cursor: &Cursor) -> Result<Condition, AppError> {
+        let invalid =
+            || AppError::validation_error(Some("cursor".to_string()), "Invalid cursor".to_string());
+
+        if cursor.sort != self.key() {
+            return Err(AppError::validation_error(
+                Some("cursor".to_string()),
+                format!(
+                    "Cursor was issued for sort '{}' but the request uses '{}'",
+                    cursor.sort,
+                    self.key()
+                ),
+            ));
+        }
+
+        let value: sea_orm::Value = match self.field {
+            ProductSortField::Name => cursor
+                .value
+                .as_str()
+                .ok_or_else(invalid)?
+                .to_string()
+                .into(),
+            ProductSortField::Price => {
+                let raw = cursor.value.as_str().ok_or_else(invalid)?;
+                Decimal::from_str(raw).map_err(|_| invalid())?.into()
+            }
+            ProductSortField::Quantity => {
+                let raw = cursor.value.as_i64().ok_or_else(invalid)?;
+                i32::try_from(raw).map_err(|_| invalid())?.into()
+            }
+            ProductSortField::CreatedAt => {
+                let raw = cursor.value.as_str().ok_or_else(invalid)?;
+                chrono::DateTime::parse_from_rfc3339(raw)
+                    .map_err(|_| invalid())?
+                    .with_timezone(&chrono::Utc)
+                    .into()
+            }
+            // ts_rank returns `real`, so compare against an f32
+            ProductSortField::Relevance => {
+                (cursor.value.as_f64().ok_or_else(invalid)? as f32).into()
+            }
+        };
+
+        let condition = if self.descending {
+            Condition::any()
+                .add(Expr::expr(self.expr()).lt(value.clone()))
+                .add(
+                    Condition::all()
+                        .add(Expr::expr(self.expr()).eq(value))
+                        .add(product::Column::Id.lt(cursor.id)),
+                )
+        } else {
+            Condition::any()
+                .add(Expr::expr(self.expr()).gt(value.clone()))
+                .add(
+                    Condition::all()
+                        .add(Expr::expr(self.expr()).eq(value))
+                        .add(product::Column::Id.gt(cursor.id)),
+                )
+        };
+
+        Ok(condition)
+    }
 }
 
 #[async_trait]
 pub trait ProductRepositoryTrait {
     async fn create(&self, request: CreateProductRequest) -> Result<product::Model, AppError>;
+    async fn create_many(
+        &self,
+        rows: Vec<(usize, CreateProductRequest)>,
+        atomic: bool,
+    ) -> Result<BulkInsertResult, AppError>;
     async fn find_all(
         &self,
         page: Option<u64>,
         per_page: Option<u64>,
-    ) -> Result<(Vec<product::Model>, u64), AppError>;
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<product::Model>, AppError>;
+    // Identifier lookups for scanners and integrations
+    async fn find_by_sku(&self, sku: &str) -> Result<Option<product::Model>, AppError>;
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<product::Model>, AppError>;
+    /// Live images of the given products, in display order
+    async fn find_images(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<Vec<product_image::Model>, AppError>;
+    /// Scheduled prices currently overriding the base price, keyed by product
+    async fn find_current_prices(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Decimal>, AppError>;
     async fn update(
         &self,
         id: Uuid,
         request: UpdateProductRequest,
+        if_match: Option<IfMatch>,
+        actor: Option<Uuid>,
     ) -> Result<product::Model, AppError>;
-    async fn delete(&self, id: Uuid) -> Result<bool, AppError>;
+    async fn delete(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError>;
+    /// Update several products in one transaction. Atomic mode stops and rolls
+    /// back at the first failing item; otherwise each item runs in a SAVEPOINT.
+    async fn update_many(
+        &self,
+        items: Vec<(Uuid, UpdateProductRequest)>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<product::Model>, AppError>;
+    /// Move several products to the trash in one transaction, like `update_many`
+    async fn delete_many(
+        &self,
+        ids: Vec<Uuid>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<()>, AppError>;
+    // Trash management for soft-deleted products
+    async fn find_deleted(
+        &self,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError>;
+    async fn restore(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<Option<product::Model>, AppError>;
+    async fn purge(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError>;
+    // Audit trail
+    async fn find_history(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<product_history::Model>, u64), AppError>;
+    async fn exists_including_deleted(&self, id: Uuid) -> Result<bool, AppError>;
+    // Inventory ledger
+    async fn adjust_stock(
+        &self,
+        id: Uuid,
+        movement: NewStockMovement,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_movement::Model), AppError>;
+    async fn find_stock_movements(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<stock_movement::Model>, u64), AppError>;
+    // Stock reservations
+    async fn reserve_stock(
+        &self,
+        id: Uuid,
+        quantity: i32,
+        expires_at: DateTime<Utc>,
+        reference: Option<String>,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_reservation::Model), AppError>;
+    async fn find_reservation(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<stock_reservation::Model>, AppError>;
+    async fn confirm_reservation(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<
+        (
+            stock_reservation::Model,
+            product::Model,
+            stock_movement::Model,
+        ),
+        AppError,
+    >;
+    async fn release_reservation(&self, id: Uuid) -> Result<stock_reservation::Model, AppError>;
+    async fn expire_reservations(&self) -> Result<u64, AppError>;
     // Custom search queries demonstrating advanced SeaORM features
+    // `conversion` puts price filters, buckets and totals in one currency: the
+    // requested one, or BASE_CURRENCY so differently priced products compare
     async fn search(
         &self,
         search_request: ProductSearchRequest,
-    ) -> Result<(Vec<product::Model>, u64), AppError>;
-    async fn find_by_category(&self, category: &str) -> Result<Vec<product::Model>, AppError>;
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductPage, AppError>;
+    async fn search_facets(
+        &self,
+        search_request: ProductSearchRequest,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductSearchFacets, AppError>;
+    async fn find_by_category(
+        &self,
+        category: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<product::Model>, AppError>;
     async fn find_by_price_range(
         &self,
         min_price: Decimal,
         max_price: Decimal,
+        conversion: &CurrencyConversion,
     ) -> Result<Vec<product::Model>, AppError>;
     async fn find_low_stock(&self, threshold: i32) -> Result<Vec<product::Model>, AppError>;
-    async fn get_product_stats(&self) -> Result<ProductStatsResponse, AppError>;
+    async fn get_product_stats(
+        &self,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductStatsResponse, AppError>;
     async fn find_similar_products(
         &self,
         product_name: &str,
         limit: u64,
     ) -> Result<Vec<product::Model>, AppError>;
-    async fn get_trending_categories(&self, limit: u64) -> Result<Vec<CategoryStats>, AppError>;
+    async fn get_trending_categories(
+        &self,
+        limit: u64,
+        conversion: &CurrencyConversion,
+    ) -> Result<Vec<CategoryStats>, AppError>;
 }
 
 #[derive(Clone)]
@@ -78,29 +559,164 @@ impl ProductRepository {
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
     }
+
+    //  **Keyset Pagination** - Seek past the cursor instead of OFFSET when one is given.
+    // One extra row is fetched to find out whether a next page exists, so no COUNT
+    // is needed unless the caller explicitly asks for a total.
+    async fn fetch_page(
+        &self,
+        query: Select<Product>,
+        sort: &ProductSort,
+        page: PageRequest<'_>,
+        ts_query: Option<&str>,
+    ) -> Result<ProductPage, AppError> {
+        let per_page = page.per_page.unwrap_or(10).clamp(1, 100);
+        // Offset clients have always received a total; cursor clients opt in
+        let include_total = page.include_total.unwrap_or(page.cursor.is_none());
+
+        let total = if include_total {
+            Some(query.clone().count(&self.db).await?)
+        } else {
+            None
+        };
+
+        let query = match page.cursor {
+            Some(cursor) => query.filter(sort.after(&decode_cursor(cursor)?)?),
+            None => query.offset(page.page.unwrap_or(1).saturating_sub(1) * per_page),
+        };
+
+        let mut products = sort.apply(query).limit(per_page + 1).all(&self.db).await?;
+        let has_more = products.len() as u64 > per_page;
+        products.truncate(per_page as usize);
+
+        let highlights = match ts_query {
+            Some(ts_query) => self.search_highlights(&products, ts_query).await?,
+            None => Vec::new(),
+        };
+
+        let next_cursor = match products.last() {
+            Some(last) if has_more => {
+                let rank = highlights.last().map(|highlight| highlight.rank);
+                // The listed price is computed in SQL; read it back exactly as it was sorted
+                let listed_price = if sort.field == ProductSortField::Price {
+                    Product::find()
+                        .select_only()
+                        .column_as(sort.expr(), "listed_price")
+                        .filter(product::Column::Id.eq(last.id))
+                        .into_tuple::<Option<Decimal>>()
+                        .one(&self.db)
+                        .await?
+                        .flatten()
+                } else {
+                    None
+                };
+                Some(encode_cursor(&sort.cursor_for(last, rank, listed_price)))
+            }
+            _ => None,
+        };
+
+        Ok(ProductPage {
+            products,
+            total,
+            next_cursor,
+            highlights,
+        })
+    }
+
+    //  **Full-Text Search** - ts_rank + ts_headline for the rows on the current page only,
+    // so the comparatively expensive headline generation never runs over the whole match set
+    async fn search_highlights(
+        &self,
+        products: &[product::Model],
+        ts_query: &str,
+    ) -> Result<Vec<ProductSearchHighlight>, AppError> {
+        if products.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows: Vec<SearchHighlightRaw> = Product::find()
+            .select_only()
+            .column(product::Column::Id)
+            .column_as(Expr::cust_with_values(TS_RANK, [ts_query]), "rank")
+            .column_as(Expr::cust_with_values(TS_HEADLINE, [ts_query]), "snippet")
+            .filter(product::Column::Id.is_in(products.iter().map(|p| p.id)))
+            .into_model::<SearchHighlightRaw>()
+            .all(&self.db)
+            .await?;
+
+        let mut by_id: HashMap<Uuid, SearchHighlightRaw> =
+            rows.into_iter().map(|row| (row.id, row)).collect();
+
+        // Keep the page order so the last highlight belongs to the last product
+        Ok(products
+            .iter()
+            .filter_map(|product| by_id.remove(&product.id))
+            .map(|row| ProductSearchHighlight {
+                product_id: row.id,
+                rank: row.rank,
+                snippet: row.snippet,
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
 impl ProductRepositoryTrait for ProductRepository {
     async fn create(&self, request: CreateProductRequest) -> Result<product::Model, AppError> {
-        let product_id = Uuid::new_v4();
-        let now = chrono::Utc::now();
+        let txn = self.db.begin().await?;
+        let product = insert_product(&txn, request).await?;
+        txn.commit().await?;
+        Ok(product)
+    }
 
-        let new_product = product::ActiveModel {
-            id: Set(product_id),
-            name: Set(request.name),
-            description: Set(request.description),
-            price: Set(request.price),
-            quantity: Set(request.quantity),
-            category: Set(request.category),
-            created_by: Set(request.created_by),
-            updated_by: Set(request.updated_by),
-            created_at: Set(now),
-            updated_at: Set(now),
-        };
+    //  **Transactions** - Bulk insert in a single transaction.
+    // Atomic mode stops and rolls back at the first failing row; otherwise every row
+    // runs in its own SAVEPOINT so one bad row doesn't abort the rest.
+    async fn create_many(
+        &self,
+        rows: Vec<(usize, CreateProductRequest)>,
+        atomic: bool,
+    ) -> Result<BulkInsertResult, AppError> {
+        let txn = self.db.begin().await?;
+        let mut result = BulkInsertResult::default();
 
-        let product = new_product.insert(&self.db).await?;
-        Ok(product)
+        for (line, request) in rows {
+            let outcome = if atomic {
+                insert_product(&txn, request).await.map(|_| ())
+            } else {
+                let savepoint = txn.begin().await?;
+                match insert_product(&savepoint, request).await {
+                    Ok(_) => savepoint.commit().await.map_err(AppError::from),
+                    Err(e) => {
+                        savepoint.rollback().await?;
+                        Err(e)
+                    }
+                }
+            };
+
+            match outcome {
+                Ok(()) => result.inserted += 1,
+                Err(e) => {
+                    result.errors.push(ProductImportRowError {
+                        line,
+                        message: match e {
+                            AppError::ValidationError { message, .. } => message,
+                            AppError::DatabaseError { details, .. } => details,
+                            other => other.to_string(),
+                        },
+                    });
+                    if atomic {
+                        txn.rollback().await?;
+                        result.inserted = 0;
+                        return Ok(result);
+                    }
+                }
+            }
+        }
+
+        txn.commit().await?;
+        result.committed = true;
+        Ok(result)
     }
 
     //  **Pagination & Sorting** - Basic pagination with sorting
@@ -108,34 +724,339 @@ impl ProductRepositoryTrait for ProductRepository {
         &self,
         page: Option<u64>,
         per_page: Option<u64>,
-    ) -> Result<(Vec<product::Model>, u64), AppError> {
-        let page = page.unwrap_or(1);
-        let per_page = per_page.unwrap_or(10).min(100);
-
-        // SeaORM Pagination: Creates a paginator that handles LIMIT/OFFSET automatically
-        let paginator = Product::find()
-            .order_by_desc(product::Column::CreatedAt) // Simple sorting by creation date
-            .paginate(&self.db, per_page);
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError> {
+        let page = PageRequest {
+            page,
+            per_page,
+            cursor: cursor.as_deref(),
+            include_total,
+        };
 
-        // Get total count for pagination metadata
-        let total = paginator.num_items().await?;
-        // Fetch specific page (0-indexed internally)
-        let products = paginator.fetch_page(page.saturating_sub(1)).await?;
-        Ok((products, total))
+        // Simple sorting by creation date, newest first
+        self.fetch_page(Product::find_active(), &ProductSort::default(), page, None)
+            .await
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<product::Model>, AppError> {
-        let product = Product::find_by_id(id).one(&self.db).await?;
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(id))
+            .one(&self.db)
+            .await?;
+        Ok(product)
+    }
+
+    //  **Unique Lookups** - Served by the partial unique index on live products
+    async fn find_by_sku(&self, sku: &str) -> Result<Option<product::Model>, AppError> {
+        let product = Product::find_active()
+            .filter(product::Column::Sku.eq(sku))
+            .one(&self.db)
+            .await?;
         Ok(product)
     }
 
+    //  **Expression Index** - Matches UPC-A and EAN-13 spellings of the same code
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<product::Model>, AppError> {
+        let product = Product::find_active()
+            .filter(Expr::cust_with_values(
+                BARCODE_KEY_MATCH,
+                [normalize_barcode(barcode)],
+            ))
+            .one(&self.db)
+            .await?;
+        Ok(product)
+    }
+
+    //  **Batch Loading** - One IN query for a whole page instead of one per product
+    async fn find_images(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<Vec<product_image::Model>, AppError> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.is_in(product_ids.iter().copied()))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .order_by_asc(product_image::Column::Position)
+            .order_by_asc(product_image::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        Ok(images)
+    }
+
+    async fn find_current_prices(
+        &self,
+        product_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Decimal>, AppError> {
+        find_current_prices(&self.db, product_ids).await
+    }
+
+    //  **Optimistic Concurrency** + **Transactions** - Version-checked update.
+    // The row is locked (SELECT ... FOR UPDATE) for the read-compare-write, so a concurrent
+    // writer waits and then sees the bumped version instead of silently overwriting it.
     async fn update(
         &self,
         id: Uuid,
         request: UpdateProductRequest,
+        if_match: Option<IfMatch>,
+        actor: Option<Uuid>,
     ) -> Result<product::Model, AppError> {
-        let product = Product::find_by_id(id)
-            .one(&self.db)
+        let txn = self.db.begin().await?;
+        let updated_product = update_product(&txn, id, request, if_match, actor).await?;
+        txn.commit().await?;
+
+        Ok(updated_product)
+    }
+
+    //  **Soft Delete** - Move to the trash by stamping deleted_at instead of removing the row,
+    // so created_by/updated_by history survives and the product can be restored
+    async fn delete(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError> {
+        let txn = self.db.begin().await?;
+        let deleted = soft_delete_product(&txn, id, actor).await?;
+        txn.commit().await?;
+
+        Ok(deleted)
+    }
+
+    //  **Transactions** - Batch update in a single transaction, same modes as create_many
+    async fn update_many(
+        &self,
+        items: Vec<(Uuid, UpdateProductRequest)>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<product::Model>, AppError> {
+        let txn = self.db.begin().await?;
+        let ids: Vec<Uuid> = items.iter().map(|(id, _)| *id).collect();
+        lock_in_id_order(&txn, &ids).await?;
+        let mut result = BatchWriteResult::default();
+
+        for (id, request) in items {
+            let outcome = if atomic {
+                update_product(&txn, id, request, None, actor).await
+            } else {
+                let savepoint = txn.begin().await?;
+                match update_product(&savepoint, id, request, None, actor).await {
+                    Ok(product) => savepoint
+                        .commit()
+                        .await
+                        .map(|_| product)
+                        .map_err(AppError::from),
+                    Err(e) => {
+                        savepoint.rollback().await?;
+                        Err(e)
+                    }
+                }
+            };
+
+            let failed = outcome.is_err();
+            result.outcomes.push((id, outcome));
+            if atomic && failed {
+                txn.rollback().await?;
+                return Ok(result);
+            }
+        }
+
+        txn.commit().await?;
+        result.committed = true;
+        Ok(result)
+    }
+
+    async fn delete_many(
+        &self,
+        ids: Vec<Uuid>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteResult<()>, AppError> {
+        let txn = self.db.begin().await?;
+        lock_in_id_order(&txn, &ids).await?;
+        let mut result = BatchWriteResult::default();
+
+        for id in ids {
+            let outcome = if atomic {
+                soft_delete_product(&txn, id, actor).await
+            } else {
+                let savepoint = txn.begin().await?;
+                match soft_delete_product(&savepoint, id, actor).await {
+                    Ok(deleted) => savepoint
+                        .commit()
+                        .await
+                        .map(|_| deleted)
+                        .map_err(AppError::from),
+                    Err(e) => {
+                        savepoint.rollback().await?;
+                        Err(e)
+                    }
+                }
+            };
+            let outcome = outcome.and_then(|deleted| {
+                if deleted {
+                    Ok(())
+                } else {
+                    Err(AppError::NotFound {
+                        resource_type: "Product".to_string(),
+                        resource_id: Some(id.to_string()),
+                        error_id: uuid::Uuid::new_v4(),
+                    })
+                }
+            });
+
+            let failed = outcome.is_err();
+            result.outcomes.push((id, outcome));
+            if atomic && failed {
+                txn.rollback().await?;
+                return Ok(result);
+            }
+        }
+
+        txn.commit().await?;
+        result.committed = true;
+        Ok(result)
+    }
+
+    async fn find_deleted(
+        &self,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductPage, AppError> {
+        let page = PageRequest {
+            page,
+            per_page,
+            cursor: cursor.as_deref(),
+            include_total,
+        };
+
+        self.fetch_page(Product::find_deleted(), &ProductSort::default(), page, None)
+            .await
+    }
+
+    async fn restore(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<Option<product::Model>, AppError> {
+        let txn = self.db.begin().await?;
+
+        let Some(product) = Product::find_deleted()
+            .filter(product::Column::Id.eq(id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Identifiers are only unique among live products, so another product may
+        // have taken them while this one was in the trash
+        ensure_identifiers_free(
+            &txn,
+            product.sku.as_deref(),
+            product.barcode.as_deref(),
+            None,
+        )
+        .await?;
+
+        let mut active_product: product::ActiveModel = product.clone().into();
+        active_product.deleted_at = Set(None);
+        stamp_change(&mut active_product, &product, actor);
+
+        let restored_product = active_product
+            .update(&txn)
+            .await
+            .map_err(identifier_conflict)?;
+        ProductImage::update_many()
+            .col_expr(
+                product_image::Column::OrphanedAt,
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(product_image::Column::ProductId.eq(id))
+            .exec(&txn)
+            .await?;
+        record_change(
+            &txn,
+            id,
+            HistoryAction::Restore,
+            actor,
+            diff_products(Some(&product), Some(&restored_product)),
+        )
+        .await?;
+        // Back in the catalog, so subscribers see it as changed
+        record_product_event(&txn, WebhookEventType::ProductUpdated, &restored_product).await?;
+        txn.commit().await?;
+
+        Ok(Some(restored_product))
+    }
+
+    //  **Hard Delete** - Permanently remove a product; only rows already in the trash qualify.
+    // The history is kept (with a final purge entry) so the audit trail outlives the row.
+    async fn purge(&self, id: Uuid, actor: Option<Uuid>) -> Result<bool, AppError> {
+        let txn = self.db.begin().await?;
+
+        let Some(product) = Product::find_deleted()
+            .filter(product::Column::Id.eq(id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        Product::delete_by_id(id).exec(&txn).await?;
+        record_change(
+            &txn,
+            id,
+            HistoryAction::Purge,
+            actor,
+            diff_products(Some(&product), None),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(true)
+    }
+
+    //  **Pagination** - Audit trail for one product, newest change first
+    async fn find_history(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<product_history::Model>, u64), AppError> {
+        let paginator = ProductHistory::find()
+            .filter(product_history::Column::ProductId.eq(product_id))
+            .order_by_desc(product_history::Column::ChangedAt)
+            .order_by_desc(product_history::Column::Id)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await?;
+        let entries = paginator.fetch_page(page.saturating_sub(1)).await?;
+
+        Ok((entries, total))
+    }
+
+    async fn exists_including_deleted(&self, id: Uuid) -> Result<bool, AppError> {
+        Ok(Product::find_by_id(id).count(&self.db).await? > 0)
+    }
+
+    //  **Transactions** - Ledger entry + running total in one transaction.
+    // The product row is locked while the new total is checked, so concurrent sales can't
+    // drive stock below zero; the CHECK constraint on products.quantity is the backstop.
+    async fn adjust_stock(
+        &self,
+        id: Uuid,
+        movement: NewStockMovement,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_movement::Model), AppError> {
+        let txn = self.db.begin().await?;
+
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(id))
+            .lock_exclusive()
+            .one(&txn)
             .await?
             .ok_or(AppError::NotFound {
                 resource_type: "Product".to_string(),
@@ -143,32 +1064,271 @@ impl ProductRepositoryTrait for ProductRepository {
                 error_id: uuid::Uuid::new_v4(),
             })?;
 
-        let mut active_product: product::ActiveModel = product.into();
-
-        if let Some(name) = request.name {
-            active_product.name = Set(name);
+        let quantity_after = product
+            .quantity
+            .checked_add(movement.quantity_delta)
+            .filter(|quantity| *quantity >= 0)
+            .ok_or_else(|| {
+                business_rule_error(
+                    "non_negative_stock",
+                    &format!(
+                        "Cannot apply {} to '{}': only {} in stock",
+                        movement.quantity_delta, product.name, product.quantity
+                    ),
+                )
+            })?;
+        // Units held by active reservations have to stay on hand until they are resolved
+        if quantity_after < product.reserved_quantity {
+            return Err(business_rule_error(
+                "reserved_stock",
+                &format!(
+                    "Cannot apply {} to '{}': {} of its {} units are reserved",
+                    movement.quantity_delta,
+                    product.name,
+                    product.reserved_quantity,
+                    product.quantity
+                ),
+            ));
         }
-        if let Some(description) = request.description {
-            active_product.description = Set(Some(description));
+
+        let mut active_product: product::ActiveModel = product.clone().into();
+        active_product.quantity = Set(quantity_after);
+        stamp_change(&mut active_product, &product, actor);
+
+        let updated_product = active_product.update(&txn).await?;
+        let movement = record_movement(&txn, id, movement, quantity_after, actor).await?;
+        record_change(
+            &txn,
+            id,
+            HistoryAction::Update,
+            actor,
+            diff_products(Some(&product), Some(&updated_product)),
+        )
+        .await?;
+        record_product_event(&txn, WebhookEventType::ProductUpdated, &updated_product).await?;
+        record_stock_level(&txn, &product, &updated_product).await?;
+        txn.commit().await?;
+
+        Ok((updated_product, movement))
+    }
+
+    //  **Pagination** - Ledger for one product, newest movement first
+    async fn find_stock_movements(
+        &self,
+        product_id: Uuid,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<stock_movement::Model>, u64), AppError> {
+        let paginator = StockMovement::find()
+            .filter(stock_movement::Column::ProductId.eq(product_id))
+            .order_by_desc(stock_movement::Column::CreatedAt)
+            .order_by_desc(stock_movement::Column::Id)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await?;
+        let movements = paginator.fetch_page(page.saturating_sub(1)).await?;
+
+        Ok((movements, total))
+    }
+
+    //  **Transactions** - Hold stock for a checkout. Only `reserved_quantity` changes, so
+    // the product's version (and ETag) stays the same.
+    async fn reserve_stock(
+        &self,
+        id: Uuid,
+        quantity: i32,
+        expires_at: DateTime<Utc>,
+        reference: Option<String>,
+        actor: Option<Uuid>,
+    ) -> Result<(product::Model, stock_reservation::Model), AppError> {
+        let txn = self.db.begin().await?;
+
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        let available = product.available_quantity();
+        if quantity > available {
+            return Err(business_rule_error(
+                "insufficient_available_stock",
+                &format!(
+                    "Cannot reserve {quantity} of '{}': only {available} available",
+                    product.name
+                ),
+            ));
         }
-        if let Some(price) = request.price {
-            active_product.price = Set(price);
+
+        let reserved_quantity = product.reserved_quantity + quantity;
+        let mut active_product: product::ActiveModel = product.into();
+        active_product.reserved_quantity = Set(reserved_quantity);
+        let updated_product = active_product.update(&txn).await?;
+
+        let reservation = stock_reservation::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            product_id: Set(id),
+            quantity: Set(quantity),
+            status: Set(ReservationStatus::Active),
+            reference: Set(reference),
+            expires_at: Set(expires_at),
+            created_by: Set(actor),
+            created_at: Set(chrono::Utc::now()),
+            resolved_at: Set(None),
         }
-        if let Some(quantity) = request.quantity {
-            active_product.quantity = Set(quantity);
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok((updated_product, reservation))
+    }
+
+    async fn find_reservation(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<stock_reservation::Model>, AppError> {
+        Ok(StockReservation::find_by_id(id).one(&self.db).await?)
+    }
+
+    //  **Transactions** - Turn a hold into a sale: quantity and reserved_quantity drop
+    // together and the sale is booked in the stock ledger.
+    async fn confirm_reservation(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<
+        (
+            stock_reservation::Model,
+            product::Model,
+            stock_movement::Model,
+        ),
+        AppError,
+    > {
+        let txn = self.db.begin().await?;
+        let now = chrono::Utc::now();
+
+        // Reservation before product, the same lock order as the expiry sweep
+        let reservation = lock_active_reservation(&txn, id).await?;
+        if !reservation.is_holding(now) {
+            // Give the hold back right away instead of waiting for the sweep
+            resolve_reservation(&txn, reservation, ReservationStatus::Expired).await?;
+            txn.commit().await?;
+            return Err(conflict_error(
+                "Reservation",
+                &format!("Reservation {id} has expired"),
+            ));
         }
-        if let Some(category) = request.category {
-            active_product.category = Set(Some(category));
+
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(reservation.product_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(reservation.product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        // Stock may have been adjusted below the held amount since the reservation
+        let quantity_after = product.quantity - reservation.quantity;
+        if quantity_after < 0 {
+            return Err(business_rule_error(
+                "non_negative_stock",
+                &format!(
+                    "Cannot confirm {} of '{}': only {} in stock",
+                    reservation.quantity, product.name, product.quantity
+                ),
+            ));
         }
-        active_product.updated_at = Set(chrono::Utc::now());
 
-        let updated_product = active_product.update(&self.db).await?;
-        Ok(updated_product)
+        let mut active_product: product::ActiveModel = product.clone().into();
+        active_product.quantity = Set(quantity_after);
+        active_product.reserved_quantity =
+            Set((product.reserved_quantity - reservation.quantity).max(0));
+        stamp_change(&mut active_product, &product, actor);
+        let updated_product = active_product.update(&txn).await?;
+
+        let movement = NewStockMovement {
+            reason: StockMovementReason::Sale,
+            quantity_delta: -reservation.quantity,
+            reference: reservation.reference.clone(),
+            note: Some(format!("Reservation {id} confirmed")),
+        };
+        let movement = record_movement(&txn, product.id, movement, quantity_after, actor).await?;
+        record_change(
+            &txn,
+            product.id,
+            HistoryAction::Update,
+            actor,
+            diff_products(Some(&product), Some(&updated_product)),
+        )
+        .await?;
+        record_product_event(&txn, WebhookEventType::ProductUpdated, &updated_product).await?;
+        record_stock_level(&txn, &product, &updated_product).await?;
+
+        let mut active_reservation: stock_reservation::ActiveModel = reservation.into();
+        active_reservation.status = Set(ReservationStatus::Confirmed);
+        active_reservation.resolved_at = Set(Some(now));
+        let reservation = active_reservation.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok((reservation, updated_product, movement))
+    }
+
+    async fn release_reservation(&self, id: Uuid) -> Result<stock_reservation::Model, AppError> {
+        let txn = self.db.begin().await?;
+        let reservation = lock_active_reservation(&txn, id).await?;
+        let reservation =
+            resolve_reservation(&txn, reservation, ReservationStatus::Released).await?;
+        txn.commit().await?;
+
+        Ok(reservation)
     }
 
-    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
-        let result = Product::delete_by_id(id).exec(&self.db).await?;
-        Ok(result.rows_affected > 0)
+    //  **Raw SQL Integration** - Expire every stale hold in one statement. SKIP LOCKED
+    // leaves reservations that are being confirmed or released right now to that request.
+    async fn expire_reservations(&self) -> Result<u64, AppError> {
+        let expire_query = Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+            WITH expired AS (
+                UPDATE stock_reservations
+                SET status = 'expired', resolved_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM stock_reservations
+                    WHERE status = 'active' AND expires_at <= NOW()
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING product_id, quantity
+            ),
+            released AS (
+                UPDATE products p
+                SET reserved_quantity = GREATEST(p.reserved_quantity - t.quantity, 0)
+                FROM (
+                    SELECT product_id, SUM(quantity) AS quantity
+                    FROM expired
+                    GROUP BY product_id
+                ) t
+                WHERE p.id = t.product_id
+            )
+            SELECT COUNT(*) AS expired FROM expired
+            "#,
+            [],
+        );
+
+        let result = ExpiredReservationsRaw::find_by_statement(expire_query)
+            .one(&self.db)
+            .await?
+            .map(|row| row.expired as u64)
+            .unwrap_or(0);
+
+        Ok(result)
     }
 
     //  **Dynamic Query Building** + **Complex WHERE Clauses** + **Range Queries** + **Text Search** + **Pagination & Sorting**
@@ -176,86 +1336,158 @@ impl ProductRepositoryTrait for ProductRepository {
     async fn search(
         &self,
         search_request: ProductSearchRequest,
-    ) -> Result<(Vec<product::Model>, u64), AppError> {
-        let page = search_request.page.unwrap_or(1);
-        let per_page = search_request.per_page.unwrap_or(10).min(100);
-
-        //  **Dynamic Query Building** - Start with base query and conditionally add filters
-        let mut query = Product::find();
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductPage, AppError> {
+        let (query, ts_query) = build_search_query(&search_request, conversion)?;
 
-        //  **Text Search** - LIKE/ILIKE pattern matching with OR conditions
-        //  **Complex WHERE Clauses** - Multiple conditions with AND/OR logic
-        if let Some(search_text) = &search_request.query {
-            let search_pattern = format!("%{search_text}%");
-            query = query.filter(
-                // OR condition: search in both name AND description
-                product::Column::Name
-                    .contains(&search_pattern)
-                    .or(product::Column::Description.contains(&search_pattern)),
-            );
-        }
+        //  **Pagination & Sorting** - Dynamic sorting based on user input, paged by
+        // offset or by keyset cursor on (sort column, id)
+        let sort_order = search_request.sort_order.as_deref();
+        let sort = match (search_request.sort_by.as_deref(), &ts_query) {
+            (Some("relevance"), Some(ts_query)) => {
+                ProductSort::relevance(ts_query.clone(), sort_order)
+            }
+            (Some("relevance"), None) => {
+                return Err(validation_error(
+                    "sort_by",
+                    "Sorting by relevance requires a query with search_mode=fulltext",
+                ))
+            }
+            (sort_by, _) => ProductSort::from_params(sort_by, sort_order).in_currency(conversion),
+        };
 
-        //  **Dynamic Query Building** - Conditional filters based on request parameters
-        if let Some(name) = &search_request.name {
-            query = query.filter(product::Column::Name.eq(name));
-        }
+        let page = PageRequest {
+            page: search_request.page,
+            per_page: search_request.per_page,
+            cursor: search_request.cursor.as_deref(),
+            include_total: search_request.include_total,
+        };
 
-        if let Some(category) = &search_request.category {
-            query = query.filter(product::Column::Category.eq(category));
-        }
+        self.fetch_page(query, &sort, page, ts_query.as_deref())
+            .await
+    }
 
-        //  **Range Queries** - Greater than/equal, less than/equal operations
-        if let Some(min_price) = search_request.min_price {
-            query = query.filter(product::Column::Price.gte(min_price));
-        }
-        if let Some(max_price) = search_request.max_price {
-            query = query.filter(product::Column::Price.lte(max_price));
-        }
+    //  **Aggregations** + **Custom Result Mapping** - Facet counts for a search.
+    // Each aggregation starts from the filtered search query (without paging or sorting),
+    // so the counts always describe exactly the rows the search can return.
+    async fn search_facets(
+        &self,
+        search_request: ProductSearchRequest,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductSearchFacets, AppError> {
+        let edges = parse_price_buckets(search_request.price_buckets.as_deref())?;
+        let (query, _) = build_search_query(&search_request, conversion)?;
 
-        //  **Range Queries** - Quantity range filtering
-        if let Some(min_quantity) = search_request.min_quantity {
-            query = query.filter(product::Column::Quantity.gte(min_quantity));
-        }
-        if let Some(max_quantity) = search_request.max_quantity {
-            query = query.filter(product::Column::Quantity.lte(max_quantity));
-        }
+        //  **Aggregations** - COUNT ... GROUP BY category
+        let categories: Vec<CategoryFacetRaw> = query
+            .clone()
+            .select_only()
+            .column(product::Column::CategoryId)
+            .column(product::Column::Category)
+            .column_as(Expr::col((Product, product::Column::Id)).count(), "count")
+            .group_by(product::Column::CategoryId)
+            .group_by(product::Column::Category)
+            .order_by(Expr::cust("count"), Order::Desc)
+            .order_by_asc(product::Column::Category)
+            .into_model::<CategoryFacetRaw>()
+            .all(&self.db)
+            .await?;
 
-        //  **Complex WHERE Clauses** - Conditional logic for stock status
-        if let Some(in_stock) = search_request.in_stock {
-            if in_stock {
-                query = query.filter(product::Column::Quantity.gt(0)); // In stock
-            } else {
-                query = query.filter(product::Column::Quantity.eq(0)); // Out of stock
-            }
-        }
+        //  **Aggregations** - width_bucket() histogram over the (converted) effective
+        // price; bucket i covers [edges[i-1], edges[i])
+        let edge_list = edges
+            .iter()
+            .map(|edge| edge.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let buckets: Vec<PriceBucketRaw> = query
+            .clone()
+            .select_only()
+            .column_as(
+                Expr::cust_with_values(
+                    format!(
+                        "width_bucket({}, $1::numeric[])",
+                        listed_price_sql("products", conversion)
+                    ),
+                    [format!("{{{edge_list}}}")],
+                ),
+                "bucket",
+            )
+            .column_as(Expr::col((Product, product::Column::Id)).count(), "count")
+            .group_by(Expr::cust("bucket"))
+            .into_model::<PriceBucketRaw>()
+            .all(&self.db)
+            .await?;
 
-        //  **Pagination & Sorting** - Dynamic sorting based on user input
-        query = match (
-            search_request.sort_by.as_deref(),
-            search_request.sort_order.as_deref(),
-        ) {
-            (Some("name"), Some("desc")) => query.order_by_desc(product::Column::Name),
-            (Some("name"), _) => query.order_by_asc(product::Column::Name),
-            (Some("price"), Some("desc")) => query.order_by_desc(product::Column::Price),
-            (Some("price"), _) => query.order_by_asc(product::Column::Price),
-            (Some("quantity"), Some("desc")) => query.order_by_desc(product::Column::Quantity),
-            (Some("quantity"), _) => query.order_by_asc(product::Column::Quantity),
-            (Some("created_at"), Some("asc")) => query.order_by_asc(product::Column::CreatedAt),
-            _ => query.order_by_desc(product::Column::CreatedAt), // Default sorting
-        };
+        //  **Aggregations** - Conditional counts with FILTER
+        let stock = query
+            .select_only()
+            .column_as(
+                Expr::cust(format!("COUNT(*) FILTER (WHERE {AVAILABLE_QUANTITY} > 0)")),
+                "in_stock",
+            )
+            .column_as(
+                Expr::cust(format!("COUNT(*) FILTER (WHERE {AVAILABLE_QUANTITY} <= 0)")),
+                "out_of_stock",
+            )
+            .into_model::<StockFacetRaw>()
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::InternalServerError {
+                context: Some("Database returned None".to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
 
-        //  **Pagination & Sorting** - Apply pagination to the dynamically built query
-        let paginator = query.paginate(&self.db, per_page);
-        let total = paginator.num_items().await?;
-        let products = paginator.fetch_page(page.saturating_sub(1)).await?;
+        // Report every bucket, including empty ones, so the UI can draw a full histogram
+        let counts: HashMap<i32, i64> = buckets
+            .into_iter()
+            .map(|bucket| (bucket.bucket, bucket.count))
+            .collect();
+        let price_buckets = (0..=edges.len())
+            .map(|index| PriceBucketFacet {
+                min: index.checked_sub(1).map(|i| edges[i]),
+                max: edges.get(index).copied(),
+                count: counts.get(&(index as i32)).copied().unwrap_or(0) as u64,
+            })
+            // The open bucket below the first edge only shows up when it has products
+            .filter(|bucket| bucket.min.is_some() || bucket.count > 0)
+            .collect();
 
-        Ok((products, total))
+        Ok(ProductSearchFacets {
+            categories: categories
+                .into_iter()
+                .map(|facet| CategoryFacet {
+                    category_id: facet.category_id,
+                    category: facet.category,
+                    count: facet.count as u64,
+                })
+                .collect(),
+            price_buckets,
+            stock: StockFacet {
+                in_stock: stock.in_stock as u64,
+                out_of_stock: stock.out_of_stock as u64,
+            },
+        })
     }
 
-    //  **Complex WHERE Clauses** - Simple category filter with sorting
-    async fn find_by_category(&self, category: &str) -> Result<Vec<product::Model>, AppError> {
-        let products = Product::find()
-            .filter(product::Column::Category.eq(category))
+    //  **Complex WHERE Clauses** - Category filter with sorting, optionally over the
+    // whole subtree below the category
+    async fn find_by_category(
+        &self,
+        category: &str,
+        include_descendants: bool,
+    ) -> Result<Vec<product::Model>, AppError> {
+        let Some(category) = find_category_by_slug(&self.db, &slugify(category)).await? else {
+            return Ok(Vec::new());
+        };
+        let category_ids = if include_descendants {
+            category_subtree_ids(&self.db, category.id).await?
+        } else {
+            vec![category.id]
+        };
+
+        let products = Product::find_active()
+            .filter(product::Column::CategoryId.is_in(category_ids))
             .order_by_desc(product::Column::CreatedAt)
             .all(&self.db)
             .await?;
@@ -263,15 +1495,20 @@ impl ProductRepositoryTrait for ProductRepository {
         Ok(products)
     }
 
-    //  **Range Queries** - BETWEEN operation for price range
+    //  **Range Queries** - BETWEEN operation on the (converted) effective price
     async fn find_by_price_range(
         &self,
         min_price: Decimal,
         max_price: Decimal,
+        conversion: &CurrencyConversion,
     ) -> Result<Vec<product::Model>, AppError> {
-        let products = Product::find()
-            .filter(product::Column::Price.between(min_price, max_price)) // BETWEEN clause
-            .order_by_asc(product::Column::Price) // Sort by price ascending
+        let effective_price = listed_price_sql("products", conversion);
+        let products = Product::find_active()
+            .filter(Expr::cust_with_values(
+                format!("{effective_price} BETWEEN $1 AND $2"),
+                [min_price, max_price],
+            )) // BETWEEN clause
+            .order_by_asc(Expr::cust(effective_price)) // Sort by price ascending
             .all(&self.db)
             .await?;
 
@@ -280,7 +1517,7 @@ impl ProductRepositoryTrait for ProductRepository {
 
     //  **Range Queries** + **Complex WHERE Clauses** - Multiple conditions with AND logic
     async fn find_low_stock(&self, threshold: i32) -> Result<Vec<product::Model>, AppError> {
-        let products = Product::find()
+        let products = Product::find_active()
             .filter(product::Column::Quantity.lte(threshold)) // Less than or equal to threshold
             .filter(product::Column::Quantity.gt(0)) // AND greater than 0 (exclude out of stock)
             .order_by_asc(product::Column::Quantity) // Sort by quantity ascending
@@ -292,18 +1529,31 @@ impl ProductRepositoryTrait for ProductRepository {
 
     //  **Raw SQL Integration** + **Aggregations** + **Custom Result Mapping**
     // Complex analytics using raw SQL for operations not easily expressed in SeaORM query builder
-    async fn get_product_stats(&self) -> Result<ProductStatsResponse, AppError> {
+    async fn get_product_stats(
+        &self,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductStatsResponse, AppError> {
         //  **Raw SQL Integration** - Custom SQL for complex aggregations
         //  **Aggregations** - COUNT, SUM, AVG operations
+        //  **Joins** - Variant stock is valued through the variant_totals CTE
+        let effective_price = effective_price_sql("p");
+        // Amounts are converted per product and the totals rounded once at the end
+        let fx = conversion.factor_sql("p.currency");
         let stats_query = Statement::from_sql_and_values(
             DatabaseBackend::Postgres,
-            r#"
+            format!(
+                r#"WITH {VARIANT_TOTALS_CTE}
             SELECT 
                 COUNT(*) as total_products,                    -- COUNT aggregation
-                COALESCE(SUM(price * quantity), 0) as total_value,  -- SUM with calculation
-                AVG(price) as avg_price                        -- AVG aggregation
-            FROM products
-            "#,
+                COALESCE(SUM((p.price * p.quantity + COALESCE(vt.variant_value, 0)) * {fx}), 0) as total_value,  -- SUM with calculation
+                AVG({effective_price} * {fx}) as avg_price,  -- AVG aggregation over current prices
+                COALESCE(SUM(vt.variant_count), 0)::BIGINT as total_variants,
+                COALESCE(SUM(vt.variant_value * {fx}), 0) as variant_value
+            FROM products p
+            LEFT JOIN variant_totals vt ON vt.product_id = p.id
+            WHERE p.deleted_at IS NULL                         -- Skip soft-deleted products
+            "#
+            ),
             [], // No parameters for this query
         );
 
@@ -316,19 +1566,28 @@ impl ProductRepositoryTrait for ProductRepository {
                 error_id: uuid::Uuid::new_v4(),
             })?;
 
-        //  **Raw SQL Integration** + **Aggregations** - Category-wise statistics
+        //  **Raw SQL Integration** + **Aggregations** - Category-wise statistics,
+        // each category rolled up over its subtree through the category_tree CTE
         let category_stats_query = Statement::from_sql_and_values(
             DatabaseBackend::Postgres,
-            r#"
+            format!(
+                r#"WITH RECURSIVE {CATEGORY_TREE_CTE}, {VARIANT_TOTALS_CTE}
             SELECT 
-                category,
-                COUNT(*) as count,                             -- COUNT by category
-                COALESCE(SUM(price * quantity), 0) as total_value    -- SUM by category
-            FROM products 
-            WHERE category IS NOT NULL
-            GROUP BY category                                  -- GROUP BY for aggregation
+                c.id as category_id,
+                c.name as category,
+                c.slug,
+                c.parent_id,
+                COUNT(*) as count,                             -- COUNT by category subtree
+                COALESCE(SUM((p.price * p.quantity + COALESCE(vt.variant_value, 0)) * {fx}), 0) as total_value    -- SUM by category subtree
+            FROM category_tree t
+            JOIN categories c ON c.id = t.root_id
+            JOIN products p ON p.category_id = t.category_id
+            LEFT JOIN variant_totals vt ON vt.product_id = p.id
+            WHERE p.deleted_at IS NULL
+            GROUP BY c.id, c.name, c.slug, c.parent_id         -- GROUP BY for aggregation
             ORDER BY count DESC                                -- Sort by count
-            "#,
+            "#
+            ),
             [],
         );
 
@@ -339,21 +1598,23 @@ impl ProductRepositoryTrait for ProductRepository {
                 .await?;
 
         // Transform raw results into response model
+        let round = |amount: Decimal| conversion.round(amount);
         let categories = category_results
             .into_iter()
-            .filter_map(|stats| {
-                stats.category.map(|cat| CategoryStats {
-                    category: cat,
-                    count: stats.count as u64,
-                    total_value: stats.total_value,
-                })
+            .map(CategoryStats::from)
+            .map(|stats| CategoryStats {
+                total_value: round(stats.total_value),
+                ..stats
             })
             .collect();
 
         Ok(ProductStatsResponse {
+            currency: conversion.currency().to_string(),
             total_products: stats_result.total_products as u64,
-            total_value: stats_result.total_value,
-            avg_price: stats_result.avg_price,
+            total_value: round(stats_result.total_value),
+            avg_price: stats_result.avg_price.map(round),
+            total_variants: stats_result.total_variants as u64,
+            variant_value: round(stats_result.variant_value),
             categories,
         })
     }
@@ -365,7 +1626,7 @@ impl ProductRepositoryTrait for ProductRepository {
         limit: u64,
     ) -> Result<Vec<product::Model>, AppError> {
         let search_pattern = format!("%{product_name}%"); // Create LIKE pattern
-        let products = Product::find()
+        let products = Product::find_active()
             .filter(product::Column::Name.contains(&search_pattern)) // LIKE/ILIKE operation
             .limit(limit) // Limit results
             .order_by_desc(product::Column::CreatedAt)
@@ -377,23 +1638,36 @@ impl ProductRepositoryTrait for ProductRepository {
 
     //  **Raw SQL Integration** + **Subqueries** + **Aggregations** + **Custom Result Mapping**
     // Advanced query with date filtering, grouping, and complex conditions
-    async fn get_trending_categories(&self, limit: u64) -> Result<Vec<CategoryStats>, AppError> {
+    async fn get_trending_categories(
+        &self,
+        limit: u64,
+        conversion: &CurrencyConversion,
+    ) -> Result<Vec<CategoryStats>, AppError> {
         //  **Raw SQL Integration** + **Subqueries** - Complex query with date filtering
+        // New products count towards their category and all of its ancestors
+        let fx = conversion.factor_sql("p.currency");
         let trending_query = Statement::from_sql_and_values(
             DatabaseBackend::Postgres,
-            r#"
+            format!(
+                r#"WITH RECURSIVE {CATEGORY_TREE_CTE}
             SELECT 
-                category,
+                c.id as category_id,
+                c.name as category,
+                c.slug,
+                c.parent_id,
                 COUNT(*) as count,                             -- COUNT aggregation
-                SUM(price * quantity) as total_value           -- SUM aggregation with calculation
-            FROM products 
-            WHERE category IS NOT NULL                         -- Filter null categories
-                AND created_at >= NOW() - INTERVAL '30 days'  --  **Subqueries** - Date filtering (last 30 days)
-            GROUP BY category                                  -- GROUP BY for category-wise stats
+                SUM(p.price * p.quantity * {fx}) as total_value  -- SUM aggregation with calculation
+            FROM category_tree t
+            JOIN categories c ON c.id = t.root_id
+            JOIN products p ON p.category_id = t.category_id
+            WHERE p.deleted_at IS NULL                         -- Skip soft-deleted products
+                AND p.created_at >= NOW() - INTERVAL '30 days'  --  **Subqueries** - Date filtering (last 30 days)
+            GROUP BY c.id, c.name, c.slug, c.parent_id         -- GROUP BY for category-wise stats
             HAVING COUNT(*) > 0                                -- HAVING clause for post-aggregation filtering
             ORDER BY count DESC, total_value DESC             -- Multiple column sorting
             LIMIT $1                                           -- Dynamic limit parameter
-            "#,
+            "#
+            ),
             [limit.into()], // Parameterized query
         );
 
@@ -404,14 +1678,552 @@ impl ProductRepositoryTrait for ProductRepository {
 
         let categories = results
             .into_iter()
-            .filter_map(|stats| {
-                stats.category.map(|cat| CategoryStats {
-                    category: cat,
-                    count: stats.count as u64,
-                    total_value: stats.total_value,
-                })
+            .map(CategoryStats::from)
+            .map(|stats| CategoryStats {
+                total_value: conversion.round(stats.total_value),
+                ..stats
             })
             .collect();
         Ok(categories)
     }
 }
+
+impl From<CategoryStatsRaw> for CategoryStats {
+    fn from(stats: CategoryStatsRaw) -> Self {
+        Self {
+            category_id: stats.category_id,
+            category: stats.category,
+            slug: stats.slug,
+            parent_id: stats.parent_id,
+            count: stats.count as u64,
+            total_value: stats.total_value,
+        }
+    }
+}
+
+/// Apply every filter of a search request to a base `products` query.
+/// Shared by the paged search and its facet aggregations so both see the same rows.
+/// Also returns the parsed tsquery when the request is a full-text search.
+fn build_search_query(
+    search_request: &ProductSearchRequest,
+    conversion: &CurrencyConversion,
+) -> Result<(Select<Product>, Option<String>), AppError> {
+    //  **Dynamic Query Building** - Start with base query and conditionally add filters
+    let mut query = Product::find_active();
+
+    let full_text = match search_request.search_mode.as_deref() {
+        None | Some("simple") => false,
+        Some("fulltext") => true,
+        Some(_) => {
+            return Err(validation_error(
+                "search_mode",
+                "search_mode must be 'simple' or 'fulltext'",
+            ))
+        }
+    };
+    let mut ts_query = None;
+
+    if let Some(search_text) = &search_request.query {
+        if full_text {
+            //  **Full-Text Search** - tsvector @@ tsquery, served by the GIN index
+            let parsed = build_tsquery(search_text)
+                .ok_or_else(|| validation_error("query", "Query contains no searchable terms"))?;
+            query = query.filter(Expr::cust_with_values(TS_MATCH, [parsed.clone()]));
+            ts_query = Some(parsed);
+        } else {
+            //  **Text Search** - LIKE/ILIKE pattern matching with OR conditions
+            //  **Complex WHERE Clauses** - Multiple conditions with AND/OR logic
+            let search_pattern = format!("%{search_text}%");
+            query = query.filter(
+                // OR condition: search in both name AND description
+                product::Column::Name
+                    .contains(&search_pattern)
+                    .or(product::Column::Description.contains(&search_pattern)),
+            );
+        }
+    }
+
+    //  **Dynamic Query Building** - Conditional filters based on request parameters
+    if let Some(name) = &search_request.name {
+        query = query.filter(product::Column::Name.eq(name));
+    }
+
+    //  **Subqueries** - Category by slug, so "Electronics" and "electronics " match alike
+    if let Some(category) = &search_request.category {
+        query = query.filter(Expr::cust_with_values(
+            "category_id IN (SELECT id FROM categories WHERE slug = $1)",
+            [slugify(category)],
+        ));
+    }
+
+    //  **Range Queries** - Greater than/equal, less than/equal on the effective price,
+    // converted first when the bounds are in another currency
+    if let Some(min_price) = search_request.min_price {
+        query = query.filter(Expr::cust_with_values(
+            format!("{} >= $1", listed_price_sql("products", conversion)),
+            [min_price],
+        ));
+    }
+    if let Some(max_price) = search_request.max_price {
+        query = query.filter(Expr::cust_with_values(
+            format!("{} <= $1", listed_price_sql("products", conversion)),
+            [max_price],
+        ));
+    }
+
+    //  **Range Queries** - Quantity range filtering
+    if let Some(min_quantity) = search_request.min_quantity {
+        query = query.filter(product::Column::Quantity.gte(min_quantity));
+    }
+    if let Some(max_quantity) = search_request.max_quantity {
+        query = query.filter(product::Column::Quantity.lte(max_quantity));
+    }
+
+    //  **Complex WHERE Clauses** - Conditional logic for stock status, based on
+    // available-to-sell stock so fully reserved products count as out of stock
+    if let Some(in_stock) = search_request.in_stock {
+        if in_stock {
+            // In stock
+            query = query.filter(Expr::expr(Expr::cust(AVAILABLE_QUANTITY)).gt(0));
+        } else {
+            // Out of stock
+            query = query.filter(Expr::expr(Expr::cust(AVAILABLE_QUANTITY)).lte(0));
+        }
+    }
+
+    //  **Subqueries** - EXISTS over product_variants; all variant filters must hold
+    // for the same variant
+    let variant_options = search_request
+        .variant_options
+        .as_deref()
+        .map(parse_variant_options)
+        .transpose()?;
+    if search_request.sku.is_some() || variant_options.is_some() {
+        let mut conditions = vec!["v.product_id = products.id".to_string()];
+        let mut values: Vec<sea_orm::Value> = Vec::new();
+        if let Some(sku) = &search_request.sku {
+            values.push(sku.trim().into());
+            conditions.push(format!("v.sku = ${}", values.len()));
+        }
+        if let Some(options) = variant_options {
+            values.push(serde_json::to_string(&options).unwrap_or_default().into());
+            conditions.push(format!("v.options @> ${}::jsonb", values.len()));
+        }
+        if search_request.in_stock == Some(true) {
+            conditions.push("v.quantity > 0".to_string());
+        }
+        query = query.filter(Expr::cust_with_values(
+            format!(
+                "EXISTS (SELECT 1 FROM product_variants v WHERE {})",
+                conditions.join(" AND ")
+            ),
+            values,
+        ));
+    }
+
+    Ok((query, ts_query))
+}
+
+/// Parse `variant_options` ("size:M,colour:red") into normalized option pairs
+pub fn parse_variant_options(raw: &str) -> Result<BTreeMap<String, String>, AppError> {
+    let mut options = BTreeMap::new();
+    for pair in raw.split(',').filter(|pair| !pair.trim().is_empty()) {
+        let (name, value) = pair
+            .split_once(':')
+            .filter(|(name, value)| !name.trim().is_empty() && !value.trim().is_empty())
+            .ok_or_else(|| {
+                validation_error(
+                    "variant_options",
+                    "Variant options must be name:value pairs, e.g. size:M,colour:red",
+                )
+            })?;
+        options.insert(name.to_string(), value.to_string());
+    }
+
+    if options.is_empty() {
+        return Err(validation_error(
+            "variant_options",
+            "Provide at least one name:value pair",
+        ));
+    }
+
+    Ok(normalize_options(options))
+}
+
+/// Parse `price_buckets` ("0,50,100") into strictly ascending histogram edges
+pub fn parse_price_buckets(raw: Option<&str>) -> Result<Vec<Decimal>, AppError> {
+    let Some(raw) = raw else {
+        return Ok(DEFAULT_PRICE_BUCKETS
+            .iter()
+            .copied()
+            .map(Decimal::from)
+            .collect());
+    };
+
+    let edges = raw
+        .split(',')
+        .map(|edge| Decimal::from_str(edge.trim()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| validation_error("price_buckets", "Price bucket edges must be numbers"))?;
+
+    if edges.is_empty() || edges.len() > MAX_PRICE_BUCKETS {
+        return Err(validation_error(
+            "price_buckets",
+            &format!("Provide between 1 and {MAX_PRICE_BUCKETS} price bucket edges"),
+        ));
+    }
+    if edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(validation_error(
+            "price_buckets",
+            "Price bucket edges must be strictly ascending",
+        ));
+    }
+
+    Ok(edges)
+}
+
+/// Effective price of the `products` row `table`, converted and rounded exactly like
+/// the prices in converted responses, so filters and buckets match what is shown
+fn listed_price_sql(table: &str, conversion: &CurrencyConversion) -> String {
+    conversion.amount_sql(&effective_price_sql(table), &format!("{table}.currency"))
+}
+
+/// Insert a product together with its opening stock movement and `create` history
+/// entry on the same connection
+async fn insert_product<C: ConnectionTrait>(
+    db: &C,
+    request: CreateProductRequest,
+) -> Result<product::Model, AppError> {
+    let category = resolve_category(db, request.category_id, request.category.as_deref()).await?;
+    ensure_identifiers_free(db, request.sku.as_deref(), request.barcode.as_deref(), None).await?;
+    if let Some(currency) = &request.currency {
+        ensure_currency_supported(db, currency).await?;
+    }
+    let mut active_product = new_product_model(request);
+    active_product.category_id = Set(category.as_ref().map(|category| category.id));
+    active_product.category = Set(category.map(|category| category.name));
+
+    let product = active_product
+        .insert(db)
+        .await
+        .map_err(identifier_conflict)?;
+    if product.quantity > 0 {
+        let movement = NewStockMovement {
+            reason: StockMovementReason::Adjustment,
+            quantity_delta: product.quantity,
+            reference: None,
+            note: Some("Opening balance".to_string()),
+        };
+        record_movement(
+            db,
+            product.id,
+            movement,
+            product.quantity,
+            product.created_by,
+        )
+        .await?;
+    }
+    record_change(
+        db,
+        product.id,
+        HistoryAction::Create,
+        product.created_by,
+        diff_products(None, Some(&product)),
+    )
+    .await?;
+    record_product_event(db, WebhookEventType::ProductCreated, &product).await?;
+    Ok(product)
+}
+
+/// Version-checked update of a live product, with its stock movement and
+/// `update` history entry, on the same connection
+async fn update_product<C: ConnectionTrait>(
+    db: &C,
+    id: Uuid,
+    request: UpdateProductRequest,
+    if_match: Option<IfMatch>,
+    actor: Option<Uuid>,
+) -> Result<product::Model, AppError> {
+    let product = Product::find_active()
+        .filter(product::Column::Id.eq(id))
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(AppError::NotFound {
+            resource_type: "Product".to_string(),
+            resource_id: Some(id.to_string()),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+    if let Some(if_match) = &if_match {
+        if !if_match.matches(product.version) {
+            return Err(AppError::precondition_failed(
+                "Product".to_string(),
+                Some(id.to_string()),
+                Some(version_etag(product.version)),
+            ));
+        }
+    }
+
+    let mut active_product: product::ActiveModel = product.clone().into();
+
+    if let Some(name) = request.name {
+        active_product.name = Set(name);
+    }
+    if let Some(description) = request.description {
+        active_product.description = Set(Some(description));
+    }
+    if let Some(price) = request.price {
+        active_product.price = Set(price);
+    }
+    if let Some(currency) = request.currency {
+        ensure_currency_supported(db, &currency).await?;
+        active_product.currency = Set(currency);
+    }
+    // Only identifiers that actually change need to be free
+    let sku = request
+        .sku
+        .filter(|sku| product.sku.as_deref() != Some(sku.as_str()));
+    let barcode = request
+        .barcode
+        .filter(|barcode| product.barcode.as_deref() != Some(barcode.as_str()));
+    ensure_identifiers_free(db, sku.as_deref(), barcode.as_deref(), Some(id)).await?;
+    if let Some(sku) = sku {
+        active_product.sku = Set(Some(sku));
+    }
+    if let Some(barcode) = barcode {
+        active_product.barcode = Set(Some(barcode));
+    }
+    if let Some(category) =
+        resolve_category(db, request.category_id, request.category.as_deref()).await?
+    {
+        active_product.category_id = Set(Some(category.id));
+        active_product.category = Set(Some(category.name));
+    }
+    stamp_change(&mut active_product, &product, actor);
+
+    let updated_product = active_product
+        .update(db)
+        .await
+        .map_err(identifier_conflict)?;
+    record_change(
+        db,
+        id,
+        HistoryAction::Update,
+        actor,
+        diff_products(Some(&product), Some(&updated_product)),
+    )
+    .await?;
+    record_product_event(db, WebhookEventType::ProductUpdated, &updated_product).await?;
+    Ok(updated_product)
+}
+
+/// Lock a batch's rows up front, sorted by id, so two batches touching the same
+/// products wait on each other instead of deadlocking; items then run in request order
+async fn lock_in_id_order<C: ConnectionTrait>(db: &C, ids: &[Uuid]) -> Result<(), AppError> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+
+    Product::find_active()
+        .select_only()
+        .column(product::Column::Id)
+        .filter(product::Column::Id.is_in(ids))
+        .order_by_asc(product::Column::Id)
+        .lock_exclusive()
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await?;
+    Ok(())
+}
+
+/// Move a live product to the trash on the same connection. Returns false if
+/// there is no live product with that id.
+async fn soft_delete_product<C: ConnectionTrait>(
+    db: &C,
+    id: Uuid,
+    actor: Option<Uuid>,
+) -> Result<bool, AppError> {
+    let Some(product) = Product::find_active()
+        .filter(product::Column::Id.eq(id))
+        .lock_exclusive()
+        .one(db)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    let mut active_product: product::ActiveModel = product.clone().into();
+    active_product.deleted_at = Set(Some(chrono::Utc::now()));
+    stamp_change(&mut active_product, &product, actor);
+
+    let deleted_product = active_product.update(db).await?;
+    // The blobs stay while the product can still be restored; purge deletes them
+    ProductImage::update_many()
+        .col_expr(
+            product_image::Column::OrphanedAt,
+            Expr::value(deleted_product.deleted_at),
+        )
+        .filter(product_image::Column::ProductId.eq(id))
+        .filter(product_image::Column::OrphanedAt.is_null())
+        .exec(db)
+        .await?;
+    record_change(
+        db,
+        id,
+        HistoryAction::Delete,
+        actor,
+        diff_products(Some(&product), Some(&deleted_product)),
+    )
+    .await?;
+    record_product_event(db, WebhookEventType::ProductDeleted, &deleted_product).await?;
+    Ok(true)
+}
+
+/// Reject a SKU or barcode already used by another live product
+async fn ensure_identifiers_free<C: ConnectionTrait>(
+    db: &C,
+    sku: Option<&str>,
+    barcode: Option<&str>,
+    except: Option<Uuid>,
+) -> Result<(), AppError> {
+    let others = || match except {
+        Some(id) => Product::find_active().filter(product::Column::Id.ne(id)),
+        None => Product::find_active(),
+    };
+
+    if let Some(sku) = sku {
+        let taken = others()
+            .filter(product::Column::Sku.eq(sku))
+            .count(db)
+            .await?;
+        if taken > 0 {
+            return Err(field_conflict_error(
+                "Product",
+                "sku",
+                &format!("SKU '{sku}' is already in use"),
+            ));
+        }
+    }
+    if let Some(barcode) = barcode {
+        let taken = others()
+            .filter(Expr::cust_with_values(
+                BARCODE_KEY_MATCH,
+                [normalize_barcode(barcode)],
+            ))
+            .count(db)
+            .await?;
+        if taken > 0 {
+            return Err(field_conflict_error(
+                "Product",
+                "barcode",
+                &format!("Barcode '{barcode}' is already in use"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A concurrent writer can still win the race past `ensure_identifiers_free`;
+/// its unique violation becomes the same field conflict
+fn identifier_conflict(err: DbErr) -> AppError {
+    if let Some(SqlErr::UniqueConstraintViolation(message)) = err.sql_err() {
+        if message.contains(SKU_UNIQUE_INDEX) {
+            return field_conflict_error("Product", "sku", "SKU is already in use");
+        }
+        if message.contains(BARCODE_UNIQUE_INDEX) {
+            return field_conflict_error("Product", "barcode", "Barcode is already in use");
+        }
+    }
+    err.into()
+}
+
+/// Lock a reservation for a state change; only active reservations can change state
+async fn lock_active_reservation<C: ConnectionTrait>(
+    db: &C,
+    id: Uuid,
+) -> Result<stock_reservation::Model, AppError> {
+    let reservation = StockReservation::find_by_id(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(AppError::NotFound {
+            resource_type: "Reservation".to_string(),
+            resource_id: Some(id.to_string()),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+    if reservation.status != ReservationStatus::Active {
+        return Err(conflict_error(
+            "Reservation",
+            &format!(
+                "Reservation {id} is already {}",
+                reservation.status.to_value()
+            ),
+        ));
+    }
+
+    Ok(reservation)
+}
+
+/// Close an active reservation without selling it and give its units back
+async fn resolve_reservation<C: ConnectionTrait>(
+    db: &C,
+    reservation: stock_reservation::Model,
+    status: ReservationStatus,
+) -> Result<stock_reservation::Model, DbErr> {
+    Product::update_many()
+        .col_expr(
+            product::Column::ReservedQuantity,
+            Expr::cust_with_values(
+                "GREATEST(reserved_quantity - $1, 0)",
+                [reservation.quantity],
+            ),
+        )
+        .filter(product::Column::Id.eq(reservation.product_id))
+        .exec(db)
+        .await?;
+
+    let mut active_reservation: stock_reservation::ActiveModel = reservation.into();
+    active_reservation.status = Set(status);
+    active_reservation.resolved_at = Set(Some(chrono::Utc::now()));
+    active_reservation.update(db).await
+}
+
+/// Bookkeeping shared by every write to an existing product
+fn stamp_change(
+    active_product: &mut product::ActiveModel,
+    current: &product::Model,
+    actor: Option<Uuid>,
+) {
+    active_product.updated_at = Set(chrono::Utc::now());
+    active_product.version = Set(current.version + 1);
+    if actor.is_some() {
+        active_product.updated_by = Set(actor);
+    }
+}
+
+fn new_product_model(request: CreateProductRequest) -> product::ActiveModel {
+    let now = chrono::Utc::now();
+
+    product::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(request.name),
+        description: Set(request.description),
+        price: Set(request.price),
+        // Callers fill in BASE_CURRENCY; the column default only covers direct inserts
+        currency: request.currency.map(Set).unwrap_or(NotSet),
+        quantity: Set(request.quantity),
+        reserved_quantity: Set(0),
+        category: Set(request.category),
+        category_id: Set(request.category_id),
+        sku: Set(request.sku),
+        barcode: Set(request.barcode),
+        created_by: Set(request.created_by),
+        updated_by: Set(request.updated_by),
+        created_at: Set(now),
+        updated_at: Set(now),
+        version: Set(1),
+        deleted_at: Set(None),
+    }
+}
@@ -0,0 +1,75 @@
+use crate::entities::{
+    product,
+    product_history::{self, HistoryAction},
+};
+use sea_orm::{prelude::*, ActiveModelTrait, Set};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// Product fields whose changes are recorded in `product_history`
+pub const AUDITED_FIELDS: [&str; 9] = [
+    "name",
+    "description",
+    "price",
+    "currency",
+    "quantity",
+    "category",
+    "sku",
+    "barcode",
+    "deleted_at",
+];
+
+/// Field-level diff between two versions of a product. `None` stands for "no
+/// row" (before a create, after a purge) so every field shows up with a null side.
+pub fn diff_products(old: Option<&product::Model>, new: Option<&product::Model>) -> Value {
+    let to_json = |model: Option<&product::Model>| {
+        model
+            .and_then(|model| serde_json::to_value(model).ok())
+            .unwrap_or(Value::Null)
+    };
+    let (old, new) = (to_json(old), to_json(new));
+
+    let changes: Map<String, Value> = AUDITED_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| {
+                (
+                    field.to_string(),
+                    json!({ "old": old_value, "new": new_value }),
+                )
+            })
+        })
+        .collect();
+
+    Value::Object(changes)
+}
+
+/// Append a history row on `db`, which is normally the transaction that made
+/// the change so the product and its audit trail commit or roll back together.
+/// Nothing is written when no audited field changed.
+pub async fn record_change<C: ConnectionTrait>(
+    db: &C,
+    product_id: Uuid,
+    action: HistoryAction,
+    changed_by: Option<Uuid>,
+    changes: Value,
+) -> Result<(), DbErr> {
+    if changes.as_object().is_some_and(Map::is_empty) {
+        return Ok(());
+    }
+
+    product_history::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        product_id: Set(product_id),
+        action: Set(action),
+        changed_by: Set(changed_by),
+        changed_at: Set(chrono::Utc::now()),
+        changes: Set(changes),
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
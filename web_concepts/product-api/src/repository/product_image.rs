@@ -0,0 +1,237 @@
+use crate::{
+    entities::{prelude::*, product, product_image},
+    error::{business_rule_error, validation_error, AppError},
+    models::MAX_IMAGES_PER_PRODUCT,
+};
+use async_trait::async_trait;
+use sea_orm::{prelude::*, ActiveModelTrait, QueryOrder, QuerySelect, Set, TransactionTrait};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// An image whose blob has already been stored
+#[derive(Debug, Clone)]
+pub struct NewProductImage {
+    pub id: Uuid,
+    pub blob_key: String,
+    pub url: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+}
+
+#[async_trait]
+pub trait ProductImageRepositoryTrait {
+    /// The live parent product, `None` if it does not exist or is in the trash
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError>;
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<product_image::Model>, AppError>;
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        image_id: Uuid,
+    ) -> Result<Option<product_image::Model>, AppError>;
+    /// Append images after the product's current last image, within
+    /// `MAX_IMAGES_PER_PRODUCT`
+    async fn create_many(
+        &self,
+        product_id: Uuid,
+        images: Vec<NewProductImage>,
+        actor: Option<Uuid>,
+    ) -> Result<Vec<product_image::Model>, AppError>;
+    async fn reorder(
+        &self,
+        product_id: Uuid,
+        image_ids: Vec<Uuid>,
+    ) -> Result<Vec<product_image::Model>, AppError>;
+    async fn delete(&self, image: product_image::Model) -> Result<(), AppError>;
+    /// Blob keys of every image of a product, orphaned ones included
+    async fn find_blob_keys(&self, product_id: Uuid) -> Result<Vec<String>, AppError>;
+}
+
+/// Reject adding `adding` images to a product that already has `existing`
+pub fn ensure_image_capacity(existing: usize, adding: usize) -> Result<(), AppError> {
+    if existing + adding > MAX_IMAGES_PER_PRODUCT {
+        return Err(business_rule_error(
+            "max_images_per_product",
+            &format!(
+                "A product can have at most {MAX_IMAGES_PER_PRODUCT} images; it has {existing}"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct ProductImageRepository {
+    db: DatabaseConnection,
+}
+
+impl ProductImageRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ProductImageRepositoryTrait for ProductImageRepository {
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError> {
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(product_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(product)
+    }
+
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<product_image::Model>, AppError> {
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .order_by_asc(product_image::Column::Position)
+            .order_by_asc(product_image::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(images)
+    }
+
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        image_id: Uuid,
+    ) -> Result<Option<product_image::Model>, AppError> {
+        let image = ProductImage::find_by_id(image_id)
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .one(&self.db)
+            .await?;
+
+        Ok(image)
+    }
+
+    //  **Transactions** - The parent row is locked so concurrent uploads can't
+    // hand out the same positions
+    async fn create_many(
+        &self,
+        product_id: Uuid,
+        images: Vec<NewProductImage>,
+        actor: Option<Uuid>,
+    ) -> Result<Vec<product_image::Model>, AppError> {
+        let txn = self.db.begin().await?;
+
+        Product::find_active()
+            .filter(product::Column::Id.eq(product_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        // Counted under the product lock, so concurrent uploads can't overshoot the limit
+        let existing = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .count(&txn)
+            .await?;
+        ensure_image_capacity(existing as usize, images.len())?;
+
+        let last_position: Option<i32> = ProductImage::find()
+            .select_only()
+            .column_as(product_image::Column::Position.max(), "max_position")
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .into_tuple()
+            .one(&txn)
+            .await?
+            .flatten();
+        let first_position = last_position.map_or(0, |position| position + 1);
+
+        let now = chrono::Utc::now();
+        let mut created = Vec::with_capacity(images.len());
+        for (offset, image) in images.into_iter().enumerate() {
+            let model = product_image::ActiveModel {
+                id: Set(image.id),
+                product_id: Set(product_id),
+                blob_key: Set(image.blob_key),
+                url: Set(image.url),
+                content_type: Set(image.content_type),
+                size_bytes: Set(image.size_bytes),
+                position: Set(first_position + offset as i32),
+                orphaned_at: Set(None),
+                created_by: Set(actor),
+                created_at: Set(now),
+            }
+            .insert(&txn)
+            .await?;
+            created.push(model);
+        }
+
+        txn.commit().await?;
+        Ok(created)
+    }
+
+    //  **Transactions** - Rewrite all positions at once; the new order must name
+    // every image of the product exactly once
+    async fn reorder(
+        &self,
+        product_id: Uuid,
+        image_ids: Vec<Uuid>,
+    ) -> Result<Vec<product_image::Model>, AppError> {
+        let txn = self.db.begin().await?;
+
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .filter(product_image::Column::OrphanedAt.is_null())
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+
+        let current: HashSet<Uuid> = images.iter().map(|image| image.id).collect();
+        let requested: HashSet<Uuid> = image_ids.iter().copied().collect();
+        if requested.len() != image_ids.len() || requested != current {
+            return Err(validation_error(
+                "image_ids",
+                "image_ids must list every image of the product exactly once",
+            ));
+        }
+
+        let mut reordered = Vec::with_capacity(images.len());
+        for image in images {
+            let position = image_ids
+                .iter()
+                .position(|id| *id == image.id)
+                .unwrap_or_default() as i32;
+            let mut active_image: product_image::ActiveModel = image.into();
+            active_image.position = Set(position);
+            reordered.push(active_image.update(&txn).await?);
+        }
+
+        txn.commit().await?;
+        reordered.sort_by_key(|image| image.position);
+        Ok(reordered)
+    }
+
+    async fn delete(&self, image: product_image::Model) -> Result<(), AppError> {
+        ProductImage::delete_by_id(image.id).exec(&self.db).await?;
+        Ok(())
+    }
+
+    async fn find_blob_keys(&self, product_id: Uuid) -> Result<Vec<String>, AppError> {
+        let keys = ProductImage::find()
+            .select_only()
+            .column(product_image::Column::BlobKey)
+            .filter(product_image::Column::ProductId.eq(product_id))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(keys)
+    }
+}
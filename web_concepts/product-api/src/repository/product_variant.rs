@@ -0,0 +1,228 @@
+use crate::{
+    entities::{prelude::*, product, product_variant},
+    error::{conflict_error, AppError},
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use sea_orm::{prelude::*, ActiveModelTrait, QueryOrder, Set, SqlErr};
+use uuid::Uuid;
+
+/// Postgres names of the unique constraints on `product_variants`
+const SKU_UNIQUE_CONSTRAINT: &str = "product_variants_sku_key";
+const OPTIONS_UNIQUE_CONSTRAINT: &str = "product_variants_product_id_options_key";
+
+/// Changes to an existing variant; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct VariantChanges {
+    pub sku: Option<String>,
+    pub options: Option<Json>,
+    /// `Some(None)` clears the price override
+    pub price: Option<Option<Decimal>>,
+    pub quantity: Option<i32>,
+}
+
+#[async_trait]
+pub trait ProductVariantRepositoryTrait {
+    /// The live parent product, `None` if it does not exist or is in the trash
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError>;
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<product_variant::Model>, AppError>;
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        variant_id: Uuid,
+    ) -> Result<Option<product_variant::Model>, AppError>;
+    async fn create(
+        &self,
+        product_id: Uuid,
+        sku: String,
+        options: Json,
+        price: Option<Decimal>,
+        quantity: i32,
+    ) -> Result<product_variant::Model, AppError>;
+    async fn update(
+        &self,
+        variant: product_variant::Model,
+        changes: VariantChanges,
+    ) -> Result<product_variant::Model, AppError>;
+    async fn delete(&self, variant: product_variant::Model) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct ProductVariantRepository {
+    db: DatabaseConnection,
+}
+
+impl ProductVariantRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// SKUs are unique across all products; check up front for a readable 409
+    async fn ensure_sku_free(&self, sku: &str, except: Option<Uuid>) -> Result<(), AppError> {
+        let mut query = ProductVariant::find().filter(product_variant::Column::Sku.eq(sku));
+        if let Some(id) = except {
+            query = query.filter(product_variant::Column::Id.ne(id));
+        }
+
+        if query.count(&self.db).await? > 0 {
+            return Err(conflict_error(
+                "ProductVariant",
+                &format!("SKU '{sku}' is already in use"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Two variants of one product can't share the same option combination
+    async fn ensure_options_free(
+        &self,
+        product_id: Uuid,
+        options: &Json,
+        except: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let mut query = ProductVariant::find()
+            .filter(product_variant::Column::ProductId.eq(product_id))
+            .filter(product_variant::Column::Options.eq(options.clone()));
+        if let Some(id) = except {
+            query = query.filter(product_variant::Column::Id.ne(id));
+        }
+
+        if query.count(&self.db).await? > 0 {
+            return Err(conflict_error(
+                "ProductVariant",
+                &format!("A variant with options {options} already exists"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A concurrent writer can still win the race past the checks above; its unique
+/// violation becomes the same conflict
+fn variant_conflict(err: DbErr) -> AppError {
+    if let Some(SqlErr::UniqueConstraintViolation(message)) = err.sql_err() {
+        if message.contains(SKU_UNIQUE_CONSTRAINT) {
+            return conflict_error("ProductVariant", "SKU is already in use");
+        }
+        if message.contains(OPTIONS_UNIQUE_CONSTRAINT) {
+            return conflict_error(
+                "ProductVariant",
+                "A variant with these options already exists",
+            );
+        }
+    }
+    err.into()
+}
+
+#[async_trait]
+impl ProductVariantRepositoryTrait for ProductVariantRepository {
+    async fn find_parent(&self, product_id: Uuid) -> Result<Option<product::Model>, AppError> {
+        let product = Product::find_active()
+            .filter(product::Column::Id.eq(product_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(product)
+    }
+
+    //  **Relations** - Variants through the product's has_many relation
+    async fn find_by_product(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Vec<product_variant::Model>, AppError> {
+        let variants = ProductVariant::find()
+            .filter(product_variant::Column::ProductId.eq(product_id))
+            .order_by_asc(product_variant::Column::Sku)
+            .all(&self.db)
+            .await?;
+
+        Ok(variants)
+    }
+
+    async fn find_by_id(
+        &self,
+        product_id: Uuid,
+        variant_id: Uuid,
+    ) -> Result<Option<product_variant::Model>, AppError> {
+        let variant = ProductVariant::find_by_id(variant_id)
+            .filter(product_variant::Column::ProductId.eq(product_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(variant)
+    }
+
+    async fn create(
+        &self,
+        product_id: Uuid,
+        sku: String,
+        options: Json,
+        price: Option<Decimal>,
+        quantity: i32,
+    ) -> Result<product_variant::Model, AppError> {
+        self.ensure_sku_free(&sku, None).await?;
+        self.ensure_options_free(product_id, &options, None).await?;
+
+        let now = chrono::Utc::now();
+        let variant = product_variant::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            product_id: Set(product_id),
+            sku: Set(sku),
+            options: Set(options),
+            price: Set(price),
+            quantity: Set(quantity),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(variant_conflict)?;
+
+        Ok(variant)
+    }
+
+    async fn update(
+        &self,
+        variant: product_variant::Model,
+        changes: VariantChanges,
+    ) -> Result<product_variant::Model, AppError> {
+        if let Some(sku) = &changes.sku {
+            self.ensure_sku_free(sku, Some(variant.id)).await?;
+        }
+        if let Some(options) = &changes.options {
+            self.ensure_options_free(variant.product_id, options, Some(variant.id))
+                .await?;
+        }
+
+        let mut active_variant: product_variant::ActiveModel = variant.into();
+        if let Some(sku) = changes.sku {
+            active_variant.sku = Set(sku);
+        }
+        if let Some(options) = changes.options {
+            active_variant.options = Set(options);
+        }
+        if let Some(price) = changes.price {
+            active_variant.price = Set(price);
+        }
+        if let Some(quantity) = changes.quantity {
+            active_variant.quantity = Set(quantity);
+        }
+        active_variant.updated_at = Set(chrono::Utc::now());
+
+        let variant = active_variant
+            .update(&self.db)
+            .await
+            .map_err(variant_conflict)?;
+        Ok(variant)
+    }
+
+    async fn delete(&self, variant: product_variant::Model) -> Result<(), AppError> {
+        ProductVariant::delete_by_id(variant.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
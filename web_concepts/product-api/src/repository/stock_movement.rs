@@ -0,0 +1,36 @@
+use crate::entities::stock_movement::{self, StockMovementReason};
+use sea_orm::{prelude::*, ActiveModelTrait, Set};
+use uuid::Uuid;
+
+/// A ledger entry about to be written
+#[derive(Debug, Clone)]
+pub struct NewStockMovement {
+    pub reason: StockMovementReason,
+    pub quantity_delta: i32,
+    pub reference: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Append a movement on `db`, which must be the transaction that changed
+/// `products.quantity` so the ledger and the running total never drift apart.
+pub async fn record_movement<C: ConnectionTrait>(
+    db: &C,
+    product_id: Uuid,
+    movement: NewStockMovement,
+    quantity_after: i32,
+    created_by: Option<Uuid>,
+) -> Result<stock_movement::Model, DbErr> {
+    stock_movement::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        product_id: Set(product_id),
+        reason: Set(movement.reason),
+        quantity_delta: Set(movement.quantity_delta),
+        quantity_after: Set(quantity_after),
+        reference: Set(movement.reference),
+        note: Set(movement.note),
+        created_by: Set(created_by),
+        created_at: Set(chrono::Utc::now()),
+    }
+    .insert(db)
+    .await
+}
@@ -0,0 +1,287 @@
+use crate::{
+    entities::{
+        prelude::*,
+        product,
+        webhook_delivery::{self, DeliveryStatus},
+        webhook_event::{self, WebhookEventType},
+    },
+    error::AppError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    prelude::*, DatabaseBackend, FromQueryResult, PaginatorTrait, QueryOrder, Statement,
+};
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// A pending delivery claimed by the dispatcher, with everything needed to send it
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    /// Attempts made before this one
+    pub attempts: i32,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: Json,
+    pub url: String,
+    pub secret: String,
+}
+
+#[async_trait]
+pub trait WebhookDeliveryRepositoryTrait {
+    /// Delivery log of one subscription, newest first
+    async fn find_by_subscription(
+        &self,
+        subscription_id: Uuid,
+        status: Option<DeliveryStatus>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<
+        (
+            Vec<(webhook_delivery::Model, Option<webhook_event::Model>)>,
+            u64,
+        ),
+        AppError,
+    >;
+    /// Claim up to `limit` pending deliveries that are due, hiding them from other
+    /// dispatchers for `lease` in case this one dies mid-attempt
+    async fn claim_due(&self, limit: u64, lease: Duration) -> Result<Vec<DueDelivery>, AppError>;
+    async fn mark_delivered(&self, id: Uuid, response_status: u16) -> Result<(), AppError>;
+    /// Record a failed attempt. `retry_at` schedules another one; `None` gives up.
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        response_status: Option<u16>,
+        error: String,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct WebhookDeliveryRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookDeliveryRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl WebhookDeliveryRepositoryTrait for WebhookDeliveryRepository {
+    async fn find_by_subscription(
+        &self,
+        subscription_id: Uuid,
+        status: Option<DeliveryStatus>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<
+        (
+            Vec<(webhook_delivery::Model, Option<webhook_event::Model>)>,
+            u64,
+        ),
+        AppError,
+    > {
+        let mut query = WebhookDelivery::find()
+            .find_also_related(WebhookEvent)
+            .filter(webhook_delivery::Column::SubscriptionId.eq(subscription_id));
+        if let Some(status) = status {
+            query = query.filter(webhook_delivery::Column::Status.eq(status));
+        }
+
+        let paginator = query
+            .order_by_desc(webhook_delivery::Column::CreatedAt)
+            .order_by_desc(webhook_delivery::Column::Id)
+            .paginate(&self.db, per_page);
+        let total = paginator.num_items().await?;
+        let deliveries = paginator.fetch_page(page.saturating_sub(1)).await?;
+
+        Ok((deliveries, total))
+    }
+
+    //  **Outbox** - Claim due rows with FOR UPDATE SKIP LOCKED and push their
+    //  next_attempt_at out by a lease, so parallel dispatchers never send the same
+    //  delivery twice and a crashed one's claims come back after the lease
+    async fn claim_due(&self, limit: u64, lease: Duration) -> Result<Vec<DueDelivery>, AppError> {
+        let claim_query = Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries
+                SET next_attempt_at = NOW() + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT d.id
+                    FROM webhook_deliveries d
+                    JOIN webhook_subscriptions s ON s.id = d.subscription_id
+                    WHERE d.status = 'pending' AND d.next_attempt_at <= NOW() AND s.is_active
+                    ORDER BY d.next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE OF d SKIP LOCKED
+                )
+                RETURNING id, attempts, event_id, subscription_id
+            )
+            SELECT c.id, c.attempts, e.id AS event_id, e.event_type, e.payload, s.url, s.secret
+            FROM claimed c
+            JOIN webhook_events e ON e.id = c.event_id
+            JOIN webhook_subscriptions s ON s.id = c.subscription_id
+            "#,
+            [(limit as i64).into(), lease.as_secs_f64().into()],
+        );
+
+        let deliveries = DueDelivery::find_by_statement(claim_query)
+            .all(&self.db)
+            .await?;
+        Ok(deliveries)
+    }
+
+    async fn mark_delivered(&self, id: Uuid, response_status: u16) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        WebhookDelivery::update_many()
+            .col_expr(
+                webhook_delivery::Column::Status,
+                Expr::value(DeliveryStatus::Delivered),
+            )
+            .col_expr(
+                webhook_delivery::Column::Attempts,
+                Expr::col(webhook_delivery::Column::Attempts).add(1),
+            )
+            .col_expr(webhook_delivery::Column::LastAttemptAt, Expr::value(now))
+            .col_expr(
+                webhook_delivery::Column::ResponseStatus,
+                Expr::value(response_status as i16),
+            )
+            .col_expr(
+                webhook_delivery::Column::LastError,
+                Expr::value(Option::<String>::None),
+            )
+            .col_expr(webhook_delivery::Column::DeliveredAt, Expr::value(now))
+            .filter(webhook_delivery::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        response_status: Option<u16>,
+        error: String,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AppError> {
+        let now = chrono::Utc::now();
+        let mut update = WebhookDelivery::update_many()
+            .col_expr(
+                webhook_delivery::Column::Attempts,
+                Expr::col(webhook_delivery::Column::Attempts).add(1),
+            )
+            .col_expr(webhook_delivery::Column::LastAttemptAt, Expr::value(now))
+            .col_expr(
+                webhook_delivery::Column::ResponseStatus,
+                Expr::value(response_status.map(|status| status as i16)),
+            )
+            .col_expr(webhook_delivery::Column::LastError, Expr::value(error));
+        update = match retry_at {
+            Some(retry_at) => update.col_expr(
+                webhook_delivery::Column::NextAttemptAt,
+                Expr::value(retry_at),
+            ),
+            None => update.col_expr(
+                webhook_delivery::Column::Status,
+                Expr::value(DeliveryStatus::Failed),
+            ),
+        };
+
+        update
+            .filter(webhook_delivery::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Queue a product event for every active subscription to it, on `db`, which is
+/// normally the transaction that made the change so the event commits or rolls
+/// back with it. Nothing is written when nobody subscribed.
+pub async fn record_product_event<C: ConnectionTrait>(
+    db: &C,
+    event_type: WebhookEventType,
+    product: &product::Model,
+) -> Result<(), AppError> {
+    enqueue_event(db, event_type, product, json!({ "product": product }), None).await
+}
+
+/// Queue `product.low_stock` for subscriptions whose threshold the quantity
+/// just dropped to or below
+pub async fn record_stock_level<C: ConnectionTrait>(
+    db: &C,
+    before: &product::Model,
+    after: &product::Model,
+) -> Result<(), AppError> {
+    if after.quantity >= before.quantity {
+        return Ok(());
+    }
+
+    let data = json!({ "product": after, "previous_quantity": before.quantity });
+    enqueue_event(
+        db,
+        WebhookEventType::ProductLowStock,
+        after,
+        data,
+        Some((before.quantity, after.quantity)),
+    )
+    .await
+}
+
+/// `crossing` limits the targets to subscriptions with a low-stock threshold in
+/// `[after, before)`
+async fn enqueue_event<C: ConnectionTrait>(
+    db: &C,
+    event_type: WebhookEventType,
+    product: &product::Model,
+    data: Json,
+    crossing: Option<(i32, i32)>,
+) -> Result<(), AppError> {
+    let event_id = Uuid::new_v4();
+    let payload = json!({
+        "id": event_id,
+        "type": event_type.name(),
+        "created_at": chrono::Utc::now(),
+        "data": data,
+    });
+    let (before, after) = crossing.unzip();
+
+    let enqueue_query = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+        WITH targets AS (
+            SELECT id FROM webhook_subscriptions
+            WHERE is_active
+              AND event_types @> jsonb_build_array($2::text)
+              AND ($5::int IS NULL OR ($5 > low_stock_threshold AND $6 <= low_stock_threshold))
+        ),
+        event AS (
+            INSERT INTO webhook_events (id, event_type, product_id, payload, created_at)
+            SELECT $1, $2, $3, $4, NOW()
+            WHERE EXISTS (SELECT 1 FROM targets)
+            RETURNING id
+        )
+        INSERT INTO webhook_deliveries (event_id, subscription_id, next_attempt_at)
+        SELECT event.id, targets.id, NOW()
+        FROM event CROSS JOIN targets
+        "#,
+        [
+            event_id.into(),
+            event_type.name().into(),
+            product.id.into(),
+            payload.into(),
+            before.into(),
+            after.into(),
+        ],
+    );
+
+    db.execute(enqueue_query).await?;
+    Ok(())
+}
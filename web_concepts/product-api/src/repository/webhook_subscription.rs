@@ -0,0 +1,145 @@
+use crate::{
+    entities::{prelude::*, webhook_event::WebhookEventType, webhook_subscription},
+    error::AppError,
+};
+use async_trait::async_trait;
+use sea_orm::{prelude::*, ActiveModelTrait, QueryOrder, Set};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub low_stock_threshold: i32,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+}
+
+/// Changes to an existing subscription; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct WebhookSubscriptionChanges {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub event_types: Option<Vec<WebhookEventType>>,
+    pub low_stock_threshold: Option<i32>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[async_trait]
+pub trait WebhookSubscriptionRepositoryTrait {
+    async fn find_all(&self) -> Result<Vec<webhook_subscription::Model>, AppError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<webhook_subscription::Model>, AppError>;
+    async fn create(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<webhook_subscription::Model, AppError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        changes: WebhookSubscriptionChanges,
+    ) -> Result<webhook_subscription::Model, AppError>;
+    /// Deletes the subscription together with its delivery log
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError>;
+}
+
+#[derive(Clone)]
+pub struct WebhookSubscriptionRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookSubscriptionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl WebhookSubscriptionRepositoryTrait for WebhookSubscriptionRepository {
+    async fn find_all(&self) -> Result<Vec<webhook_subscription::Model>, AppError> {
+        let subscriptions = WebhookSubscription::find()
+            .order_by_asc(webhook_subscription::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        Ok(subscriptions)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<webhook_subscription::Model>, AppError> {
+        let subscription = WebhookSubscription::find_by_id(id).one(&self.db).await?;
+        Ok(subscription)
+    }
+
+    async fn create(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<webhook_subscription::Model, AppError> {
+        let now = chrono::Utc::now();
+        let model = webhook_subscription::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            url: Set(subscription.url),
+            secret: Set(subscription.secret),
+            event_types: Set(event_types_json(&subscription.event_types)),
+            low_stock_threshold: Set(subscription.low_stock_threshold),
+            description: Set(subscription.description),
+            is_active: Set(true),
+            created_by: Set(subscription.created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        Ok(model.insert(&self.db).await?)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        changes: WebhookSubscriptionChanges,
+    ) -> Result<webhook_subscription::Model, AppError> {
+        let subscription = WebhookSubscription::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "WebhookSubscription".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        let mut active_subscription: webhook_subscription::ActiveModel = subscription.into();
+        if let Some(url) = changes.url {
+            active_subscription.url = Set(url);
+        }
+        if let Some(secret) = changes.secret {
+            active_subscription.secret = Set(secret);
+        }
+        if let Some(event_types) = changes.event_types {
+            active_subscription.event_types = Set(event_types_json(&event_types));
+        }
+        if let Some(threshold) = changes.low_stock_threshold {
+            active_subscription.low_stock_threshold = Set(threshold);
+        }
+        if let Some(description) = changes.description {
+            active_subscription.description = Set(Some(description));
+        }
+        if let Some(is_active) = changes.is_active {
+            active_subscription.is_active = Set(is_active);
+        }
+        active_subscription.updated_at = Set(chrono::Utc::now());
+
+        Ok(active_subscription.update(&self.db).await?)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = WebhookSubscription::delete_by_id(id).exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+fn event_types_json(event_types: &[WebhookEventType]) -> Json {
+    Json::Array(
+        event_types
+            .iter()
+            .map(|event_type| Json::String(event_type.name().to_string()))
+            .collect(),
+    )
+}
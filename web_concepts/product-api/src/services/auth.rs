@@ -1,11 +1,21 @@
 use crate::{
-    entities::user::UserRole,
-    error::{conflict_error, AppError},
-    models::{AuthResponse, LoginRequest, RegisterRequest, UserResponse},
+    config::Config,
+    entities::{refresh_token, user, user::UserRole},
+    error::{business_rule_error, conflict_error, not_found_error, AppError},
+    models::{
+        AuthResponse, Claims, LoginRequest, LogoutRequest, RefreshTokenRequest, RegisterRequest,
+        UserResponse,
+    },
     repository::auth::AuthRepositoryTrait,
-    utils::{create_jwt, hash_password, verify_password},
+    services::token_denylist::TokenDenylist,
+    utils::{
+        create_jwt, generate_refresh_token, hash_password, hash_refresh_token, unix_now,
+        verify_password,
+    },
 };
+use chrono::{DateTime, Duration, Utc};
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub struct AuthService<T: AuthRepositoryTrait> {
     auth_repository: Arc<T>,
@@ -47,25 +57,8 @@ impl<T: AuthRepositoryTrait> AuthService<T> {
             ));
         }
 
-        // Create JWT with role information
-        let token = create_jwt(
-            &user.id.to_string(),
-            &user.username,
-            &user.role,
-            &config.jwt_secret,
-            config.jwt_expiration,
-        )?;
-
-        Ok(AuthResponse {
-            token,
-            user: UserResponse {
-                id: user.id,
-                username: user.username,
-                email: user.email,
-                role: user.role,
-                is_active: user.is_active,
-            },
-        })
+        // A new login starts a new refresh token family
+        self.issue_tokens(&config, user, Uuid::new_v4()).await
     }
 
     pub async fn register(
@@ -105,6 +98,155 @@ impl<T: AuthRepositoryTrait> AuthService<T> {
             )
             .await?;
 
+        // A new login starts a new refresh token family
+        self.issue_tokens(&config, user, Uuid::new_v4()).await
+    }
+
+    /// Exchange a refresh token for a new access token and a new refresh
+    /// token. The presented token is revoked; presenting it again revokes
+    /// every token rotated from the same login.
+    pub async fn refresh(
+        &self,
+        config: Arc<Config>,
+        request: RefreshTokenRequest,
+    ) -> Result<AuthResponse, AppError> {
+        let invalid = || AppError::unauthorized_with_context("Invalid refresh token".to_string());
+
+        let current = self
+            .auth_repository
+            .find_refresh_token(&hash_refresh_token(&request.refresh_token))
+            .await?
+            .ok_or_else(invalid)?;
+
+        if current.revoked_at.is_some() {
+            return Err(self.reuse_detected(&current).await);
+        }
+        if current.expires_at <= Utc::now() {
+            return Err(AppError::unauthorized_with_context(
+                "Refresh token expired".to_string(),
+            ));
+        }
+
+        // Pick up role changes and deactivations since the last refresh
+        let user = match self.auth_repository.find_by_id(current.user_id).await? {
+            Some(user) if user.is_active => user,
+            _ => {
+                self.auth_repository
+                    .revoke_refresh_token_family(current.family_id)
+                    .await?;
+                return Err(invalid());
+            }
+        };
+
+        let (refresh_token, next) = new_refresh_token(&config, user.id, current.family_id);
+        if !self
+            .auth_repository
+            .rotate_refresh_token(current.id, next)
+            .await?
+        {
+            // A concurrent request rotated it first: the token was used twice
+            return Err(self.reuse_detected(&current).await);
+        }
+
+        self.auth_response(&config, user, refresh_token)
+    }
+
+    /// Revoke the caller's access token and, if given, the refresh token
+    /// family it belongs to
+    pub async fn logout(
+        &self,
+        claims: &Claims,
+        request: LogoutRequest,
+        denylist: &TokenDenylist,
+    ) -> Result<(), AppError> {
+        self.auth_repository
+            .revoke_access_token(&claims.jti, unix_timestamp(claims.exp as u64)?)
+            .await?;
+        denylist.revoke_token(&claims.jti, claims.exp as u64);
+
+        if let Some(refresh_token) = request.refresh_token {
+            let token = self
+                .auth_repository
+                .find_refresh_token(&hash_refresh_token(&refresh_token))
+                .await?;
+            // Someone else's refresh token is ignored rather than revoked
+            if let Some(token) = token.filter(|t| t.user_id.to_string() == claims.sub) {
+                self.auth_repository
+                    .revoke_refresh_token_family(token.family_id)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Activate or deactivate a user. Deactivation revokes all of the user's
+    /// refresh tokens and the access tokens issued so far; activation lifts
+    /// that, though the revoked refresh tokens stay revoked.
+    pub async fn set_user_active(
+        &self,
+        config: Arc<Config>,
+        actor: &Claims,
+        user_id: Uuid,
+        is_active: bool,
+        denylist: &TokenDenylist,
+    ) -> Result<UserResponse, AppError> {
+        if !is_active && actor.sub == user_id.to_string() {
+            return Err(business_rule_error(
+                "self_deactivation",
+                "Admins cannot deactivate their own account",
+            ));
+        }
+
+        let user = self
+            .auth_repository
+            .set_active(user_id, is_active)
+            .await?
+            .ok_or_else(|| not_found_error("user", Some(&user_id.to_string())))?;
+
+        if !is_active {
+            let revoked = self
+                .auth_repository
+                .revoke_user_refresh_tokens(user.id)
+                .await?;
+            let now = unix_now()?;
+            let until = now + config.jwt_expiration.max(0) as u64;
+            self.auth_repository
+                .revoke_user_access_tokens(user.id, unix_timestamp(now)?, unix_timestamp(until)?)
+                .await?;
+            denylist.revoke_user(&user.id.to_string(), now, until);
+            tracing::info!(
+                user_id = %user.id,
+                refresh_tokens_revoked = revoked,
+                "User deactivated, tokens revoked"
+            );
+        } else {
+            self.auth_repository
+                .clear_user_access_token_revocation(user.id)
+                .await?;
+            denylist.clear_user(&user.id.to_string());
+        }
+
+        Ok(user_response(user))
+    }
+
+    async fn issue_tokens(
+        &self,
+        config: &Config,
+        user: user::Model,
+        family_id: Uuid,
+    ) -> Result<AuthResponse, AppError> {
+        let (refresh_token, model) = new_refresh_token(config, user.id, family_id);
+        self.auth_repository.create_refresh_token(model).await?;
+        self.auth_response(config, user, refresh_token)
+    }
+
+    fn auth_response(
+        &self,
+        config: &Config,
+        user: user::Model,
+        refresh_token: String,
+    ) -> Result<AuthResponse, AppError> {
         // Create JWT with role information
         let token = create_jwt(
             &user.id.to_string(),
@@ -116,16 +258,30 @@ impl<T: AuthRepositoryTrait> AuthService<T> {
 
         Ok(AuthResponse {
             token,
-            user: UserResponse {
-                id: user.id,
-                username: user.username,
-                email: user.email,
-                role: user.role,
-                is_active: user.is_active,
-            },
+            expires_in: config.jwt_expiration,
+            refresh_token,
+            user: user_response(user),
         })
     }
 
+    /// A revoked refresh token was presented: assume it was stolen and revoke
+    /// the whole family, so neither the thief nor the victim can keep using it
+    async fn reuse_detected(&self, token: &refresh_token::Model) -> AppError {
+        tracing::warn!(
+            user_id = %token.user_id,
+            family_id = %token.family_id,
+            "Refresh token reuse detected, revoking the token family"
+        );
+        if let Err(e) = self
+            .auth_repository
+            .revoke_refresh_token_family(token.family_id)
+            .await
+        {
+            return e;
+        }
+        AppError::unauthorized_with_context("Refresh token reuse detected".to_string())
+    }
+
     /// Determine what role a new user should have based on request and permissions
     fn determine_user_role(
         &self,
@@ -173,3 +329,44 @@ impl<T: AuthRepositoryTrait> AuthService<T> {
         }
     }
 }
+
+fn new_refresh_token(
+    config: &Config,
+    user_id: Uuid,
+    family_id: Uuid,
+) -> (String, refresh_token::Model) {
+    let token = generate_refresh_token();
+    let now = Utc::now();
+    let model = refresh_token::Model {
+        id: Uuid::new_v4(),
+        user_id,
+        family_id,
+        token_hash: hash_refresh_token(&token),
+        expires_at: now + Duration::seconds(config.refresh_token_ttl_seconds),
+        revoked_at: None,
+        replaced_by: None,
+        created_at: now,
+    };
+    (token, model)
+}
+
+fn user_response(user: user::Model) -> UserResponse {
+    UserResponse {
+        id: user.id,
+        username: user.username,
+        email: user.email,
+        role: user.role,
+        is_active: user.is_active,
+    }
+}
+
+/// A JWT `iat`/`exp` as a database timestamp
+fn unix_timestamp(seconds: u64) -> Result<DateTime<Utc>, AppError> {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .ok_or_else(|| AppError::InternalServerError {
+            context: Some(format!("Timestamp {seconds} is out of range")),
+            error_id: Uuid::new_v4(),
+        })
+}
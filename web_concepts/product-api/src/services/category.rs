@@ -0,0 +1,143 @@
+use crate::{
+    entities::category,
+    error::{validation_error, AppError},
+    models::{
+        CategoryListResponse, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest,
+    },
+    repository::category::{CategoryChanges, CategoryRepositoryTrait},
+    utils::slugify,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct CategoryService<T: CategoryRepositoryTrait> {
+    category_repository: Arc<T>,
+}
+
+impl<T: CategoryRepositoryTrait> CategoryService<T> {
+    pub fn new(category_repository: Arc<T>) -> Self {
+        Self {
+            category_repository,
+        }
+    }
+
+    pub async fn list_categories(&self) -> Result<CategoryListResponse, AppError> {
+        let categories = self.category_repository.find_all().await?;
+
+        Ok(CategoryListResponse {
+            categories: categories.into_iter().map(CategoryResponse::from).collect(),
+        })
+    }
+
+    pub async fn get_category(&self, id: Uuid) -> Result<CategoryResponse, AppError> {
+        let category =
+            self.category_repository
+                .find_by_id(id)
+                .await?
+                .ok_or(AppError::NotFound {
+                    resource_type: "Category".to_string(),
+                    resource_id: Some(id.to_string()),
+                    error_id: uuid::Uuid::new_v4(),
+                })?;
+
+        Ok(CategoryResponse::from(category))
+    }
+
+    pub async fn create_category(
+        &self,
+        request: CreateCategoryRequest,
+    ) -> Result<CategoryResponse, AppError> {
+        let name = clean_name(&request.name)?;
+        let slug = match request.slug {
+            Some(slug) => clean_slug(&slug)?,
+            None => clean_slug(&name).map_err(|_| {
+                validation_error("name", "Name must contain at least one letter or digit")
+            })?,
+        };
+
+        let category = self
+            .category_repository
+            .create(name, slug, request.parent_id)
+            .await?;
+
+        Ok(CategoryResponse::from(category))
+    }
+
+    pub async fn update_category(
+        &self,
+        id: Uuid,
+        request: UpdateCategoryRequest,
+    ) -> Result<CategoryResponse, AppError> {
+        if request.make_root && request.parent_id.is_some() {
+            return Err(validation_error(
+                "make_root",
+                "Send either a parent_id or make_root, not both",
+            ));
+        }
+        if request.parent_id == Some(id) {
+            return Err(validation_error(
+                "parent_id",
+                "A category can't be its own parent",
+            ));
+        }
+
+        let changes = CategoryChanges {
+            name: request.name.as_deref().map(clean_name).transpose()?,
+            slug: request.slug.as_deref().map(clean_slug).transpose()?,
+            parent_id: if request.make_root {
+                Some(None)
+            } else {
+                request.parent_id.map(Some)
+            },
+        };
+        let category = self.category_repository.update(id, changes).await?;
+
+        Ok(CategoryResponse::from(category))
+    }
+
+    pub async fn delete_category(&self, id: Uuid) -> Result<(), AppError> {
+        let deleted = self.category_repository.delete(id).await?;
+        if !deleted {
+            return Err(AppError::NotFound {
+                resource_type: "Category".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn clean_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error("name", "Name must not be blank"));
+    }
+    Ok(name.to_string())
+}
+
+/// Slugs are stored in canonical form, whatever the caller sent
+fn clean_slug(slug: &str) -> Result<String, AppError> {
+    let canonical = slugify(slug);
+    if canonical.is_empty() {
+        return Err(validation_error(
+            "slug",
+            "Slug must contain at least one letter or digit",
+        ));
+    }
+    Ok(canonical)
+}
+
+impl From<category::Model> for CategoryResponse {
+    fn from(category: category::Model) -> Self {
+        Self {
+            id: category.id,
+            name: category.name,
+            slug: category.slug,
+            parent_id: category.parent_id,
+            created_at: category.created_at,
+            updated_at: category.updated_at,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    entities::exchange_rate,
+    error::{business_rule_error, validation_error, AppError},
+    models::{ExchangeRateListResponse, ExchangeRateResponse, UpsertExchangeRateRequest},
+    repository::exchange_rate::ExchangeRateRepositoryTrait,
+    utils::{is_valid_currency_code, CurrencyConversion},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ExchangeRateService<T: ExchangeRateRepositoryTrait> {
+    rate_repository: Arc<T>,
+    base_currency: String,
+}
+
+impl<T: ExchangeRateRepositoryTrait> ExchangeRateService<T> {
+    pub fn new(rate_repository: Arc<T>, base_currency: String) -> Self {
+        Self {
+            rate_repository,
+            base_currency,
+        }
+    }
+
+    pub async fn list_rates(&self) -> Result<ExchangeRateListResponse, AppError> {
+        let rates = self.rate_repository.find_all().await?;
+
+        Ok(ExchangeRateListResponse {
+            base_currency: self.base_currency.clone(),
+            rates: rates.into_iter().map(ExchangeRateResponse::from).collect(),
+        })
+    }
+
+    pub async fn upsert_rate(
+        &self,
+        currency: &str,
+        request: UpsertExchangeRateRequest,
+        actor: Option<Uuid>,
+    ) -> Result<ExchangeRateResponse, AppError> {
+        check_currency_code(currency)?;
+        self.ensure_not_base(currency)?;
+
+        let rate = self
+            .rate_repository
+            .upsert(currency, request.rate, actor)
+            .await?;
+
+        Ok(ExchangeRateResponse::from(rate))
+    }
+
+    pub async fn delete_rate(&self, currency: &str) -> Result<(), AppError> {
+        check_currency_code(currency)?;
+        self.ensure_not_base(currency)?;
+
+        if !self.rate_repository.delete(currency).await? {
+            return Err(AppError::NotFound {
+                resource_type: "ExchangeRate".to_string(),
+                resource_id: Some(currency.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Conversion into the currency a client asked for with `currency=`, over the
+    /// rates as they are now. Without one it converts into the base currency, so
+    /// products priced in different currencies can still be compared and totalled.
+    pub async fn conversion(&self, currency: Option<&str>) -> Result<CurrencyConversion, AppError> {
+        let currency = currency.unwrap_or(&self.base_currency);
+        check_currency_code(currency)?;
+
+        let rates = self.rate_repository.find_rates().await?;
+        CurrencyConversion::new(currency, rates).ok_or_else(|| {
+            validation_error(
+                "currency",
+                &format!("No exchange rate is configured for {currency}"),
+            )
+        })
+    }
+
+    /// Rates are quoted against the base currency, so its own rate stays 1
+    fn ensure_not_base(&self, currency: &str) -> Result<(), AppError> {
+        if currency == self.base_currency {
+            return Err(business_rule_error(
+                "base_currency_rate",
+                &format!("{currency} is the base currency; its rate is always 1"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn check_currency_code(currency: &str) -> Result<(), AppError> {
+    if !is_valid_currency_code(currency) {
+        return Err(validation_error(
+            "currency",
+            "Currency must be a three-letter ISO 4217 code such as USD",
+        ));
+    }
+    Ok(())
+}
+
+impl From<exchange_rate::Model> for ExchangeRateResponse {
+    fn from(rate: exchange_rate::Model) -> Self {
+        Self {
+            currency: rate.currency,
+            rate: rate.rate,
+            updated_by: rate.updated_by,
+            updated_at: rate.updated_at,
+        }
+    }
+}
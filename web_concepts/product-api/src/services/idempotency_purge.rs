@@ -0,0 +1,24 @@
+use crate::repository::idempotency_key::{IdempotencyKeyRepository, IdempotencyKeyRepositoryTrait};
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Delete idempotency keys past their `expires_at` every `interval`. Expired
+/// keys are already ignored on lookup; this only keeps the table small.
+pub fn spawn_idempotency_purge(db: DatabaseConnection, interval: Duration) -> JoinHandle<()> {
+    let repository = IdempotencyKeyRepository::new(db);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match repository.purge_expired().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!(purged, "Purged expired idempotency keys"),
+                Err(e) => tracing::warn!(error = %e, "Failed to purge idempotency keys"),
+            }
+        }
+    })
+}
@@ -1,4 +1,24 @@
 pub mod auth;
+pub mod category;
+pub mod exchange_rate;
+pub mod idempotency_purge;
+pub mod price_schedule;
 pub mod product;
+pub mod product_image;
+pub mod product_variant;
+pub mod reservation_expiry;
+pub mod token_denylist;
+pub mod webhook;
+pub mod webhook_dispatcher;
 pub use auth::*;
+pub use category::*;
+pub use exchange_rate::*;
+pub use idempotency_purge::*;
+pub use price_schedule::*;
 pub use product::*;
+pub use product_image::*;
+pub use product_variant::*;
+pub use reservation_expiry::*;
+pub use token_denylist::*;
+pub use webhook::*;
+pub use webhook_dispatcher::*;
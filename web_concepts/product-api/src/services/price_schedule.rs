@@ -0,0 +1,176 @@
+use crate::{
+    entities::{price_schedule, product},
+    error::{validation_error, AppError},
+    models::{
+        CreatePriceScheduleRequest, PriceScheduleListResponse, PriceScheduleResponse,
+        UpdatePriceScheduleRequest,
+    },
+    repository::price_schedule::{PriceScheduleChanges, PriceScheduleRepositoryTrait},
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct PriceScheduleService<T: PriceScheduleRepositoryTrait> {
+    schedule_repository: Arc<T>,
+}
+
+impl<T: PriceScheduleRepositoryTrait> PriceScheduleService<T> {
+    pub fn new(schedule_repository: Arc<T>) -> Self {
+        Self {
+            schedule_repository,
+        }
+    }
+
+    pub async fn list_schedules(
+        &self,
+        product_id: Uuid,
+    ) -> Result<PriceScheduleListResponse, AppError> {
+        let product = self.parent(product_id).await?;
+        let schedules = self.schedule_repository.find_by_product(product_id).await?;
+        let effective_price = self
+            .schedule_repository
+            .find_current_price(product_id)
+            .await?
+            .unwrap_or(product.price);
+
+        Ok(PriceScheduleListResponse {
+            product_id,
+            base_price: product.price,
+            effective_price,
+            schedules: schedules
+                .into_iter()
+                .map(PriceScheduleResponse::from)
+                .collect(),
+        })
+    }
+
+    pub async fn create_schedule(
+        &self,
+        product_id: Uuid,
+        request: CreatePriceScheduleRequest,
+        actor: Option<Uuid>,
+    ) -> Result<PriceScheduleResponse, AppError> {
+        self.parent(product_id).await?;
+        check_window(request.effective_from, request.effective_to)?;
+
+        let schedule = self
+            .schedule_repository
+            .create(
+                product_id,
+                request.price,
+                request.effective_from,
+                request.effective_to,
+                request.label,
+                actor,
+            )
+            .await?;
+
+        Ok(PriceScheduleResponse::from(schedule))
+    }
+
+    pub async fn update_schedule(
+        &self,
+        product_id: Uuid,
+        schedule_id: Uuid,
+        request: UpdatePriceScheduleRequest,
+    ) -> Result<PriceScheduleResponse, AppError> {
+        self.parent(product_id).await?;
+        let schedule = self.schedule(product_id, schedule_id).await?;
+
+        let effective_to = if request.clear_effective_to {
+            Some(None)
+        } else {
+            request.effective_to.map(Some)
+        };
+        check_window(
+            request.effective_from.unwrap_or(schedule.effective_from),
+            effective_to.unwrap_or(schedule.effective_to),
+        )?;
+
+        let changes = PriceScheduleChanges {
+            price: request.price,
+            effective_from: request.effective_from,
+            effective_to,
+            label: request.label,
+        };
+        let schedule = self.schedule_repository.update(schedule, changes).await?;
+
+        Ok(PriceScheduleResponse::from(schedule))
+    }
+
+    pub async fn delete_schedule(
+        &self,
+        product_id: Uuid,
+        schedule_id: Uuid,
+    ) -> Result<(), AppError> {
+        self.parent(product_id).await?;
+        let schedule = self.schedule(product_id, schedule_id).await?;
+        self.schedule_repository.delete(schedule).await
+    }
+
+    async fn parent(&self, product_id: Uuid) -> Result<product::Model, AppError> {
+        self.schedule_repository
+            .find_parent(product_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+
+    async fn schedule(
+        &self,
+        product_id: Uuid,
+        schedule_id: Uuid,
+    ) -> Result<price_schedule::Model, AppError> {
+        self.schedule_repository
+            .find_by_id(product_id, schedule_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "PriceSchedule".to_string(),
+                resource_id: Some(schedule_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+}
+
+/// A window must be non-empty and must not lie entirely in the past
+fn check_window(
+    effective_from: DateTime<Utc>,
+    effective_to: Option<DateTime<Utc>>,
+) -> Result<(), AppError> {
+    if let Some(effective_to) = effective_to {
+        if effective_to <= effective_from {
+            return Err(validation_error(
+                "effective_to",
+                "effective_to must be later than effective_from",
+            ));
+        }
+        if effective_to <= Utc::now() {
+            return Err(validation_error(
+                "effective_to",
+                "The schedule would already have ended",
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl From<price_schedule::Model> for PriceScheduleResponse {
+    fn from(schedule: price_schedule::Model) -> Self {
+        let status = schedule.status_at(Utc::now());
+        Self {
+            id: schedule.id,
+            product_id: schedule.product_id,
+            price: schedule.price,
+            effective_from: schedule.effective_from,
+            effective_to: schedule.effective_to,
+            label: schedule.label,
+            status,
+            created_by: schedule.created_by,
+            created_at: schedule.created_at,
+        }
+    }
+}
@@ -1,13 +1,29 @@
 use crate::{
-    error::AppError,
+    entities::{product, stock_movement::StockMovementReason},
+    error::{business_rule_error, validation_error, AppError, ErrorRegistry},
     models::{
-        CreateProductRequest, ProductListResponse, ProductResponse, ProductSearchFilters,
-        ProductSearchRequest, ProductSearchResponse, ProductStatsResponse, UpdateProductRequest,
+        BatchItemError, BatchItemResult, BatchItemStatus, BatchUpdateItem, BatchWriteReport,
+        CreateProductRequest, CreateReservationRequest, ProductHistoryEntry,
+        ProductHistoryResponse, ProductImageResponse, ProductImportReport, ProductImportRowError,
+        ProductListResponse, ProductResponse, ProductSearchFilters, ProductSearchRequest,
+        ProductSearchResponse, ProductStatsResponse, ReservationConfirmationResponse,
+        ReservationResponse, StockAdjustmentRequest, StockAdjustmentResponse,
+        StockMovementListResponse, StockMovementResponse, UpdateProductRequest,
     },
-    repository::product::ProductRepositoryTrait,
+    repository::{product::ProductRepositoryTrait, stock_movement::NewStockMovement},
+    utils::{export_header, export_rows, CurrencyConversion, IfMatch, ImportRow, TransferFormat},
 };
-use std::sync::Arc;
+use axum::body::Bytes;
+use futures::{stream, Stream, StreamExt};
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
+use validator::Validate;
+
+/// Rows fetched per round trip while streaming an export
+const EXPORT_BATCH_SIZE: u64 = 100;
+
+/// Result of one batch item; `Ok` carries the updated product, if there is one to show
+type BatchOutcome = Result<Option<ProductResponse>, AppError>;
 
 pub struct ProductService<T: ProductRepositoryTrait> {
     product_repository: Arc<T>,
@@ -18,6 +34,50 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
         Self { product_repository }
     }
 
+    /// Responses with each product's images and current scheduled price attached,
+    /// one query each for the whole batch
+    async fn responses_with_details(
+        &self,
+        products: Vec<product::Model>,
+    ) -> Result<Vec<ProductResponse>, AppError> {
+        let product_ids: Vec<Uuid> = products.iter().map(|product| product.id).collect();
+        let current_prices = self
+            .product_repository
+            .find_current_prices(&product_ids)
+            .await?;
+        let mut images: HashMap<Uuid, Vec<ProductImageResponse>> = HashMap::new();
+        for image in self.product_repository.find_images(&product_ids).await? {
+            images
+                .entry(image.product_id)
+                .or_default()
+                .push(ProductImageResponse::from(image));
+        }
+
+        Ok(products
+            .into_iter()
+            .map(|product| {
+                let product_images = images.remove(&product.id).unwrap_or_default();
+                let effective_price = current_prices
+                    .get(&product.id)
+                    .copied()
+                    .unwrap_or(product.price);
+                ProductResponse {
+                    effective_price,
+                    images: product_images,
+                    ..ProductResponse::from(product)
+                }
+            })
+            .collect())
+    }
+
+    async fn response_with_details(
+        &self,
+        product: product::Model,
+    ) -> Result<ProductResponse, AppError> {
+        let mut responses = self.responses_with_details(vec![product]).await?;
+        Ok(responses.remove(0))
+    }
+
     pub async fn create_product(
         &self,
         request: CreateProductRequest,
@@ -30,22 +90,28 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
         &self,
         page: Option<u64>,
         per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+        conversion: Option<&CurrencyConversion>,
     ) -> Result<ProductListResponse, AppError> {
-        let page = page.unwrap_or(1);
-        let per_page = per_page.unwrap_or(10).min(100);
+        // Page numbers are meaningless once the client follows a cursor
+        let page = cursor.is_none().then(|| page.unwrap_or(1));
+        let per_page = per_page.unwrap_or(10).clamp(1, 100);
 
-        let (products, total) = self
+        let result = self
             .product_repository
-            .find_all(Some(page), Some(per_page))
+            .find_all(page, Some(per_page), cursor, include_total)
             .await?;
 
-        let products = products.into_iter().map(ProductResponse::from).collect();
+        let products = self.responses_with_details(result.products).await?;
+        let products = convert_responses(products, conversion)?;
 
         Ok(ProductListResponse {
             products,
-            total,
+            total: result.total,
             page,
             per_page,
+            next_cursor: result.next_cursor,
         })
     }
 
@@ -60,20 +126,58 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
                 error_id: uuid::Uuid::new_v4(),
             })?;
 
-        Ok(ProductResponse::from(product))
+        self.response_with_details(product).await
+    }
+
+    /// Find a live product by exactly one of its SKU or barcode
+    pub async fn lookup_product(
+        &self,
+        sku: Option<String>,
+        barcode: Option<String>,
+    ) -> Result<ProductResponse, AppError> {
+        let (product, identifier) = match (sku, barcode) {
+            (Some(sku), None) => (self.product_repository.find_by_sku(&sku).await?, sku),
+            (None, Some(barcode)) => (
+                self.product_repository.find_by_barcode(&barcode).await?,
+                barcode,
+            ),
+            _ => {
+                return Err(validation_error(
+                    "sku",
+                    "Provide exactly one of sku or barcode",
+                ))
+            }
+        };
+
+        let product = product.ok_or(AppError::NotFound {
+            resource_type: "Product".to_string(),
+            resource_id: Some(identifier),
+            error_id: uuid::Uuid::new_v4(),
+        })?;
+
+        self.response_with_details(product).await
     }
 
     pub async fn update_product(
         &self,
         product_id: Uuid,
         request: UpdateProductRequest,
+        if_match: Option<IfMatch>,
+        actor: Option<Uuid>,
     ) -> Result<ProductResponse, AppError> {
-        let updated_product = self.product_repository.update(product_id, request).await?;
-        Ok(ProductResponse::from(updated_product))
+        let updated_product = self
+            .product_repository
+            .update(product_id, request, if_match, actor)
+            .await?;
+        self.response_with_details(updated_product).await
     }
 
-    pub async fn delete_product(&self, product_id: Uuid) -> Result<(), AppError> {
-        let deleted = self.product_repository.delete(product_id).await?;
+    pub async fn delete_product(
+        &self,
+        product_id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let deleted = self.product_repository.delete(product_id, actor).await?;
         if !deleted {
             return Err(AppError::NotFound {
                 resource_type: "Product".to_string(),
@@ -85,24 +189,491 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
         Ok(())
     }
 
+    /// Validate and apply a batch of updates. Invalid items fail on their own;
+    /// in atomic mode they stop the whole batch before anything is written.
+    pub async fn batch_update_products(
+        &self,
+        items: Vec<BatchUpdateItem>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteReport, AppError> {
+        let ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+        let mut outcomes: Vec<Option<BatchOutcome>> = ids.iter().map(|_| None).collect();
+        let mut indexes = Vec::new();
+        let mut valid = Vec::new();
+
+        for (index, item) in items.into_iter().enumerate() {
+            match item.changes.validate() {
+                Ok(()) => {
+                    indexes.push(index);
+                    valid.push((item.id, item.changes));
+                }
+                Err(e) => outcomes[index] = Some(Err(AppError::from(e))),
+            }
+        }
+
+        if atomic && valid.len() < ids.len() {
+            return Ok(batch_report(
+                &ids,
+                outcomes,
+                atomic,
+                false,
+                BatchItemStatus::Updated,
+            ));
+        }
+
+        let result = self
+            .product_repository
+            .update_many(valid, atomic, actor)
+            .await?;
+        let committed = result.committed;
+
+        // Rolled back changes are not worth showing
+        let updated = if committed {
+            result
+                .outcomes
+                .iter()
+                .filter_map(|(_, outcome)| outcome.as_ref().ok().cloned())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut responses = self.responses_with_details(updated).await?.into_iter();
+        for (index, (_, outcome)) in indexes.into_iter().zip(result.outcomes) {
+            outcomes[index] = Some(outcome.map(|_| responses.next()));
+        }
+
+        Ok(batch_report(
+            &ids,
+            outcomes,
+            atomic,
+            committed,
+            BatchItemStatus::Updated,
+        ))
+    }
+
+    pub async fn batch_delete_products(
+        &self,
+        ids: Vec<Uuid>,
+        atomic: bool,
+        actor: Option<Uuid>,
+    ) -> Result<BatchWriteReport, AppError> {
+        let result = self
+            .product_repository
+            .delete_many(ids.clone(), atomic, actor)
+            .await?;
+
+        let mut outcomes: Vec<Option<BatchOutcome>> = ids.iter().map(|_| None).collect();
+        for (slot, (_, outcome)) in outcomes.iter_mut().zip(result.outcomes) {
+            *slot = Some(outcome.map(|()| None));
+        }
+
+        Ok(batch_report(
+            &ids,
+            outcomes,
+            atomic,
+            result.committed,
+            BatchItemStatus::Deleted,
+        ))
+    }
+
+    pub async fn get_trash(
+        &self,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        cursor: Option<String>,
+        include_total: Option<bool>,
+    ) -> Result<ProductListResponse, AppError> {
+        let page = cursor.is_none().then(|| page.unwrap_or(1));
+        let per_page = per_page.unwrap_or(10).clamp(1, 100);
+
+        let result = self
+            .product_repository
+            .find_deleted(page, Some(per_page), cursor, include_total)
+            .await?;
+
+        Ok(ProductListResponse {
+            products: result
+                .products
+                .into_iter()
+                .map(ProductResponse::from)
+                .collect(),
+            total: result.total,
+            page,
+            per_page,
+            next_cursor: result.next_cursor,
+        })
+    }
+
+    pub async fn restore_product(
+        &self,
+        product_id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<ProductResponse, AppError> {
+        let product = self
+            .product_repository
+            .restore(product_id, actor)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Deleted product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        self.response_with_details(product).await
+    }
+
+    pub async fn purge_product(
+        &self,
+        product_id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let purged = self.product_repository.purge(product_id, actor).await?;
+        if !purged {
+            return Err(AppError::NotFound {
+                resource_type: "Deleted product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_product_history(
+        &self,
+        product_id: Uuid,
+        page: Option<u64>,
+        per_page: Option<u64>,
+    ) -> Result<ProductHistoryResponse, AppError> {
+        let page = page.unwrap_or(1).max(1);
+        let per_page = per_page.unwrap_or(20).clamp(1, 100);
+
+        let (entries, total) = self
+            .product_repository
+            .find_history(product_id, page, per_page)
+            .await?;
+
+        // Purged products keep their history, so only 404 when there is nothing at all
+        if total == 0
+            && !self
+                .product_repository
+                .exists_including_deleted(product_id)
+                .await?
+        {
+            return Err(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+
+        Ok(ProductHistoryResponse {
+            product_id,
+            entries: entries
+                .into_iter()
+                .map(|entry| ProductHistoryEntry {
+                    id: entry.id,
+                    action: entry.action,
+                    changed_by: entry.changed_by,
+                    changed_at: entry.changed_at,
+                    changes: entry.changes,
+                })
+                .collect(),
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// Book a stock movement and return the product with its new quantity
+    pub async fn adjust_stock(
+        &self,
+        product_id: Uuid,
+        request: StockAdjustmentRequest,
+        actor: Option<Uuid>,
+    ) -> Result<StockAdjustmentResponse, AppError> {
+        if !request.reason.allows_delta(request.quantity_delta) {
+            return Err(validation_error(
+                "quantity_delta",
+                match request.reason {
+                    StockMovementReason::Receipt | StockMovementReason::Return => {
+                        "Receipts and returns must add stock (positive quantity_delta)"
+                    }
+                    StockMovementReason::Sale => {
+                        "Sales must remove stock (negative quantity_delta)"
+                    }
+                    _ => "quantity_delta must not be zero",
+                },
+            ));
+        }
+
+        let movement = NewStockMovement {
+            reason: request.reason,
+            quantity_delta: request.quantity_delta,
+            reference: request.reference,
+            note: request.note,
+        };
+        let (product, movement) = self
+            .product_repository
+            .adjust_stock(product_id, movement, actor)
+            .await?;
+
+        Ok(StockAdjustmentResponse {
+            product: self.response_with_details(product).await?,
+            movement: StockMovementResponse::from(movement),
+        })
+    }
+
+    /// Hold stock for `ttl_seconds` (or `default_ttl_seconds`)
+    pub async fn reserve_stock(
+        &self,
+        product_id: Uuid,
+        request: CreateReservationRequest,
+        default_ttl_seconds: i64,
+        actor: Option<Uuid>,
+    ) -> Result<ReservationResponse, AppError> {
+        let ttl = request.ttl_seconds.unwrap_or(default_ttl_seconds);
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(ttl);
+
+        let (_, reservation) = self
+            .product_repository
+            .reserve_stock(
+                product_id,
+                request.quantity,
+                expires_at,
+                request.reference,
+                actor,
+            )
+            .await?;
+
+        Ok(ReservationResponse::from(reservation))
+    }
+
+    pub async fn get_reservation(&self, id: Uuid) -> Result<ReservationResponse, AppError> {
+        let reservation =
+            self.product_repository
+                .find_reservation(id)
+                .await?
+                .ok_or(AppError::NotFound {
+                    resource_type: "Reservation".to_string(),
+                    resource_id: Some(id.to_string()),
+                    error_id: uuid::Uuid::new_v4(),
+                })?;
+
+        Ok(ReservationResponse::from(reservation))
+    }
+
+    pub async fn confirm_reservation(
+        &self,
+        id: Uuid,
+        actor: Option<Uuid>,
+    ) -> Result<ReservationConfirmationResponse, AppError> {
+        let (reservation, product, movement) = self
+            .product_repository
+            .confirm_reservation(id, actor)
+            .await?;
+
+        Ok(ReservationConfirmationResponse {
+            reservation: ReservationResponse::from(reservation),
+            product: self.response_with_details(product).await?,
+            movement: StockMovementResponse::from(movement),
+        })
+    }
+
+    pub async fn release_reservation(&self, id: Uuid) -> Result<ReservationResponse, AppError> {
+        let reservation = self.product_repository.release_reservation(id).await?;
+        Ok(ReservationResponse::from(reservation))
+    }
+
+    pub async fn get_stock_movements(
+        &self,
+        product_id: Uuid,
+        page: Option<u64>,
+        per_page: Option<u64>,
+    ) -> Result<StockMovementListResponse, AppError> {
+        let page = page.unwrap_or(1).max(1);
+        let per_page = per_page.unwrap_or(20).clamp(1, 100);
+
+        let (movements, total) = self
+            .product_repository
+            .find_stock_movements(product_id, page, per_page)
+            .await?;
+
+        if total == 0
+            && !self
+                .product_repository
+                .exists_including_deleted(product_id)
+                .await?
+        {
+            return Err(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+
+        Ok(StockMovementListResponse {
+            product_id,
+            movements: movements
+                .into_iter()
+                .map(StockMovementResponse::from)
+                .collect(),
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// Validate parsed rows and insert the valid ones. In atomic mode nothing is
+    /// written unless every row is valid and inserts cleanly.
+    pub async fn import_products(
+        &self,
+        rows: Vec<ImportRow>,
+        format: TransferFormat,
+        atomic: bool,
+        user_id: Option<Uuid>,
+        default_currency: &str,
+    ) -> Result<ProductImportReport, AppError> {
+        let total_rows = rows.len();
+        let mut errors = Vec::new();
+        let mut valid = Vec::new();
+
+        for row in rows {
+            let request = row.result.and_then(|mut request| {
+                request.validate().map_err(|e| match AppError::from(e) {
+                    AppError::ValidationError { message, .. } => message,
+                    other => other.to_string(),
+                })?;
+                // Audit columns always come from the caller, never the file
+                request.created_by = user_id;
+                request.updated_by = user_id;
+                request
+                    .currency
+                    .get_or_insert_with(|| default_currency.to_string());
+                Ok(request)
+            });
+
+            match request {
+                Ok(request) => valid.push((row.line, request)),
+                Err(message) => errors.push(ProductImportRowError {
+                    line: row.line,
+                    message,
+                }),
+            }
+        }
+
+        let (imported, committed) = if atomic && !errors.is_empty() {
+            (0, false)
+        } else {
+            let result = self.product_repository.create_many(valid, atomic).await?;
+            errors.extend(result.errors);
+            (result.inserted, result.committed)
+        };
+
+        errors.sort_by_key(|error| error.line);
+
+        Ok(ProductImportReport {
+            format: format.name().to_string(),
+            atomic,
+            committed,
+            total_rows,
+            imported,
+            failed: errors.len(),
+            errors,
+        })
+    }
+
+    /// Stream every product matching `search_request`, walking the keyset cursor
+    /// so memory use stays flat regardless of table size. The first batch is
+    /// fetched eagerly so bad filters fail before the response starts.
+    pub async fn export_products(
+        &self,
+        mut search_request: ProductSearchRequest,
+        format: TransferFormat,
+        conversion: CurrencyConversion,
+    ) -> Result<impl Stream<Item = Result<Bytes, AppError>> + Send + 'static, AppError>
+    where
+        T: Send + Sync + 'static,
+    {
+        search_request.page = None;
+        search_request.cursor = None;
+        search_request.per_page = Some(EXPORT_BATCH_SIZE);
+        search_request.include_total = Some(false);
+        search_request.include_facets = None;
+
+        let first = self
+            .product_repository
+            .search(search_request.clone(), &conversion)
+            .await?;
+
+        let mut head = export_header(format);
+        head.extend(export_rows(&first.products, format)?);
+
+        let repository = self.product_repository.clone();
+        let rest = stream::unfold(first.next_cursor, move |cursor| {
+            let repository = repository.clone();
+            let mut request = search_request.clone();
+            let conversion = conversion.clone();
+            async move {
+                request.cursor = Some(cursor?);
+                let batch = match repository.search(request, &conversion).await {
+                    Ok(page) => export_rows(&page.products, format)
+                        .map(|bytes| (Bytes::from(bytes), page.next_cursor)),
+                    Err(e) => Err(e),
+                };
+                // Stop after an error; the client sees a truncated body
+                Some(match batch {
+                    Ok((bytes, next)) => (Ok(bytes), next),
+                    Err(e) => (Err(e), None),
+                })
+            }
+        });
+
+        Ok(stream::once(async move { Ok(Bytes::from(head)) }).chain(rest))
+    }
+
     // NEW SEARCH METHODS
+    /// Price filters and buckets are in `conversion`'s currency; prices in the
+    /// response only when the request asked for a currency
     pub async fn search_products(
         &self,
         search_request: ProductSearchRequest,
+        conversion: &CurrencyConversion,
     ) -> Result<ProductSearchResponse, AppError> {
-        let page = search_request.page.unwrap_or(1);
-        let per_page = search_request.per_page.unwrap_or(10).min(100);
+        let page = search_request
+            .cursor
+            .is_none()
+            .then(|| search_request.page.unwrap_or(1));
+        let per_page = search_request.per_page.unwrap_or(10).clamp(1, 100);
 
-        let (products, total) = self
+        let result = self
             .product_repository
-            .search(search_request.clone())
+            .search(search_request.clone(), conversion)
             .await?;
 
-        let products = products.into_iter().map(ProductResponse::from).collect();
+        let facets = if search_request.include_facets.unwrap_or(false) {
+            Some(
+                self.product_repository
+                    .search_facets(search_request.clone(), conversion)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let products = self.responses_with_details(result.products).await?;
+        let products = convert_responses(
+            products,
+            search_request.currency.is_some().then_some(conversion),
+        )?;
 
         // Build filters applied summary
         let filters_applied = ProductSearchFilters {
             query: search_request.query,
+            search_mode: search_request
+                .search_mode
+                .unwrap_or_else(|| "simple".to_string()),
             category: search_request.category,
             price_range: match (search_request.min_price, search_request.max_price) {
                 (Some(min), Some(max)) => Some((min, max)),
@@ -113,13 +684,19 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
                 _ => None,
             },
             in_stock: search_request.in_stock,
+            sku: search_request.sku,
+            variant_options: search_request.variant_options,
+            currency: conversion.currency().to_string(),
         };
 
         Ok(ProductSearchResponse {
             products,
-            total,
+            total: result.total,
             page,
             per_page,
+            next_cursor: result.next_cursor,
+            highlights: result.highlights,
+            facets,
             filters_applied,
         })
     }
@@ -127,21 +704,28 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
     pub async fn get_products_by_category(
         &self,
         category: &str,
+        include_descendants: bool,
     ) -> Result<Vec<ProductResponse>, AppError> {
-        let products = self.product_repository.find_by_category(category).await?;
-        Ok(products.into_iter().map(ProductResponse::from).collect())
+        let products = self
+            .product_repository
+            .find_by_category(category, include_descendants)
+            .await?;
+        self.responses_with_details(products).await
     }
 
     pub async fn get_products_by_price_range(
         &self,
         min_price: rust_decimal::Decimal,
         max_price: rust_decimal::Decimal,
+        conversion: &CurrencyConversion,
+        convert_prices: bool,
     ) -> Result<Vec<ProductResponse>, AppError> {
         let products = self
             .product_repository
-            .find_by_price_range(min_price, max_price)
+            .find_by_price_range(min_price, max_price, conversion)
             .await?;
-        Ok(products.into_iter().map(ProductResponse::from).collect())
+        let products = self.responses_with_details(products).await?;
+        convert_responses(products, convert_prices.then_some(conversion))
     }
 
     pub async fn get_low_stock_products(
@@ -149,11 +733,14 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
         threshold: i32,
     ) -> Result<Vec<ProductResponse>, AppError> {
         let products = self.product_repository.find_low_stock(threshold).await?;
-        Ok(products.into_iter().map(ProductResponse::from).collect())
+        self.responses_with_details(products).await
     }
 
-    pub async fn get_product_stats(&self) -> Result<ProductStatsResponse, AppError> {
-        self.product_repository.get_product_stats().await
+    pub async fn get_product_stats(
+        &self,
+        conversion: &CurrencyConversion,
+    ) -> Result<ProductStatsResponse, AppError> {
+        self.product_repository.get_product_stats(conversion).await
     }
 
     pub async fn get_similar_products(
@@ -165,28 +752,173 @@ impl<T: ProductRepositoryTrait> ProductService<T> {
             .product_repository
             .find_similar_products(product_name, limit)
             .await?;
-        Ok(products.into_iter().map(ProductResponse::from).collect())
+        self.responses_with_details(products).await
     }
 
     pub async fn get_trending_categories(
         &self,
         limit: u64,
+        conversion: &CurrencyConversion,
     ) -> Result<Vec<crate::models::CategoryStats>, AppError> {
-        self.product_repository.get_trending_categories(limit).await
+        self.product_repository
+            .get_trending_categories(limit, conversion)
+            .await
+    }
+}
+
+/// Per-item results in request order. `None` marks an item that an atomic
+/// batch never got to.
+fn batch_report(
+    ids: &[Uuid],
+    outcomes: Vec<Option<BatchOutcome>>,
+    atomic: bool,
+    committed: bool,
+    success: BatchItemStatus,
+) -> BatchWriteReport {
+    let results: Vec<BatchItemResult> = ids
+        .iter()
+        .zip(outcomes)
+        .map(|(&id, outcome)| match outcome {
+            Some(Ok(product)) => BatchItemResult {
+                id,
+                status: if committed {
+                    success
+                } else {
+                    BatchItemStatus::RolledBack
+                },
+                product,
+                error: None,
+            },
+            Some(Err(e)) => BatchItemResult {
+                id,
+                status: BatchItemStatus::Failed,
+                product: None,
+                error: Some(batch_item_error(&e)),
+            },
+            None => BatchItemResult {
+                id,
+                status: BatchItemStatus::Skipped,
+                product: None,
+                error: None,
+            },
+        })
+        .collect();
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    BatchWriteReport {
+        atomic,
+        committed,
+        total: ids.len(),
+        succeeded: count(success),
+        failed: count(BatchItemStatus::Failed),
+        results,
+    }
+}
+
+/// The code and status the item would have failed with as a single request
+fn batch_item_error(error: &AppError) -> BatchItemError {
+    let definition = ErrorRegistry::get_definition(error);
+    let message = match error {
+        AppError::ValidationError { message, .. }
+        | AppError::BadRequest { message, .. }
+        | AppError::Conflict { message, .. } => message.clone(),
+        AppError::BusinessRuleViolation { context, .. } => context.clone(),
+        _ => definition.message.to_string(),
+    };
+
+    BatchItemError {
+        code: definition.code.to_string(),
+        status: definition.status_code.as_u16(),
+        message,
+    }
+}
+
+/// Restate both prices in the requested currency
+fn convert_responses(
+    responses: Vec<ProductResponse>,
+    conversion: Option<&CurrencyConversion>,
+) -> Result<Vec<ProductResponse>, AppError> {
+    let Some(conversion) = conversion else {
+        return Ok(responses);
+    };
+
+    responses
+        .into_iter()
+        .map(|response| {
+            let convert = |amount| {
+                conversion
+                    .convert(amount, &response.currency)
+                    .ok_or_else(|| {
+                        business_rule_error(
+                            "exchange_rate_missing",
+                            &format!("No exchange rate is configured for {}", response.currency),
+                        )
+                    })
+            };
+            Ok(ProductResponse {
+                price: convert(response.price)?,
+                effective_price: convert(response.effective_price)?,
+                currency: conversion.currency().to_string(),
+                ..response
+            })
+        })
+        .collect()
+}
+
+impl From<crate::entities::stock_movement::Model> for StockMovementResponse {
+    fn from(movement: crate::entities::stock_movement::Model) -> Self {
+        Self {
+            id: movement.id,
+            product_id: movement.product_id,
+            reason: movement.reason,
+            quantity_delta: movement.quantity_delta,
+            quantity_after: movement.quantity_after,
+            reference: movement.reference,
+            note: movement.note,
+            created_by: movement.created_by,
+            created_at: movement.created_at,
+        }
+    }
+}
+
+impl From<crate::entities::stock_reservation::Model> for ReservationResponse {
+    fn from(reservation: crate::entities::stock_reservation::Model) -> Self {
+        Self {
+            id: reservation.id,
+            product_id: reservation.product_id,
+            quantity: reservation.quantity,
+            status: reservation.status,
+            reference: reservation.reference,
+            expires_at: reservation.expires_at,
+            created_by: reservation.created_by,
+            created_at: reservation.created_at,
+            resolved_at: reservation.resolved_at,
+        }
     }
 }
 
 impl From<crate::entities::product::Model> for ProductResponse {
     fn from(product: crate::entities::product::Model) -> Self {
+        let available_quantity = product.available_quantity();
         Self {
             id: product.id,
             name: product.name,
             description: product.description,
             price: product.price,
+            effective_price: product.price,
+            currency: product.currency,
             quantity: product.quantity,
+            reserved_quantity: product.reserved_quantity,
+            available_quantity,
             category: product.category,
+            category_id: product.category_id,
+            sku: product.sku,
+            barcode: product.barcode,
+            images: Vec::new(),
             created_at: product.created_at,
             updated_at: product.updated_at,
+            version: product.version,
+            deleted_at: product.deleted_at,
         }
     }
 }
@@ -0,0 +1,194 @@
+use crate::{
+    entities::{product, product_image},
+    error::{validation_error, AppError},
+    models::{ProductImageListResponse, ProductImageResponse, ReorderImagesRequest},
+    repository::product_image::{
+        ensure_image_capacity, NewProductImage, ProductImageRepositoryTrait,
+    },
+    storage::BlobStore,
+    utils::{image_extension, validate_image},
+};
+use axum::body::Bytes;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// One file of a multipart upload
+#[derive(Debug, Clone)]
+pub struct ImageUpload {
+    pub content_type: Option<String>,
+    pub bytes: Bytes,
+}
+
+pub struct ProductImageService<T: ProductImageRepositoryTrait> {
+    image_repository: Arc<T>,
+    blob_store: Arc<dyn BlobStore>,
+}
+
+impl<T: ProductImageRepositoryTrait> ProductImageService<T> {
+    pub fn new(image_repository: Arc<T>, blob_store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            image_repository,
+            blob_store,
+        }
+    }
+
+    pub async fn list_images(
+        &self,
+        product_id: Uuid,
+    ) -> Result<ProductImageListResponse, AppError> {
+        self.parent(product_id).await?;
+        let images = self.image_repository.find_by_product(product_id).await?;
+
+        Ok(image_list(product_id, images))
+    }
+
+    /// Validate every file before storing any, then store the blobs and append
+    /// the image records. Blobs of a failed insert are removed again.
+    pub async fn upload_images(
+        &self,
+        product_id: Uuid,
+        uploads: Vec<ImageUpload>,
+        max_image_bytes: usize,
+        actor: Option<Uuid>,
+    ) -> Result<ProductImageListResponse, AppError> {
+        if uploads.is_empty() {
+            return Err(validation_error("image", "No image files were uploaded"));
+        }
+
+        self.parent(product_id).await?;
+        // Fail fast before storing any blobs; create_many checks again under the lock
+        let existing = self.image_repository.find_by_product(product_id).await?;
+        ensure_image_capacity(existing.len(), uploads.len())?;
+
+        let mut checked = Vec::with_capacity(uploads.len());
+        for upload in uploads {
+            let content_type = validate_image(
+                upload.content_type.as_deref(),
+                &upload.bytes,
+                max_image_bytes,
+            )?;
+            checked.push((content_type, upload.bytes));
+        }
+
+        let mut stored = Vec::with_capacity(checked.len());
+        for (content_type, bytes) in checked {
+            let id = Uuid::new_v4();
+            let blob_key = format!(
+                "products/{product_id}/{id}.{}",
+                image_extension(content_type)
+            );
+            let size_bytes = bytes.len() as i64;
+
+            match self.blob_store.put(&blob_key, content_type, bytes).await {
+                Ok(url) => stored.push(NewProductImage {
+                    id,
+                    blob_key,
+                    url,
+                    content_type: content_type.to_string(),
+                    size_bytes,
+                }),
+                Err(e) => {
+                    self.delete_blobs(stored.into_iter().map(|image| image.blob_key))
+                        .await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let blob_keys: Vec<String> = stored.iter().map(|image| image.blob_key.clone()).collect();
+        match self
+            .image_repository
+            .create_many(product_id, stored, actor)
+            .await
+        {
+            Ok(images) => Ok(image_list(product_id, images)),
+            Err(e) => {
+                self.delete_blobs(blob_keys).await;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn reorder_images(
+        &self,
+        product_id: Uuid,
+        request: ReorderImagesRequest,
+    ) -> Result<ProductImageListResponse, AppError> {
+        self.parent(product_id).await?;
+        let images = self
+            .image_repository
+            .reorder(product_id, request.image_ids)
+            .await?;
+
+        Ok(image_list(product_id, images))
+    }
+
+    pub async fn delete_image(&self, product_id: Uuid, image_id: Uuid) -> Result<(), AppError> {
+        let image = self
+            .image_repository
+            .find_by_id(product_id, image_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "ProductImage".to_string(),
+                resource_id: Some(image_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        let blob_key = image.blob_key.clone();
+        self.image_repository.delete(image).await?;
+        self.delete_blobs([blob_key]).await;
+
+        Ok(())
+    }
+
+    /// Blob keys of a product's images; collect them before purging the product,
+    /// since purging removes the image records
+    pub async fn product_blob_keys(&self, product_id: Uuid) -> Result<Vec<String>, AppError> {
+        self.image_repository.find_blob_keys(product_id).await
+    }
+
+    /// Best-effort blob cleanup once the records are gone. A blob that can't be
+    /// deleted is only logged; it no longer belongs to anything.
+    pub async fn delete_blobs(&self, blob_keys: impl IntoIterator<Item = String>) {
+        for blob_key in blob_keys {
+            if let Err(e) = self.blob_store.delete(&blob_key).await {
+                tracing::warn!(
+                    blob_key = %blob_key,
+                    error = ?e,
+                    "Failed to delete orphaned image blob"
+                );
+            }
+        }
+    }
+
+    async fn parent(&self, product_id: Uuid) -> Result<product::Model, AppError> {
+        self.image_repository
+            .find_parent(product_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+}
+
+fn image_list(product_id: Uuid, images: Vec<product_image::Model>) -> ProductImageListResponse {
+    ProductImageListResponse {
+        product_id,
+        images: images.into_iter().map(ProductImageResponse::from).collect(),
+    }
+}
+
+impl From<product_image::Model> for ProductImageResponse {
+    fn from(image: product_image::Model) -> Self {
+        Self {
+            id: image.id,
+            url: image.url,
+            content_type: image.content_type,
+            size_bytes: image.size_bytes,
+            position: image.position,
+            created_at: image.created_at,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    entities::{product, product_variant},
+    error::{validation_error, AppError},
+    models::{
+        normalize_options, CreateVariantRequest, UpdateVariantRequest, VariantListResponse,
+        VariantResponse,
+    },
+    repository::product_variant::{ProductVariantRepositoryTrait, VariantChanges},
+};
+use std::{collections::BTreeMap, sync::Arc};
+use uuid::Uuid;
+
+pub struct ProductVariantService<T: ProductVariantRepositoryTrait> {
+    variant_repository: Arc<T>,
+}
+
+impl<T: ProductVariantRepositoryTrait> ProductVariantService<T> {
+    pub fn new(variant_repository: Arc<T>) -> Self {
+        Self { variant_repository }
+    }
+
+    pub async fn list_variants(&self, product_id: Uuid) -> Result<VariantListResponse, AppError> {
+        let product = self.parent(product_id).await?;
+        let variants = self.variant_repository.find_by_product(product_id).await?;
+
+        Ok(VariantListResponse {
+            product_id,
+            variants: variants
+                .into_iter()
+                .map(|variant| variant_response(variant, &product))
+                .collect(),
+        })
+    }
+
+    pub async fn get_variant(
+        &self,
+        product_id: Uuid,
+        variant_id: Uuid,
+    ) -> Result<VariantResponse, AppError> {
+        let product = self.parent(product_id).await?;
+        let variant = self.variant(product_id, variant_id).await?;
+
+        Ok(variant_response(variant, &product))
+    }
+
+    pub async fn create_variant(
+        &self,
+        product_id: Uuid,
+        request: CreateVariantRequest,
+    ) -> Result<VariantResponse, AppError> {
+        let product = self.parent(product_id).await?;
+
+        let variant = self
+            .variant_repository
+            .create(
+                product_id,
+                request.sku.trim().to_string(),
+                options_json(request.options)?,
+                request.price,
+                request.quantity,
+            )
+            .await?;
+
+        Ok(variant_response(variant, &product))
+    }
+
+    pub async fn update_variant(
+        &self,
+        product_id: Uuid,
+        variant_id: Uuid,
+        request: UpdateVariantRequest,
+    ) -> Result<VariantResponse, AppError> {
+        if request.clear_price && request.price.is_some() {
+            return Err(validation_error(
+                "clear_price",
+                "Send either a new price or clear_price, not both",
+            ));
+        }
+
+        let product = self.parent(product_id).await?;
+        let variant = self.variant(product_id, variant_id).await?;
+
+        let changes = VariantChanges {
+            sku: request.sku.map(|sku| sku.trim().to_string()),
+            options: request.options.map(options_json).transpose()?,
+            price: if request.clear_price {
+                Some(None)
+            } else {
+                request.price.map(Some)
+            },
+            quantity: request.quantity,
+        };
+        let variant = self.variant_repository.update(variant, changes).await?;
+
+        Ok(variant_response(variant, &product))
+    }
+
+    pub async fn delete_variant(&self, product_id: Uuid, variant_id: Uuid) -> Result<(), AppError> {
+        self.parent(product_id).await?;
+        let variant = self.variant(product_id, variant_id).await?;
+
+        self.variant_repository.delete(variant).await
+    }
+
+    async fn parent(&self, product_id: Uuid) -> Result<product::Model, AppError> {
+        self.variant_repository
+            .find_parent(product_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "Product".to_string(),
+                resource_id: Some(product_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+
+    async fn variant(
+        &self,
+        product_id: Uuid,
+        variant_id: Uuid,
+    ) -> Result<product_variant::Model, AppError> {
+        self.variant_repository
+            .find_by_id(product_id, variant_id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "ProductVariant".to_string(),
+                resource_id: Some(variant_id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+}
+
+fn options_json(options: BTreeMap<String, String>) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(normalize_options(options)).map_err(|e| AppError::InternalServerError {
+        context: Some(format!("Failed to encode variant options: {e}")),
+        error_id: uuid::Uuid::new_v4(),
+    })
+}
+
+/// Variant as returned by the API, priced against its parent product
+fn variant_response(
+    variant: product_variant::Model,
+    product: &product::Model,
+) -> VariantResponse {
+    let effective_price = variant.effective_price(product.price);
+    let options = serde_json::from_value(variant.options).unwrap_or_default();
+
+    VariantResponse {
+        id: variant.id,
+        product_id: variant.product_id,
+        sku: variant.sku,
+        options,
+        price: variant.price,
+        effective_price,
+        quantity: variant.quantity,
+        created_at: variant.created_at,
+        updated_at: variant.updated_at,
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{
+    cache::ProductCache,
+    repository::{
+        cached_product::CachedProductRepository,
+        product::{ProductRepository, ProductRepositoryTrait},
+    },
+};
+use sea_orm::DatabaseConnection;
+use std::{sync::Arc, time::Duration};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Expire stock reservations past their `expires_at` every `interval`, so the
+/// held units become available again. Runs until the runtime shuts down.
+pub fn spawn_reservation_expiry(
+    db: DatabaseConnection,
+    product_cache: Arc<dyn ProductCache>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    let repository = CachedProductRepository::new(ProductRepository::new(db), product_cache);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match repository.expire_reservations().await {
+                Ok(0) => {}
+                Ok(expired) => tracing::info!(expired, "Expired stale stock reservations"),
+                Err(e) => tracing::warn!(error = %e, "Failed to expire stock reservations"),
+            }
+        }
+    })
+}
@@ -0,0 +1,209 @@
+use crate::{
+    error::AppError,
+    models::Claims,
+    repository::auth::{AuthRepository, AuthRepositoryTrait},
+};
+use sea_orm::DatabaseConnection;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Access tokens that must stop working before they expire: single tokens
+/// after a logout, and every token of a user issued up to their deactivation.
+/// Entries are only needed until the tokens they cover expire, so the list
+/// stays as small as the number of recent revocations. The revocations are
+/// stored in the database; this is each replica's cached copy, so requests are
+/// checked without a query. A replica sees its own revocations right away and
+/// the other replicas' after its next `reload`.
+#[derive(Debug, Default)]
+pub struct TokenDenylist {
+    /// jti -> the token's `exp`
+    tokens: Mutex<HashMap<String, u64>>,
+    users: Mutex<UserRevocations>,
+}
+
+#[derive(Debug, Default)]
+struct UserRevocations {
+    /// user id -> (revoked at, entry needed until)
+    entries: HashMap<String, (u64, u64)>,
+    /// Bumped by every local revoke or clear
+    generation: u64,
+    /// user id -> generation of its last local revoke or clear, kept until a
+    /// reload that started after it has seen the stored state
+    changed: HashMap<String, u64>,
+}
+
+impl UserRevocations {
+    fn record_change(&mut self, user_id: &str) {
+        self.generation += 1;
+        self.changed.insert(user_id.to_string(), self.generation);
+    }
+}
+
+impl TokenDenylist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject the token with this `jti` until it expires at `exp`
+    pub fn revoke_token(&self, jti: &str, exp: u64) {
+        self.tokens.lock().unwrap().insert(jti.to_string(), exp);
+    }
+
+    /// Reject every token of `user_id` issued at or before `revoked_at`.
+    /// `until` is when the last of them expires.
+    pub fn revoke_user(&self, user_id: &str, revoked_at: u64, until: u64) {
+        let mut users = self.users.lock().unwrap();
+        users
+            .entries
+            .insert(user_id.to_string(), (revoked_at, until));
+        users.record_change(user_id);
+    }
+
+    /// Accept tokens of `user_id` again, e.g. after a reactivation. Without
+    /// this, a token issued in the same second as the revocation is rejected.
+    pub fn clear_user(&self, user_id: &str) {
+        let mut users = self.users.lock().unwrap();
+        users.entries.remove(user_id);
+        users.record_change(user_id);
+    }
+
+    /// Refresh the cache from the stored revocations; returns how many there are.
+    /// Token revocations are only ever added, but user revocations are replaced
+    /// by the stored ones so a reactivation on another replica is picked up too.
+    /// Local changes are stored before they are made here, so only those made
+    /// while the stored revocations were being read can be missing from them;
+    /// those users keep their local entry.
+    pub async fn reload<R: AuthRepositoryTrait>(&self, repository: &R) -> Result<usize, AppError> {
+        let started = self.users.lock().unwrap().generation;
+        let (tokens, users) = repository.find_access_token_revocations().await?;
+        let count = tokens.len() + users.len();
+
+        self.tokens.lock().unwrap().extend(
+            tokens
+                .into_iter()
+                .map(|token| (token.jti, unix_seconds(token.expires_at))),
+        );
+        let mut stored: HashMap<String, (u64, u64)> = users
+            .into_iter()
+            .map(|user| {
+                (
+                    user.user_id.to_string(),
+                    (unix_seconds(user.revoked_at), unix_seconds(user.expires_at)),
+                )
+            })
+            .collect();
+
+        let mut local = self.users.lock().unwrap();
+        local.changed.retain(|_, generation| *generation > started);
+        for user_id in local.changed.keys() {
+            match local.entries.get(user_id) {
+                Some(entry) => stored.insert(user_id.clone(), *entry),
+                None => stored.remove(user_id),
+            };
+        }
+        local.entries = stored;
+
+        Ok(count)
+    }
+
+    pub fn is_revoked(&self, claims: &Claims) -> bool {
+        if self.tokens.lock().unwrap().contains_key(&claims.jti) {
+            return true;
+        }
+        self.users
+            .lock()
+            .unwrap()
+            .entries
+            .get(&claims.sub)
+            .is_some_and(|(revoked_at, _)| claims.iat as u64 <= *revoked_at)
+    }
+
+    /// Drop entries whose tokens have all expired; returns how many
+    pub fn purge_expired(&self, now: u64) -> usize {
+        let mut tokens = self.tokens.lock().unwrap();
+        let before = tokens.len();
+        tokens.retain(|_, exp| *exp > now);
+        let mut purged = before - tokens.len();
+
+        let mut users = self.users.lock().unwrap();
+        let before = users.entries.len();
+        users.entries.retain(|_, (_, until)| *until > now);
+        purged += before - users.entries.len();
+
+        purged
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.lock().unwrap().len() + self.users.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn unix_seconds(at: chrono::DateTime<chrono::Utc>) -> u64 {
+    at.timestamp().max(0) as u64
+}
+
+/// Every `interval`, reload the denylist from the database so revocations made
+/// by other replicas take effect here too
+pub fn spawn_token_denylist_sync(
+    db: DatabaseConnection,
+    denylist: Arc<TokenDenylist>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    let repository = AuthRepository::new(db);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            if let Err(e) = denylist.reload(&repository).await {
+                tracing::warn!(error = %e, "Failed to reload the token denylist");
+            }
+        }
+    })
+}
+
+/// Every `interval`, drop denylist entries that outlived their tokens and
+/// delete expired refresh tokens and revocations. Expired tokens are already
+/// refused; deleting them only keeps the tables small.
+pub fn spawn_token_purge(
+    db: DatabaseConnection,
+    denylist: Arc<TokenDenylist>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    let repository = AuthRepository::new(db);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            let now = chrono::Utc::now().timestamp().max(0) as u64;
+            let purged = denylist.purge_expired(now);
+            if purged > 0 {
+                tracing::debug!(purged, "Purged expired token denylist entries");
+            }
+
+            match repository.purge_expired_refresh_tokens().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!(purged, "Purged expired refresh tokens"),
+                Err(e) => tracing::warn!(error = %e, "Failed to purge refresh tokens"),
+            }
+            match repository.purge_expired_access_token_revocations().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!(purged, "Purged expired access token revocations"),
+                Err(e) => tracing::warn!(error = %e, "Failed to purge access token revocations"),
+            }
+        }
+    })
+}
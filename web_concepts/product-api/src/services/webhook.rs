@@ -0,0 +1,182 @@
+use crate::{
+    entities::{
+        webhook_delivery::DeliveryStatus, webhook_event::WebhookEventType, webhook_subscription,
+    },
+    error::AppError,
+    models::{
+        CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveryListResponse,
+        WebhookDeliveryResponse, WebhookListResponse, WebhookResponse,
+    },
+    repository::{
+        webhook_delivery::WebhookDeliveryRepositoryTrait,
+        webhook_subscription::{
+            NewWebhookSubscription, WebhookSubscriptionChanges, WebhookSubscriptionRepositoryTrait,
+        },
+    },
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Matches the default of `/products/low-stock`
+const DEFAULT_LOW_STOCK_THRESHOLD: i32 = 10;
+
+/// Each event type once, in declaration order
+fn unique_event_types(mut event_types: Vec<WebhookEventType>) -> Vec<WebhookEventType> {
+    event_types.sort_unstable();
+    event_types.dedup();
+    event_types
+}
+
+pub struct WebhookService<S: WebhookSubscriptionRepositoryTrait, D: WebhookDeliveryRepositoryTrait>
+{
+    subscription_repository: Arc<S>,
+    delivery_repository: Arc<D>,
+}
+
+impl<S: WebhookSubscriptionRepositoryTrait, D: WebhookDeliveryRepositoryTrait>
+    WebhookService<S, D>
+{
+    pub fn new(subscription_repository: Arc<S>, delivery_repository: Arc<D>) -> Self {
+        Self {
+            subscription_repository,
+            delivery_repository,
+        }
+    }
+
+    pub async fn list_webhooks(&self) -> Result<WebhookListResponse, AppError> {
+        let subscriptions = self.subscription_repository.find_all().await?;
+
+        Ok(WebhookListResponse {
+            webhooks: subscriptions
+                .into_iter()
+                .map(WebhookResponse::from)
+                .collect(),
+        })
+    }
+
+    pub async fn get_webhook(&self, id: Uuid) -> Result<WebhookResponse, AppError> {
+        let subscription = self.subscription(id).await?;
+        Ok(WebhookResponse::from(subscription))
+    }
+
+    pub async fn create_webhook(
+        &self,
+        request: CreateWebhookRequest,
+        actor: Option<Uuid>,
+    ) -> Result<WebhookResponse, AppError> {
+        let subscription = self
+            .subscription_repository
+            .create(NewWebhookSubscription {
+                url: request.url,
+                secret: request.secret,
+                event_types: unique_event_types(request.event_types),
+                low_stock_threshold: request
+                    .low_stock_threshold
+                    .unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD),
+                description: request.description,
+                created_by: actor,
+            })
+            .await?;
+
+        Ok(WebhookResponse::from(subscription))
+    }
+
+    pub async fn update_webhook(
+        &self,
+        id: Uuid,
+        request: UpdateWebhookRequest,
+    ) -> Result<WebhookResponse, AppError> {
+        let changes = WebhookSubscriptionChanges {
+            url: request.url,
+            secret: request.secret,
+            event_types: request.event_types.map(unique_event_types),
+            low_stock_threshold: request.low_stock_threshold,
+            description: request.description,
+            is_active: request.is_active,
+        };
+
+        let subscription = self.subscription_repository.update(id, changes).await?;
+        Ok(WebhookResponse::from(subscription))
+    }
+
+    pub async fn delete_webhook(&self, id: Uuid) -> Result<(), AppError> {
+        if !self.subscription_repository.delete(id).await? {
+            return Err(AppError::NotFound {
+                resource_type: "WebhookSubscription".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Delivery log of one subscription, newest first
+    pub async fn list_deliveries(
+        &self,
+        id: Uuid,
+        status: Option<DeliveryStatus>,
+        page: Option<u64>,
+        per_page: Option<u64>,
+    ) -> Result<WebhookDeliveryListResponse, AppError> {
+        self.subscription(id).await?;
+        let page = page.unwrap_or(1).max(1);
+        let per_page = per_page.unwrap_or(20).clamp(1, 100);
+
+        let (deliveries, total) = self
+            .delivery_repository
+            .find_by_subscription(id, status, page, per_page)
+            .await?;
+
+        Ok(WebhookDeliveryListResponse {
+            subscription_id: id,
+            deliveries: deliveries
+                .into_iter()
+                .map(|(delivery, event)| WebhookDeliveryResponse {
+                    id: delivery.id,
+                    event_id: delivery.event_id,
+                    event_type: event.as_ref().map(|event| event.event_type),
+                    status: delivery.status,
+                    attempts: delivery.attempts,
+                    next_attempt_at: (delivery.status == DeliveryStatus::Pending)
+                        .then_some(delivery.next_attempt_at),
+                    last_attempt_at: delivery.last_attempt_at,
+                    response_status: delivery.response_status,
+                    last_error: delivery.last_error,
+                    created_at: delivery.created_at,
+                    delivered_at: delivery.delivered_at,
+                    payload: event.map(|event| event.payload),
+                })
+                .collect(),
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    async fn subscription(&self, id: Uuid) -> Result<webhook_subscription::Model, AppError> {
+        self.subscription_repository
+            .find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound {
+                resource_type: "WebhookSubscription".to_string(),
+                resource_id: Some(id.to_string()),
+                error_id: uuid::Uuid::new_v4(),
+            })
+    }
+}
+
+impl From<webhook_subscription::Model> for WebhookResponse {
+    fn from(subscription: webhook_subscription::Model) -> Self {
+        Self {
+            event_types: subscription.event_types(),
+            id: subscription.id,
+            url: subscription.url,
+            low_stock_threshold: subscription.low_stock_threshold,
+            description: subscription.description,
+            is_active: subscription.is_active,
+            created_by: subscription.created_by,
+            created_at: subscription.created_at,
+            updated_at: subscription.updated_at,
+        }
+    }
+}
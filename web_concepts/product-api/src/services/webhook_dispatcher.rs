@@ -0,0 +1,179 @@
+use crate::{
+    error::AppError,
+    repository::webhook_delivery::{
+        DueDelivery, WebhookDeliveryRepository, WebhookDeliveryRepositoryTrait,
+    },
+};
+use futures::{stream, StreamExt};
+use hmac::{Hmac, Mac};
+use sea_orm::DatabaseConnection;
+use sha2::Sha256;
+use std::{sync::Arc, time::Duration};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+pub const WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+pub const WEBHOOK_EVENT_HEADER: &str = "x-webhook-event";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Longest wait between two attempts, however many have failed
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 60 * 60);
+/// Deliveries sent at the same time by one dispatch pass
+const MAX_CONCURRENT_DELIVERIES: usize = 8;
+
+/// Retry schedule and HTTP settings for webhook delivery
+#[derive(Debug, Clone)]
+pub struct WebhookDispatchConfig {
+    /// Attempts before a delivery is marked failed, the first one included
+    pub max_attempts: u32,
+    /// Wait after the first failure; doubles with every further failure
+    pub retry_base: Duration,
+    pub timeout: Duration,
+    /// Deliveries claimed per pass
+    pub batch_size: u64,
+}
+
+/// `sha256=` plus the hex HMAC-SHA256 of `"{timestamp}.{body}"` under the
+/// subscription secret. Receivers recompute it and compare, and reject old
+/// timestamps to stop replays.
+pub fn sign_webhook_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before retrying after failed attempt number `attempt` (1-based):
+/// `base`, `2 × base`, `4 × base`, ... capped at `MAX_RETRY_DELAY`
+pub fn retry_delay(attempt: u32, base: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    base.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+/// Sends claimed outbox entries to their subscribers and records the outcome
+pub struct WebhookDispatcher<T: WebhookDeliveryRepositoryTrait> {
+    delivery_repository: Arc<T>,
+    client: reqwest::Client,
+    config: WebhookDispatchConfig,
+}
+
+impl<T: WebhookDeliveryRepositoryTrait> WebhookDispatcher<T> {
+    pub fn new(
+        delivery_repository: Arc<T>,
+        config: WebhookDispatchConfig,
+    ) -> Result<Self, AppError> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| AppError::ConfigurationError {
+                parameter: "webhook_client".to_string(),
+                message: e.to_string(),
+                error_id: uuid::Uuid::new_v4(),
+            })?;
+
+        Ok(Self {
+            delivery_repository,
+            client,
+            config,
+        })
+    }
+
+    /// Attempt every due delivery once. Returns how many were attempted.
+    pub async fn dispatch_due(&self) -> Result<usize, AppError> {
+        // The lease outlasts a timed out attempt, so nobody else picks it up meanwhile
+        let lease = self.config.timeout + Duration::from_secs(30);
+        let deliveries = self
+            .delivery_repository
+            .claim_due(self.config.batch_size, lease)
+            .await?;
+        let attempted = deliveries.len();
+
+        stream::iter(deliveries)
+            .for_each_concurrent(MAX_CONCURRENT_DELIVERIES, |delivery| async move {
+                let id = delivery.id;
+                if let Err(e) = self.deliver(delivery).await {
+                    tracing::warn!(delivery_id = %id, error = %e, "Failed to record webhook delivery");
+                }
+            })
+            .await;
+
+        Ok(attempted)
+    }
+
+    async fn deliver(&self, delivery: DueDelivery) -> Result<(), AppError> {
+        let body = delivery.payload.to_string().into_bytes();
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign_webhook_payload(&delivery.secret, timestamp, &body);
+
+        let result = self
+            .client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, delivery.event_id.to_string())
+            .header(WEBHOOK_EVENT_HEADER, &delivery.event_type)
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await;
+
+        let (status, error) = match result {
+            Ok(response) if response.status().is_success() => {
+                return self
+                    .delivery_repository
+                    .mark_delivered(delivery.id, response.status().as_u16())
+                    .await;
+            }
+            Ok(response) => {
+                let status = response.status();
+                (
+                    Some(status.as_u16()),
+                    format!("Subscriber responded with {status}"),
+                )
+            }
+            Err(e) => (None, e.to_string()),
+        };
+
+        let attempt = u32::try_from(delivery.attempts).unwrap_or(0) + 1;
+        let retry_at = (attempt < self.config.max_attempts).then(|| {
+            let delay = retry_delay(attempt, self.config.retry_base);
+            chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default()
+        });
+        tracing::info!(
+            delivery_id = %delivery.id,
+            attempt,
+            error = %error,
+            retry_at = ?retry_at,
+            "Webhook delivery failed"
+        );
+
+        self.delivery_repository
+            .mark_failed(delivery.id, status, error, retry_at)
+            .await
+    }
+}
+
+/// Deliver due webhooks every `interval`. Runs until the runtime shuts down.
+pub fn spawn_webhook_dispatcher(
+    db: DatabaseConnection,
+    config: WebhookDispatchConfig,
+    interval: Duration,
+) -> Result<JoinHandle<()>, AppError> {
+    let dispatcher = WebhookDispatcher::new(Arc::new(WebhookDeliveryRepository::new(db)), config)?;
+
+    Ok(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match dispatcher.dispatch_due().await {
+                Ok(0) => {}
+                Ok(attempted) => tracing::debug!(attempted, "Dispatched webhooks"),
+                Err(e) => tracing::warn!(error = %e, "Failed to dispatch webhooks"),
+            }
+        }
+    }))
+}
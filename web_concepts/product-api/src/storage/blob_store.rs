@@ -0,0 +1,36 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use axum::body::Bytes;
+
+/// Storage for uploaded files such as product images.
+///
+/// Keys are `/`-separated relative paths (e.g. `products/{id}/{image_id}.png`)
+/// chosen by the caller. Implementations map them onto their backend: a
+/// directory tree for [`LocalBlobStore`](super::LocalBlobStore), object keys
+/// for an S3-compatible bucket.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Store `bytes` under `key`, replacing any existing blob, and return the
+    /// URL clients fetch it from
+    async fn put(&self, key: &str, content_type: &str, bytes: Bytes) -> Result<String, AppError>;
+
+    /// `None` if no blob is stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, AppError>;
+
+    /// Remove a blob; deleting a missing blob is not an error
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Keys must be relative paths of plain segments so no backend can be walked
+/// out of its root (`..`, absolute paths, empty segments)
+pub fn is_valid_blob_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_'))
+        })
+}
@@ -0,0 +1,97 @@
+use crate::{
+    error::AppError,
+    storage::blob_store::{is_valid_blob_key, BlobStore},
+};
+use async_trait::async_trait;
+use axum::body::Bytes;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+/// Blobs as files under a root directory, served by the app itself (see
+/// `MEDIA_ROOT` and `MEDIA_BASE_URL`)
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>, base_url: &str) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn url(&self, key: &str) -> String {
+        format!("{}/{key}", self.base_url)
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        if !is_valid_blob_key(key) {
+            return Err(AppError::validation_error(
+                Some("key".to_string()),
+                format!("Invalid blob key '{key}'"),
+            ));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+fn io_error(operation: &str, path: &Path, err: std::io::Error) -> AppError {
+    AppError::IoError {
+        operation: operation.to_string(),
+        path: Some(path.display().to_string()),
+        details: err.to_string(),
+        error_id: Uuid::new_v4(),
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, _content_type: &str, bytes: Bytes) -> Result<String, AppError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error("create_dir", parent, e))?;
+        }
+
+        // Write to a temporary name first so readers never see a partial file
+        let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        tokio::fs::write(&temp_path, &bytes)
+            .await
+            .map_err(|e| io_error("write", &temp_path, e))?;
+        if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(io_error("rename", &path, e));
+        }
+
+        Ok(self.url(key))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, AppError> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(Bytes::from(bytes))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error("read", &path, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("delete", &path, e)),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod blob_store;
+pub mod local;
+
+pub use blob_store::*;
+pub use local::*;
@@ -0,0 +1,30 @@
+/// GTIN check digit over the data digits (everything but the check digit).
+///
+/// Weights alternate 3, 1, 3, ... starting from the rightmost data digit, so
+/// the same routine serves EAN-13 and UPC-A.
+pub fn gtin_check_digit(data_digits: &[u8]) -> u8 {
+    let sum: u32 = data_digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| u32::from(*digit) * if index % 2 == 0 { 3 } else { 1 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Whether `code` is a 13-digit EAN-13 or 12-digit UPC-A with a correct check digit
+pub fn is_valid_barcode(code: &str) -> bool {
+    if !matches!(code.len(), 12 | 13) || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let digits: Vec<u8> = code.bytes().map(|b| b - b'0').collect();
+    let (check, data) = digits.split_last().expect("length checked above");
+    gtin_check_digit(data) == *check
+}
+
+/// EAN-13 form of a barcode: UPC-A codes get a leading zero, which keeps the check
+/// digit valid. A UPC-A label and its EAN-13 spelling identify the same item.
+pub fn normalize_barcode(code: &str) -> String {
+    format!("{code:0>13}")
+}
@@ -0,0 +1,85 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::BTreeMap;
+
+/// Converted amounts round half away from zero, like Postgres `round(numeric)`,
+/// so a price filtered in SQL and the price in the response always agree
+pub const CURRENCY_ROUNDING: RoundingStrategy = RoundingStrategy::MidpointAwayFromZero;
+
+/// ISO 4217 alphabetic code: three uppercase ASCII letters
+pub fn is_valid_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// Decimal places of the currency's minor unit (ISO 4217), 2 unless listed
+pub fn currency_minor_units(code: &str) -> u32 {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// Conversion of stored prices into one requested currency, over a snapshot of
+/// the exchange rates. Rates are units of a currency per one unit of the base
+/// currency; the base currency itself is always 1.
+#[derive(Debug, Clone)]
+pub struct CurrencyConversion {
+    currency: String,
+    rates: BTreeMap<String, Decimal>,
+}
+
+impl CurrencyConversion {
+    /// `None` when there is no rate for `currency`
+    pub fn new(currency: &str, rates: BTreeMap<String, Decimal>) -> Option<Self> {
+        rates.contains_key(currency).then(|| Self {
+            currency: currency.to_string(),
+            rates,
+        })
+    }
+
+    /// The target currency
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Round an amount in the target currency to its minor unit
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(currency_minor_units(&self.currency), CURRENCY_ROUNDING)
+    }
+
+    /// Multiplier from `from` into the target currency, `None` without a rate
+    pub fn factor(&self, from: &str) -> Option<Decimal> {
+        let from_rate = self.rates.get(from)?;
+        Some(self.rates[&self.currency] / from_rate)
+    }
+
+    /// `amount` in currency `from`, converted and rounded
+    pub fn convert(&self, amount: Decimal, from: &str) -> Option<Decimal> {
+        Some(self.round(amount * self.factor(from)?))
+    }
+
+    /// SQL for the multiplier from the currency in `currency_column` into the
+    /// target currency, NULL for a currency without a rate. The rates are
+    /// inlined; codes are validated and decimals print as plain numerals.
+    pub fn factor_sql(&self, currency_column: &str) -> String {
+        let cases: String = self
+            .rates
+            .iter()
+            .map(|(code, rate)| format!(" WHEN '{code}' THEN {rate}::numeric"))
+            .collect();
+        format!(
+            "({}::numeric / CASE {currency_column}{cases} END)",
+            self.rates[&self.currency]
+        )
+    }
+
+    /// SQL for `amount_sql` converted and rounded the way `convert` does it
+    pub fn amount_sql(&self, amount_sql: &str, currency_column: &str) -> String {
+        format!(
+            "ROUND({amount_sql} * {}, {})",
+            self.factor_sql(currency_column),
+            currency_minor_units(&self.currency)
+        )
+    }
+}
@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Keyset pagination position: the sort key of the last row on a page plus its id
+/// as a tie-breaker. Clients only ever see the opaque encoded form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Sort the cursor was issued for (e.g. "price:asc")
+    pub sort: String,
+    /// Value of the sort column for the last row
+    pub value: serde_json::Value,
+    /// Id of the last row
+    pub id: Uuid,
+}
+
+pub fn encode_cursor(cursor: &Cursor) -> String {
+    // Serializing a struct of strings/JSON values cannot fail
+    let json = serde_json::to_vec(cursor).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode_cursor(encoded: &str) -> Result<Cursor, AppError> {
+    URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| {
+            AppError::validation_error(Some("cursor".to_string()), "Invalid cursor".to_string())
+        })
+}
@@ -0,0 +1,39 @@
+/// Strong entity tag for a row version, e.g. `"3"`
+pub fn version_etag(version: i32) -> String {
+    format!("\"{version}\"")
+}
+
+/// Parsed `If-Match` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    /// `*`: any current representation matches
+    Any,
+    /// Row versions named by the header's entity tags. Tags we did not issue
+    /// (and weak tags, which never match under If-Match) are dropped, so an
+    /// empty list matches nothing.
+    Versions(Vec<i32>),
+}
+
+impl IfMatch {
+    pub fn parse(header: &str) -> Self {
+        if header.trim() == "*" {
+            return Self::Any;
+        }
+
+        let versions = header
+            .split(',')
+            .map(str::trim)
+            .filter_map(|tag| tag.strip_prefix('"')?.strip_suffix('"'))
+            .filter_map(|version| version.parse().ok())
+            .collect();
+
+        Self::Versions(versions)
+    }
+
+    pub fn matches(&self, version: i32) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Versions(versions) => versions.contains(&version),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::error::{validation_error, AppError};
+
+/// Image types accepted by `POST /products/:id/images`
+pub const ALLOWED_IMAGE_TYPES: [&str; 4] = ["image/jpeg", "image/png", "image/webp", "image/gif"];
+
+/// Content type of an image from its leading bytes, so a mislabelled upload
+/// can't slip through on its declared `Content-Type` alone
+pub fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else {
+        None
+    }
+}
+
+/// File extension used in blob keys
+pub fn image_extension(content_type: &str) -> &'static str {
+    match content_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        _ => "bin",
+    }
+}
+
+/// Check one upload against the allowed types and size limit and return its
+/// content type. The declared type must be allowed and match the file's bytes.
+pub fn validate_image(
+    declared_type: Option<&str>,
+    bytes: &[u8],
+    max_bytes: usize,
+) -> Result<&'static str, AppError> {
+    if bytes.is_empty() {
+        return Err(validation_error("image", "Image file is empty"));
+    }
+    if bytes.len() > max_bytes {
+        return Err(validation_error(
+            "image",
+            &format!("Image is larger than {max_bytes} bytes"),
+        ));
+    }
+
+    // Ignore parameters such as "; charset=binary"
+    let declared = declared_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase())
+        .ok_or_else(|| validation_error("content_type", "Image part has no Content-Type"))?;
+    if !ALLOWED_IMAGE_TYPES.contains(&declared.as_str()) {
+        return Err(validation_error(
+            "content_type",
+            &format!(
+                "Unsupported image type '{declared}', expected one of {}",
+                ALLOWED_IMAGE_TYPES.join(", ")
+            ),
+        ));
+    }
+
+    match sniff_image_type(bytes) {
+        Some(actual) if actual == declared => Ok(actual),
+        _ => Err(validation_error(
+            "content_type",
+            &format!("File content does not match its Content-Type '{declared}'"),
+        )),
+    }
+}
@@ -1,7 +1,26 @@
 use crate::{entities::user::UserRole, error::AppError, models::Claims};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Random bytes in a refresh token
+const REFRESH_TOKEN_BYTES: usize = 32;
+
+/// Seconds since the Unix epoch
+pub fn unix_now() -> Result<u64, AppError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .map_err(|_| AppError::InternalServerError {
+            context: Some("System clock is before the Unix epoch".to_string()),
+            error_id: uuid::Uuid::new_v4(),
+        })
+}
+
+/// Sign an access token. Each token gets its own `jti` so it can be revoked
+/// on its own.
 pub fn create_jwt(
     user_id: &str,
     username: &str,
@@ -9,19 +28,15 @@ pub fn create_jwt(
     secret: &str,
     expiration: i64,
 ) -> Result<String, AppError> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|_| AppError::InternalServerError {
-            context: Some("JWT signing failed".to_string()),
-            error_id: uuid::Uuid::new_v4(),
-        })?
-        .as_secs();
+    let now = unix_now()?;
 
     let claims = Claims {
         sub: user_id.to_owned(),
         username: username.to_owned(),
         role: role.clone(), // `UserRole` is `Clone` (derive it if needed)
         exp: (now + expiration as u64) as usize,
+        iat: now as usize,
+        jti: uuid::Uuid::new_v4().to_string(),
     };
 
     encode(
@@ -41,3 +56,16 @@ pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims, AppError> {
     .map(|data| data.claims)
     .map_err(AppError::from)
 }
+
+/// A new opaque refresh token: 256 random bits, URL-safe base64
+pub fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; REFRESH_TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// What gets stored for a refresh token: SHA-256, hex encoded. The tokens are
+/// random, so a fast unsalted hash is enough to make a leaked table useless.
+pub fn hash_refresh_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
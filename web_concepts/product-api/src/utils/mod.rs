@@ -1,5 +1,21 @@
+pub mod barcode;
+pub mod currency;
+pub mod cursor;
+pub mod etag;
+pub mod image;
 pub mod jwt;
 pub mod password;
+pub mod product_io;
+pub mod search;
+pub mod slug;
 
+pub use barcode::*;
+pub use currency::*;
+pub use cursor::*;
+pub use etag::*;
+pub use image::*;
 pub use jwt::*;
 pub use password::*;
+pub use product_io::*;
+pub use search::*;
+pub use slug::*;
@@ -0,0 +1,213 @@
+use crate::{entities::product, error::AppError, models::CreateProductRequest};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// File formats accepted by `POST /products/import` and produced by `GET /products/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFormat {
+    Csv,
+    Ndjson,
+}
+
+impl TransferFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        // Ignore parameters such as "; charset=utf-8"
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime.to_ascii_lowercase().as_str() {
+            "text/csv" => Some(Self::Csv),
+            "application/x-ndjson" | "application/ndjson" | "application/jsonl" => {
+                Some(Self::Ndjson)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+/// One row of an uploaded file. `line` is 1-based and counts the CSV header.
+#[derive(Debug)]
+pub struct ImportRow {
+    pub line: usize,
+    pub result: Result<CreateProductRequest, String>,
+}
+
+/// Split an upload into rows. Rows that cannot be deserialized are kept as
+/// errors so the caller can report them next to validation failures.
+pub fn parse_import(body: &[u8], format: TransferFormat) -> Vec<ImportRow> {
+    match format {
+        TransferFormat::Csv => parse_csv(body),
+        TransferFormat::Ndjson => parse_ndjson(body),
+    }
+}
+
+fn parse_csv(body: &[u8]) -> Vec<ImportRow> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body);
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            return vec![ImportRow {
+                line: 1,
+                result: Err(format!("Invalid CSV header: {e}")),
+            }]
+        }
+    };
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            // Header is line 1; fall back to the record index if csv has no position
+            let fallback_line = index + 2;
+            match record {
+                Ok(record) => ImportRow {
+                    line: record
+                        .position()
+                        .map(|position| position.line() as usize)
+                        .unwrap_or(fallback_line),
+                    result: record
+                        .deserialize(Some(&headers))
+                        .map_err(|e| format!("Invalid row: {e}")),
+                },
+                Err(e) => ImportRow {
+                    line: e
+                        .position()
+                        .map(|position| position.line() as usize)
+                        .unwrap_or(fallback_line),
+                    result: Err(format!("Invalid row: {e}")),
+                },
+            }
+        })
+        .collect()
+}
+
+fn parse_ndjson(body: &[u8]) -> Vec<ImportRow> {
+    let text = String::from_utf8_lossy(body);
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| ImportRow {
+            line: index + 1,
+            result: serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {e}")),
+        })
+        .collect()
+}
+
+/// Flat export record: the import columns plus identifiers and timestamps,
+/// so an export can be edited and imported again
+#[derive(Debug, Serialize)]
+pub struct ProductExportRow {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub quantity: i32,
+    pub category: Option<String>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub currency: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+const EXPORT_COLUMNS: [&str; 11] = [
+    "id",
+    "name",
+    "description",
+    "price",
+    "quantity",
+    "category",
+    "sku",
+    "barcode",
+    "currency",
+    "created_at",
+    "updated_at",
+];
+
+impl From<&product::Model> for ProductExportRow {
+    fn from(product: &product::Model) -> Self {
+        Self {
+            id: product.id,
+            name: product.name.clone(),
+            description: product.description.clone(),
+            price: product.price,
+            quantity: product.quantity,
+            category: product.category.clone(),
+            sku: product.sku.clone(),
+            barcode: product.barcode.clone(),
+            currency: product.currency.clone(),
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+        }
+    }
+}
+
+/// Leading bytes of an export (the CSV header row, nothing for NDJSON)
+pub fn export_header(format: TransferFormat) -> Vec<u8> {
+    match format {
+        TransferFormat::Csv => format!("{}\n", EXPORT_COLUMNS.join(",")).into_bytes(),
+        TransferFormat::Ndjson => Vec::new(),
+    }
+}
+
+/// Serialize one batch of products
+pub fn export_rows(
+    products: &[product::Model],
+    format: TransferFormat,
+) -> Result<Vec<u8>, AppError> {
+    let serialize_error = |e: String| AppError::ParseError {
+        data_type: format.name().to_string(),
+        message: e,
+        error_id: Uuid::new_v4(),
+    };
+
+    match format {
+        TransferFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(Vec::new());
+            for product in products {
+                writer
+                    .serialize(ProductExportRow::from(product))
+                    .map_err(|e| serialize_error(e.to_string()))?;
+            }
+            writer
+                .into_inner()
+                .map_err(|e| serialize_error(e.to_string()))
+        }
+        TransferFormat::Ndjson => {
+            let mut buffer = Vec::new();
+            for product in products {
+                serde_json::to_writer(&mut buffer, &ProductExportRow::from(product))
+                    .map_err(|e| serialize_error(e.to_string()))?;
+                buffer.push(b'\n');
+            }
+            Ok(buffer)
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/// Turn user input into a Postgres `to_tsquery` expression.
+///
+/// - bare words are AND-ed together: `wireless mouse` -> `wireless & mouse`
+/// - double quotes make a phrase: `"coffee mug"` -> `(coffee <-> mug)`
+/// - a trailing `*` makes a prefix match: `lap*` -> `lap:*`
+///
+/// Anything other than letters and digits is dropped so the result is always
+/// valid tsquery syntax. Returns `None` when nothing searchable is left.
+pub fn build_tsquery(input: &str) -> Option<String> {
+    let mut parts = Vec::new();
+
+    // Splitting on quotes leaves phrases at the odd indices
+    for (index, segment) in input.split('"').enumerate() {
+        if index % 2 == 1 {
+            if let Some(phrase) = phrase_query(segment) {
+                parts.push(phrase);
+            }
+        } else {
+            parts.extend(segment.split_whitespace().filter_map(phrase_query));
+        }
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" & "))
+    }
+}
+
+/// Words within one token or quoted phrase must appear next to each other
+fn phrase_query(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .flat_map(|token| {
+            let prefix = token.ends_with('*');
+            let pieces: Vec<&str> = token
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|piece| !piece.is_empty())
+                .collect();
+            let last = pieces.len().saturating_sub(1);
+
+            pieces
+                .into_iter()
+                .enumerate()
+                .map(|(i, piece)| {
+                    let word = piece.to_lowercase();
+                    if prefix && i == last {
+                        format!("{word}:*")
+                    } else {
+                        word
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    match words.len() {
+        0 => None,
+        1 => words.into_iter().next(),
+        _ => Some(format!("({})", words.join(" <-> "))),
+    }
+}
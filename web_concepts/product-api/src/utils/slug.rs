@@ -0,0 +1,21 @@
+/// URL-safe identifier for a name: lowercase ASCII letters and digits, with
+/// every other run of characters collapsed into a single `-`.
+///
+/// `"Office Supplies"`, `" office supplies "` and `"Office & Supplies"` all
+/// become `office-supplies`. Mirrors the SQL used by the categories migration.
+pub fn slugify(input: &str) -> String {
+    let mut slug = String::with_capacity(input.len());
+
+    for ch in input.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
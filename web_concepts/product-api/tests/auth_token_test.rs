@@ -0,0 +1,673 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::test_db;
+    use async_trait::async_trait;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use chrono::{DateTime, Duration, Utc};
+    use product_api::{
+        cache::DisabledProductCache,
+        config::Config,
+        entities::{
+            refresh_token, revoked_access_token, user, user::UserRole, user_token_revocation,
+        },
+        error::AppError,
+        middleware::{auth::auth_middleware, rate_limit::RateLimiter, rbac::RbacConfig},
+        models::{Claims, LogoutRequest, RefreshTokenRequest, RegisterRequest},
+        rate_limit::{InMemoryRateLimitStore, Quota, RateLimitPolicies},
+        repository::auth::{AuthRepository, AuthRepositoryTrait},
+        services::{AuthService, TokenDenylist},
+        storage::LocalBlobStore,
+        utils::{create_jwt, generate_refresh_token, hash_refresh_token, verify_jwt},
+        AppState,
+    };
+    use sea_orm::DatabaseConnection;
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    const SECRET: &str = "test-secret";
+
+    /// In-memory stand-in for the users, refresh token and revocation tables
+    #[derive(Default)]
+    struct FakeAuthRepository {
+        users: Mutex<Vec<user::Model>>,
+        tokens: Mutex<Vec<refresh_token::Model>>,
+        revoked_tokens: Mutex<Vec<revoked_access_token::Model>>,
+        revoked_users: Mutex<Vec<user_token_revocation::Model>>,
+        /// Runs once, right after the next read of the revocations
+        after_revocations_read: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+    }
+
+    impl FakeAuthRepository {
+        fn live_tokens(&self) -> usize {
+            let tokens = self.tokens.lock().unwrap();
+            tokens.iter().filter(|t| t.revoked_at.is_none()).count()
+        }
+
+        fn revoke_where(&self, matches: impl Fn(&refresh_token::Model) -> bool) -> u64 {
+            let mut revoked = 0;
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.revoked_at.is_none() && matches(token) {
+                    token.revoked_at = Some(Utc::now());
+                    revoked += 1;
+                }
+            }
+            revoked
+        }
+    }
+
+    #[async_trait]
+    impl AuthRepositoryTrait for FakeAuthRepository {
+        async fn find_by_username(&self, username: &str) -> Result<Option<user::Model>, AppError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .iter()
+                .find(|u| u.username == username && u.is_active)
+                .cloned())
+        }
+
+        async fn find_by_username_or_email(
+            &self,
+            username: &str,
+            email: &str,
+        ) -> Result<Option<user::Model>, AppError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .iter()
+                .find(|u| u.username == username || u.email == email)
+                .cloned())
+        }
+
+        async fn create_user(
+            &self,
+            username: String,
+            email: String,
+            password_hash: String,
+            role: UserRole,
+        ) -> Result<user::Model, AppError> {
+            let user = user::Model {
+                id: Uuid::new_v4(),
+                username,
+                email,
+                password_hash,
+                role,
+                is_active: true,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            self.users.lock().unwrap().push(user.clone());
+            Ok(user)
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> Result<Option<user::Model>, AppError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.id == id).cloned())
+        }
+
+        async fn set_active(
+            &self,
+            id: Uuid,
+            is_active: bool,
+        ) -> Result<Option<user::Model>, AppError> {
+            let mut users = self.users.lock().unwrap();
+            Ok(users.iter_mut().find(|u| u.id == id).map(|user| {
+                user.is_active = is_active;
+                user.clone()
+            }))
+        }
+
+        async fn create_refresh_token(&self, token: refresh_token::Model) -> Result<(), AppError> {
+            self.tokens.lock().unwrap().push(token);
+            Ok(())
+        }
+
+        async fn find_refresh_token(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<refresh_token::Model>, AppError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens.iter().find(|t| t.token_hash == token_hash).cloned())
+        }
+
+        async fn rotate_refresh_token(
+            &self,
+            current: Uuid,
+            next: refresh_token::Model,
+        ) -> Result<bool, AppError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let Some(token) = tokens
+                .iter_mut()
+                .find(|t| t.id == current && t.revoked_at.is_none())
+            else {
+                return Ok(false);
+            };
+            token.revoked_at = Some(Utc::now());
+            token.replaced_by = Some(next.id);
+            tokens.push(next);
+            Ok(true)
+        }
+
+        async fn revoke_refresh_token_family(&self, family_id: Uuid) -> Result<u64, AppError> {
+            Ok(self.revoke_where(|t| t.family_id == family_id))
+        }
+
+        async fn revoke_user_refresh_tokens(&self, user_id: Uuid) -> Result<u64, AppError> {
+            Ok(self.revoke_where(|t| t.user_id == user_id))
+        }
+
+        async fn purge_expired_refresh_tokens(&self) -> Result<u64, AppError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let before = tokens.len();
+            tokens.retain(|t| t.expires_at > Utc::now());
+            Ok((before - tokens.len()) as u64)
+        }
+
+        async fn revoke_access_token(
+            &self,
+            jti: &str,
+            expires_at: DateTime<Utc>,
+        ) -> Result<(), AppError> {
+            self.revoked_tokens
+                .lock()
+                .unwrap()
+                .push(revoked_access_token::Model {
+                    jti: jti.to_string(),
+                    expires_at,
+                    created_at: Utc::now(),
+                });
+            Ok(())
+        }
+
+        async fn revoke_user_access_tokens(
+            &self,
+            user_id: Uuid,
+            revoked_at: DateTime<Utc>,
+            expires_at: DateTime<Utc>,
+        ) -> Result<(), AppError> {
+            let mut users = self.revoked_users.lock().unwrap();
+            users.retain(|u| u.user_id != user_id);
+            users.push(user_token_revocation::Model {
+                user_id,
+                revoked_at,
+                expires_at,
+            });
+            Ok(())
+        }
+
+        async fn clear_user_access_token_revocation(&self, user_id: Uuid) -> Result<(), AppError> {
+            self.revoked_users
+                .lock()
+                .unwrap()
+                .retain(|u| u.user_id != user_id);
+            Ok(())
+        }
+
+        async fn find_access_token_revocations(
+            &self,
+        ) -> Result<
+            (
+                Vec<revoked_access_token::Model>,
+                Vec<user_token_revocation::Model>,
+            ),
+            AppError,
+        > {
+            let revocations = (
+                self.revoked_tokens.lock().unwrap().clone(),
+                self.revoked_users.lock().unwrap().clone(),
+            );
+            if let Some(hook) = self.after_revocations_read.lock().unwrap().take() {
+                hook();
+            }
+            Ok(revocations)
+        }
+
+        async fn purge_expired_access_token_revocations(&self) -> Result<u64, AppError> {
+            Ok(0)
+        }
+    }
+
+    fn config() -> Arc<Config> {
+        let mut config = Config::from_env().unwrap();
+        config.jwt_secret = SECRET.to_string();
+        config.jwt_expiration = 900;
+        config.refresh_token_ttl_seconds = 3600;
+        Arc::new(config)
+    }
+
+    fn register_request(username: &str) -> RegisterRequest {
+        RegisterRequest {
+            username: username.to_string(),
+            email: format!("{username}@example.com"),
+            password: "password123".to_string(),
+            role: None,
+        }
+    }
+
+    fn refresh_request(refresh_token: &str) -> RefreshTokenRequest {
+        RefreshTokenRequest {
+            refresh_token: refresh_token.to_string(),
+        }
+    }
+
+    fn is_unauthorized(result: Result<impl std::fmt::Debug, AppError>) -> bool {
+        matches!(result, Err(AppError::Unauthorized { .. }))
+    }
+
+    #[test]
+    fn test_access_tokens_carry_unique_ids() {
+        let first = create_jwt("user-1", "alice", &UserRole::User, SECRET, 900).unwrap();
+        let second = create_jwt("user-1", "alice", &UserRole::User, SECRET, 900).unwrap();
+
+        let first = verify_jwt(&first, SECRET).unwrap();
+        let second = verify_jwt(&second, SECRET).unwrap();
+        assert_ne!(first.jti, second.jti);
+        assert_eq!(first.exp - first.iat, 900);
+        assert_eq!(first.role, UserRole::User);
+    }
+
+    #[test]
+    fn test_refresh_tokens_are_random_and_stored_hashed() {
+        let token = generate_refresh_token();
+        assert_eq!(token.len(), 43, "32 bytes of unpadded base64");
+        assert_ne!(token, generate_refresh_token());
+
+        let hash = hash_refresh_token(&token);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_refresh_token(&token));
+        assert!(!hash.contains(&token));
+    }
+
+    #[test]
+    fn test_denylist_revokes_tokens_and_users() {
+        let denylist = TokenDenylist::new();
+        let claims = |sub: &str, jti: &str, iat: usize| {
+            let token = create_jwt(sub, sub, &UserRole::User, SECRET, 900).unwrap();
+            let mut claims = verify_jwt(&token, SECRET).unwrap();
+            claims.jti = jti.to_string();
+            claims.iat = iat;
+            claims
+        };
+
+        denylist.revoke_token("logged-out", 2_000);
+        assert!(denylist.is_revoked(&claims("alice", "logged-out", 1_000)));
+        assert!(!denylist.is_revoked(&claims("alice", "other", 1_000)));
+
+        // Tokens issued up to the deactivation are rejected, later ones aren't
+        denylist.revoke_user("bob", 1_500, 2_400);
+        assert!(denylist.is_revoked(&claims("bob", "a", 1_500)));
+        assert!(!denylist.is_revoked(&claims("bob", "b", 1_501)));
+
+        assert_eq!(denylist.purge_expired(2_000), 1);
+        assert_eq!(denylist.len(), 1);
+        assert_eq!(denylist.purge_expired(2_400), 1);
+        assert!(denylist.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_and_detects_reuse() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let service = AuthService::new(repository.clone());
+
+        let login = service
+            .register(config(), register_request("alice"), None)
+            .await
+            .unwrap();
+        assert_eq!(login.expires_in, 900);
+        assert_eq!(repository.live_tokens(), 1);
+
+        let refreshed = service
+            .refresh(config(), refresh_request(&login.refresh_token))
+            .await
+            .unwrap();
+        assert_ne!(refreshed.refresh_token, login.refresh_token);
+        assert_eq!(refreshed.user.username, "alice");
+        assert_eq!(repository.live_tokens(), 1, "The old token was revoked");
+
+        // Replaying the rotated token burns the whole family
+        assert!(is_unauthorized(
+            service
+                .refresh(config(), refresh_request(&login.refresh_token))
+                .await
+        ));
+        assert_eq!(repository.live_tokens(), 0);
+        assert!(is_unauthorized(
+            service
+                .refresh(config(), refresh_request(&refreshed.refresh_token))
+                .await
+        ));
+
+        assert!(is_unauthorized(
+            service
+                .refresh(config(), refresh_request("never-issued"))
+                .await
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_logout_revokes_access_and_refresh_tokens() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let service = AuthService::new(repository.clone());
+        let denylist = TokenDenylist::new();
+
+        let login = service
+            .register(config(), register_request("carol"), None)
+            .await
+            .unwrap();
+        let claims = verify_jwt(&login.token, SECRET).unwrap();
+
+        service
+            .logout(
+                &claims,
+                LogoutRequest {
+                    refresh_token: Some(login.refresh_token.clone()),
+                },
+                &denylist,
+            )
+            .await
+            .unwrap();
+
+        assert!(denylist.is_revoked(&claims));
+        assert!(is_unauthorized(
+            service
+                .refresh(config(), refresh_request(&login.refresh_token))
+                .await
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deactivation_revokes_all_tokens() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let service = AuthService::new(repository.clone());
+        let denylist = TokenDenylist::new();
+
+        let admin = service
+            .register(config(), register_request("admin"), None)
+            .await
+            .unwrap();
+        let admin = verify_jwt(&admin.token, SECRET).unwrap();
+        let login = service
+            .register(config(), register_request("dave"), None)
+            .await
+            .unwrap();
+        let claims = verify_jwt(&login.token, SECRET).unwrap();
+
+        let user = service
+            .set_user_active(config(), &admin, login.user.id, false, &denylist)
+            .await
+            .unwrap();
+        assert!(!user.is_active);
+        assert!(denylist.is_revoked(&claims));
+        assert_eq!(
+            repository.live_tokens(),
+            1,
+            "Only the admin's token is left"
+        );
+        assert!(is_unauthorized(
+            service
+                .refresh(config(), refresh_request(&login.refresh_token))
+                .await
+        ));
+
+        // Admins can't lock themselves out
+        let own_id = Uuid::parse_str(&admin.sub).unwrap();
+        assert!(matches!(
+            service
+                .set_user_active(config(), &admin, own_id, false, &denylist)
+                .await,
+            Err(AppError::BusinessRuleViolation { .. })
+        ));
+        assert!(matches!(
+            service
+                .set_user_active(config(), &admin, Uuid::new_v4(), false, &denylist)
+                .await,
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_rejects_revoked_tokens() {
+        let config = config();
+        let denylist = Arc::new(TokenDenylist::new());
+        let state = AppState {
+            db: DatabaseConnection::Disconnected,
+            rate_limiter: RateLimiter::new(
+                Arc::new(InMemoryRateLimitStore::new(1)),
+                100,
+                RateLimitPolicies::new(Quota::per_minute(100), Vec::new()),
+            ),
+            blob_store: Arc::new(LocalBlobStore::new(
+                &config.media_root,
+                &config.media_base_url,
+            )),
+            product_cache: Arc::new(DisabledProductCache),
+            token_denylist: denylist.clone(),
+            rbac: Arc::new(RbacConfig::new()),
+            config,
+        };
+        let app = Router::new()
+            .route("/me", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                auth_middleware,
+            ))
+            .with_state(state);
+
+        let token = create_jwt("user-1", "alice", &UserRole::User, SECRET, 900).unwrap();
+        let request = || {
+            Request::get("/me")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let claims = verify_jwt(&token, SECRET).unwrap();
+        denylist.revoke_token(&claims.jti, claims.exp as u64);
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_reactivation_lifts_the_revocation() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let service = AuthService::new(repository.clone());
+        let denylist = TokenDenylist::new();
+
+        let admin = service
+            .register(config(), register_request("admin"), None)
+            .await
+            .unwrap();
+        let admin = verify_jwt(&admin.token, SECRET).unwrap();
+        let login = service
+            .register(config(), register_request("erin"), None)
+            .await
+            .unwrap();
+        let user_id = login.user.id;
+
+        service
+            .set_user_active(config(), &admin, user_id, false, &denylist)
+            .await
+            .unwrap();
+        service
+            .set_user_active(config(), &admin, user_id, true, &denylist)
+            .await
+            .unwrap();
+
+        // A login right after the reactivation, within the same second
+        let token = create_jwt(&user_id.to_string(), "erin", &UserRole::User, SECRET, 900).unwrap();
+        assert!(!denylist.is_revoked(&verify_jwt(&token, SECRET).unwrap()));
+        assert!(repository.revoked_users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revocations_reach_other_replicas() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let service = AuthService::new(repository.clone());
+        // Each replica has its own denylist over the shared tables
+        let (first, second) = (TokenDenylist::new(), TokenDenylist::new());
+
+        let admin = service
+            .register(config(), register_request("admin"), None)
+            .await
+            .unwrap();
+        let admin = verify_jwt(&admin.token, SECRET).unwrap();
+        let frank = service
+            .register(config(), register_request("frank"), None)
+            .await
+            .unwrap();
+        let frank = verify_jwt(&frank.token, SECRET).unwrap();
+        let grace = service
+            .register(config(), register_request("grace"), None)
+            .await
+            .unwrap();
+        let grace_id = grace.user.id;
+        let grace = verify_jwt(&grace.token, SECRET).unwrap();
+
+        let logout = LogoutRequest {
+            refresh_token: None,
+        };
+        service.logout(&frank, logout, &first).await.unwrap();
+        service
+            .set_user_active(config(), &admin, grace_id, false, &first)
+            .await
+            .unwrap();
+        assert!(!second.is_revoked(&frank) && !second.is_revoked(&grace));
+
+        assert_eq!(second.reload(repository.as_ref()).await.unwrap(), 2);
+        assert!(second.is_revoked(&frank));
+        assert!(second.is_revoked(&grace));
+
+        // Reactivations are picked up too
+        service
+            .set_user_active(config(), &admin, grace_id, true, &first)
+            .await
+            .unwrap();
+        second.reload(repository.as_ref()).await.unwrap();
+        let token = create_jwt(&grace.sub, "grace", &UserRole::User, SECRET, 900).unwrap();
+        assert!(!second.is_revoked(&verify_jwt(&token, SECRET).unwrap()));
+        assert!(second.is_revoked(&frank));
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_changes_made_while_it_reads() {
+        let repository = Arc::new(FakeAuthRepository::default());
+        let denylist = Arc::new(TokenDenylist::new());
+        let (heidi, ivan) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+        repository
+            .revoke_user_access_tokens(ivan, now, now + Duration::minutes(15))
+            .await
+            .unwrap();
+        denylist.reload(repository.as_ref()).await.unwrap();
+
+        // Stored and applied locally just after the reload read the table
+        let local = denylist.clone();
+        let (revoked_at, until) = (now.timestamp() as u64, (now.timestamp() + 900) as u64);
+        *repository.after_revocations_read.lock().unwrap() = Some(Box::new(move || {
+            local.revoke_user(&heidi.to_string(), revoked_at, until);
+            local.clear_user(&ivan.to_string());
+        }));
+        denylist.reload(repository.as_ref()).await.unwrap();
+
+        let token = |user_id: Uuid| {
+            let token = create_jwt(&user_id.to_string(), "user", &UserRole::User, SECRET, 900);
+            verify_jwt(&token.unwrap(), SECRET).unwrap()
+        };
+        let issued_before = |user_id: Uuid| Claims {
+            iat: now.timestamp() as usize - 60,
+            ..token(user_id)
+        };
+        assert!(denylist.is_revoked(&issued_before(heidi)));
+        assert!(!denylist.is_revoked(&issued_before(ivan)));
+
+        // Once the stored state catches up, later reloads follow it again
+        repository
+            .revoke_user_access_tokens(heidi, now, now + Duration::minutes(15))
+            .await
+            .unwrap();
+        repository
+            .clear_user_access_token_revocation(ivan)
+            .await
+            .unwrap();
+        denylist.reload(repository.as_ref()).await.unwrap();
+        repository
+            .clear_user_access_token_revocation(heidi)
+            .await
+            .unwrap();
+        denylist.reload(repository.as_ref()).await.unwrap();
+        assert!(!denylist.is_revoked(&issued_before(heidi)));
+        assert!(!denylist.is_revoked(&issued_before(ivan)));
+    }
+
+    #[tokio::test]
+    async fn test_revocations_are_stored_until_they_expire() {
+        let Some(db) = test_db().await else { return };
+        let repository = AuthRepository::new(db);
+        let user = repository
+            .create_user(
+                "heidi".to_string(),
+                "heidi@example.com".to_string(),
+                "hash".to_string(),
+                UserRole::User,
+            )
+            .await
+            .unwrap();
+        let now = Utc::now();
+
+        repository
+            .revoke_access_token("live", now + Duration::minutes(15))
+            .await
+            .unwrap();
+        // A second logout with the same token is a no-op
+        repository
+            .revoke_access_token("live", now + Duration::minutes(15))
+            .await
+            .unwrap();
+        repository
+            .revoke_access_token("expired", now - Duration::minutes(1))
+            .await
+            .unwrap();
+        repository
+            .revoke_user_access_tokens(user.id, now, now + Duration::minutes(5))
+            .await
+            .unwrap();
+        // A later deactivation replaces the earlier one
+        repository
+            .revoke_user_access_tokens(user.id, now, now + Duration::minutes(15))
+            .await
+            .unwrap();
+
+        let (tokens, users) = repository.find_access_token_revocations().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].jti, "live");
+        assert_eq!(users.len(), 1);
+        assert_eq!(
+            users[0].expires_at.timestamp(),
+            (now + Duration::minutes(15)).timestamp()
+        );
+
+        assert_eq!(
+            repository
+                .purge_expired_access_token_revocations()
+                .await
+                .unwrap(),
+            1
+        );
+        repository
+            .clear_user_access_token_revocation(user.id)
+            .await
+            .unwrap();
+        let (tokens, users) = repository.find_access_token_revocations().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(users.is_empty());
+    }
+}
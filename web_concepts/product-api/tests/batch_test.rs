@@ -0,0 +1,150 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{create_request, test_db};
+    use product_api::models::{
+        BatchDeleteProductsRequest, BatchItemError, BatchItemResult, BatchItemStatus,
+        BatchUpdateProductsRequest, UpdateProductRequest,
+    };
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    #[test]
+    fn test_batch_update_items_flatten_the_update_fields() {
+        let id = Uuid::new_v4();
+        let request: BatchUpdateProductsRequest = serde_json::from_value(json!({
+            "items": [
+                {"id": id, "price": "9.99", "quantity": 3},
+                {"id": Uuid::new_v4(), "price": 12.5, "currency": "EUR"}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(request.items.len(), 2);
+        assert_eq!(request.items[0].id, id);
+        assert_eq!(request.items[0].changes.price, Some(dec!(9.99)));
+        assert_eq!(request.items[0].changes.quantity, Some(3));
+        assert_eq!(request.items[1].changes.price, Some(dec!(12.5)));
+        assert_eq!(request.items[1].changes.currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn test_batch_update_items_are_validated_like_single_updates() {
+        let request: BatchUpdateProductsRequest = serde_json::from_value(json!({
+            "items": [
+                {"id": Uuid::new_v4(), "sku": "MUG-01"},
+                {"id": Uuid::new_v4(), "sku": "MUG 01"},
+                {"id": Uuid::new_v4(), "quantity": 1}
+            ]
+        }))
+        .unwrap();
+
+        assert!(request.items[0].changes.validate().is_ok());
+        assert!(request.items[1].changes.validate().is_err());
+        // Stock is only changed through the stock adjustment endpoint
+        let errors = request.items[2].changes.validate().unwrap_err();
+        assert_eq!(
+            errors.field_errors()["quantity"][0].code,
+            "quantity_read_only"
+        );
+    }
+
+    #[test]
+    fn test_batch_delete_requires_product_ids() {
+        let parsed: Result<BatchDeleteProductsRequest, _> =
+            serde_json::from_value(json!({"ids": ["not-a-uuid"]}));
+        assert!(parsed.is_err());
+
+        let id = Uuid::new_v4();
+        let request: BatchDeleteProductsRequest =
+            serde_json::from_value(json!({ "ids": [id] })).unwrap();
+        assert_eq!(request.ids, vec![id]);
+    }
+
+    #[test]
+    fn test_batch_item_result_serialization() {
+        let rolled_back = BatchItemResult {
+            id: Uuid::new_v4(),
+            status: BatchItemStatus::RolledBack,
+            product: None,
+            error: None,
+        };
+        let value = serde_json::to_value(&rolled_back).unwrap();
+        assert_eq!(value["status"], "rolled_back");
+        assert!(value.get("product").is_none());
+        assert!(value.get("error").is_none());
+
+        let failed = BatchItemResult {
+            id: Uuid::new_v4(),
+            status: BatchItemStatus::Failed,
+            product: None,
+            error: Some(BatchItemError {
+                code: "RESOURCE_NOT_FOUND".to_string(),
+                status: 404,
+                message: "Resource not found".to_string(),
+            }),
+        };
+        let value = serde_json::to_value(&failed).unwrap();
+        assert_eq!(value["status"], "failed");
+        assert_eq!(value["error"]["code"], "RESOURCE_NOT_FOUND");
+        assert_eq!(value["error"]["status"], 404);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_batches_do_not_deadlock() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+
+        let mut ids = Vec::new();
+        for index in 0..10 {
+            let product = repo
+                .create(create_request(&format!("Batch {index}"), 5))
+                .await
+                .unwrap();
+            ids.push(product.id);
+        }
+        let reversed: Vec<Uuid> = ids.iter().rev().copied().collect();
+
+        let items = |ids: &[Uuid], price: &str| -> Vec<(Uuid, UpdateProductRequest)> {
+            ids.iter()
+                .map(|id| {
+                    (
+                        *id,
+                        serde_json::from_value(json!({ "price": price })).unwrap(),
+                    )
+                })
+                .collect()
+        };
+
+        // Without a shared lock order these take row locks in opposite orders
+        for _ in 0..5 {
+            let (forward, backward) = tokio::join!(
+                repo.update_many(items(&ids, "11.00"), true, None),
+                repo.update_many(items(&reversed, "12.00"), true, None),
+            );
+            let (forward, backward) = (forward.unwrap(), backward.unwrap());
+            assert!(forward.committed && backward.committed);
+
+            // Outcomes keep the request order
+            let order: Vec<Uuid> = backward.outcomes.iter().map(|(id, _)| *id).collect();
+            assert_eq!(order, reversed);
+        }
+
+        let (forward, backward) = tokio::join!(
+            repo.delete_many(ids.clone(), false, None),
+            repo.delete_many(reversed.clone(), false, None),
+        );
+        let (forward, backward) = (forward.unwrap(), backward.unwrap());
+        let deleted = forward
+            .outcomes
+            .iter()
+            .chain(&backward.outcomes)
+            .filter(|(_, outcome)| outcome.is_ok())
+            .count();
+        assert_eq!(deleted, ids.len(), "Each product is deleted exactly once");
+    }
+}
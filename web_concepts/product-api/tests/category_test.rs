@@ -0,0 +1,235 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use product_api::error::AppError;
+    use product_api::models::{
+        CategoryStats, CreateCategoryRequest, CreateProductRequest, UpdateCategoryRequest,
+    };
+    use product_api::repository::{
+        CategoryChanges, CategoryRepository, CategoryRepositoryTrait, ProductRepository,
+        ProductRepositoryTrait,
+    };
+    use product_api::utils::slugify;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    #[test]
+    fn test_slugify_collapses_separators() {
+        assert_eq!(slugify("Office Supplies"), "office-supplies");
+        assert_eq!(slugify("  office   supplies "), "office-supplies");
+        assert_eq!(slugify("Office & Supplies"), "office-supplies");
+        assert_eq!(slugify("--Audio/Video--"), "audio-video");
+        assert_eq!(slugify("4K TVs"), "4k-tvs");
+    }
+
+    #[test]
+    fn test_slugify_drops_non_ascii() {
+        assert_eq!(slugify("Café Gear"), "caf-gear");
+        assert_eq!(slugify("???"), "");
+    }
+
+    #[test]
+    fn test_create_request_validation() {
+        let request: CreateCategoryRequest = serde_json::from_value(json!({
+            "name": "Laptops",
+            "parent_id": Uuid::new_v4()
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+        assert!(request.slug.is_none());
+
+        let request: CreateCategoryRequest =
+            serde_json::from_value(json!({ "name": "", "slug": "x".repeat(101) })).unwrap();
+        let errors = request.validate().unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("name"));
+        assert!(fields.contains_key("slug"));
+    }
+
+    #[test]
+    fn test_update_request_defaults() {
+        let request: UpdateCategoryRequest =
+            serde_json::from_value(json!({ "name": "Notebooks" })).unwrap();
+        assert!(request.validate().is_ok());
+        assert!(!request.make_root);
+        assert!(request.parent_id.is_none());
+
+        let request: UpdateCategoryRequest =
+            serde_json::from_value(json!({ "make_root": true })).unwrap();
+        assert!(request.make_root);
+    }
+
+    #[test]
+    fn test_category_stats_serialization() {
+        let parent_id = Uuid::new_v4();
+        let stats = CategoryStats {
+            category_id: Uuid::new_v4(),
+            category: "Laptops".to_string(),
+            slug: "laptops".to_string(),
+            parent_id: Some(parent_id),
+            count: 3,
+            total_value: dec!(3899.97),
+        };
+
+        let value = serde_json::to_value(&stats).unwrap();
+        assert_eq!(value["slug"], "laptops");
+        assert_eq!(value["parent_id"], parent_id.to_string());
+        assert_eq!(value["count"], 3);
+    }
+
+    /// Home > Kitchen > Mugs, with one product of `quantity` units at 10.00 in each
+    async fn category_tree(
+        categories: &CategoryRepository,
+        products: &ProductRepository,
+    ) -> [Uuid; 3] {
+        let mut ids = [Uuid::nil(); 3];
+        let mut parent_id = None;
+        for (index, name) in ["Home", "Kitchen", "Mugs"].into_iter().enumerate() {
+            let category = categories
+                .create(name.to_string(), slugify(name), parent_id)
+                .await
+                .unwrap();
+            products
+                .create(CreateProductRequest {
+                    name: format!("{name} item"),
+                    description: None,
+                    price: dec!(10.00),
+                    currency: Some("USD".to_string()),
+                    quantity: 1 + index as i32,
+                    category: None,
+                    category_id: Some(category.id),
+                    sku: None,
+                    barcode: None,
+                    created_by: None,
+                    updated_by: None,
+                })
+                .await
+                .unwrap();
+            ids[index] = category.id;
+            parent_id = Some(category.id);
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_category_filter_can_include_descendants() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let products = ProductRepository::new(db.clone());
+        category_tree(&CategoryRepository::new(db), &products).await;
+
+        let names = |found: Vec<product_api::entities::product::Model>| {
+            let mut names: Vec<_> = found.into_iter().map(|product| product.name).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(products.find_by_category("Home", false).await.unwrap()),
+            vec!["Home item"]
+        );
+        assert_eq!(
+            names(products.find_by_category("home", true).await.unwrap()),
+            vec!["Home item", "Kitchen item", "Mugs item"]
+        );
+        assert_eq!(
+            names(products.find_by_category("Kitchen", true).await.unwrap()),
+            vec!["Kitchen item", "Mugs item"]
+        );
+        assert!(products
+            .find_by_category("unknown", true)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_category_stats_roll_up_subtrees() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let products = ProductRepository::new(db.clone());
+        let [home, kitchen, mugs] = category_tree(&CategoryRepository::new(db), &products).await;
+
+        let stats = products
+            .get_product_stats(&common::conversion("USD"))
+            .await
+            .unwrap();
+        let rolled_up = |id: Uuid| -> (u64, Decimal) {
+            let category = stats
+                .categories
+                .iter()
+                .find(|category| category.category_id == id)
+                .unwrap();
+            (category.count, category.total_value)
+        };
+        assert_eq!(rolled_up(home), (3, dec!(60.00)));
+        assert_eq!(rolled_up(kitchen), (2, dec!(50.00)));
+        assert_eq!(rolled_up(mugs), (1, dec!(30.00)));
+    }
+
+    #[tokio::test]
+    async fn test_categories_cannot_be_moved_into_their_subtree() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let categories = CategoryRepository::new(db.clone());
+        let [home, kitchen, mugs] = category_tree(&categories, &ProductRepository::new(db)).await;
+        let move_below = |id: Uuid, parent_id: Uuid| {
+            categories.update(
+                id,
+                CategoryChanges {
+                    parent_id: Some(Some(parent_id)),
+                    ..Default::default()
+                },
+            )
+        };
+
+        for (id, parent_id) in [(home, home), (home, mugs), (kitchen, mugs)] {
+            let error = move_below(id, parent_id).await.unwrap_err();
+            assert!(
+                matches!(error, AppError::ValidationError { .. }),
+                "{error:?}"
+            );
+        }
+        assert_eq!(
+            categories
+                .find_by_id(home)
+                .await
+                .unwrap()
+                .unwrap()
+                .parent_id,
+            None
+        );
+
+        // Moving sideways or up is fine
+        let moved = move_below(mugs, home).await.unwrap();
+        assert_eq!(moved.parent_id, Some(home));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_slugs_are_conflicts() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let categories = CategoryRepository::new(db);
+        let create = || categories.create("Garden".to_string(), "garden".to_string(), None);
+
+        // Whether the pre-check or the unique constraint catches the loser,
+        // it gets the same 409
+        let (first, second) = tokio::join!(create(), create());
+        let conflicts = [&first, &second]
+            .into_iter()
+            .filter(|outcome| matches!(outcome, Err(AppError::Conflict { .. })))
+            .count();
+        assert_eq!(conflicts, 1, "{first:?} {second:?}");
+
+        let error = create().await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict { .. }), "{error:?}");
+    }
+}
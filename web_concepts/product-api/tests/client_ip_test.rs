@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        extract::connect_info::MockConnectInfo,
+        http::{HeaderMap, Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use product_api::middleware::{
+        client_ip::{client_ip_middleware, ClientIpResolver, IpNet},
+        rate_limit::{ip_rate_limit_middleware, RateLimiter},
+    };
+    use product_api::rate_limit::{InMemoryRateLimitStore, Quota, RateLimitPolicies};
+    use std::{
+        net::{IpAddr, SocketAddr},
+        sync::Arc,
+    };
+    use tower::ServiceExt;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn resolver(trusted: &str) -> ClientIpResolver {
+        ClientIpResolver::new(IpNet::parse_list(trusted).unwrap())
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_ip_net_parsing_and_matching() {
+        let nets = IpNet::parse_list("10.0.0.0/8, 192.168.1.7 ,fd00::/8").unwrap();
+        assert_eq!(nets.len(), 3);
+
+        assert!(nets[0].contains(ip("10.200.3.4")));
+        assert!(!nets[0].contains(ip("11.0.0.1")));
+        assert!(nets[1].contains(ip("192.168.1.7")));
+        assert!(!nets[1].contains(ip("192.168.1.8")));
+        assert!(nets[2].contains(ip("fd12::1")));
+        assert!(!nets[2].contains(ip("10.0.0.1")));
+        // IPv4-mapped IPv6 peers match IPv4 networks
+        assert!(nets[0].contains(ip("::ffff:10.0.0.1")));
+        assert!("0.0.0.0/0"
+            .parse::<IpNet>()
+            .unwrap()
+            .contains(ip("8.8.8.8")));
+
+        for invalid in [
+            "10.0.0.0/33",
+            "fd00::/129",
+            "10.0.0/8",
+            "proxy",
+            "10.0.0.0/x",
+        ] {
+            assert!(
+                invalid.parse::<IpNet>().is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_untrusted_peer_cannot_spoof_its_address() {
+        let resolver = resolver("10.0.0.0/8");
+        let spoofed = headers(&[
+            ("x-forwarded-for", "1.2.3.4"),
+            ("x-real-ip", "1.2.3.4"),
+            ("forwarded", "for=1.2.3.4"),
+        ]);
+
+        assert_eq!(
+            resolver.resolve(Some(ip("203.0.113.9")), &spoofed),
+            Some(ip("203.0.113.9"))
+        );
+        // Without trusted proxies nothing is read from the headers
+        assert_eq!(
+            ClientIpResolver::default().resolve(Some(ip("10.0.0.2")), &spoofed),
+            Some(ip("10.0.0.2"))
+        );
+        assert_eq!(resolver.resolve(None, &spoofed), None);
+    }
+
+    #[test]
+    fn test_x_forwarded_for_is_walked_right_to_left() {
+        let resolver = resolver("10.0.0.0/8");
+
+        // The client prepended a fake entry; the proxies appended the real ones
+        let chain = headers(&[("x-forwarded-for", "1.2.3.4, 198.51.100.7, 10.0.0.3")]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &chain),
+            Some(ip("198.51.100.7"))
+        );
+
+        // Repeated headers form one list
+        let split = headers(&[
+            ("x-forwarded-for", "1.2.3.4, 198.51.100.7"),
+            ("x-forwarded-for", "10.0.0.3"),
+        ]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &split),
+            Some(ip("198.51.100.7"))
+        );
+
+        // Only trusted hops: the leftmost one is the client
+        let internal = headers(&[("x-forwarded-for", "10.1.1.1, 10.0.0.3")]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &internal),
+            Some(ip("10.1.1.1"))
+        );
+
+        // A garbage hop stops the walk at the last proxy that vouched for it
+        let garbage = headers(&[("x-forwarded-for", "1.2.3.4, not-an-ip, 10.0.0.3")]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &garbage),
+            Some(ip("10.0.0.3"))
+        );
+
+        // X-Real-IP is only used when there is no forwarding chain
+        let real_ip = headers(&[("x-real-ip", "198.51.100.8")]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &real_ip),
+            Some(ip("198.51.100.8"))
+        );
+    }
+
+    #[test]
+    fn test_forwarded_header_is_preferred() {
+        let resolver = resolver("10.0.0.0/8,2001:db8:ffff::/48");
+
+        let both = headers(&[
+            (
+                "forwarded",
+                r#"for=1.2.3.4, For="[2001:db8::cafe]:4711";proto=https, for=10.0.0.3:8080"#,
+            ),
+            ("x-forwarded-for", "192.0.2.1"),
+        ]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &both),
+            Some(ip("2001:db8::cafe"))
+        );
+
+        // Obfuscated identifiers can't be attributed further
+        let hidden = headers(&[("forwarded", "for=_hidden, for=\"10.0.0.3\"")]);
+        assert_eq!(
+            resolver.resolve(Some(ip("10.0.0.2")), &hidden),
+            Some(ip("10.0.0.3"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_direct_clients_get_their_own_bucket() {
+        let rate_limiter = RateLimiter::new(
+            Arc::new(InMemoryRateLimitStore::new(1)),
+            1,
+            RateLimitPolicies::new(Quota::per_minute(1), Vec::new()),
+        );
+        let app = |peer: &str| {
+            Router::new()
+                .route("/health", get(|| async { "ok" }))
+                .layer(axum::middleware::from_fn_with_state(
+                    rate_limiter.clone(),
+                    ip_rate_limit_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    resolver("10.0.0.0/8"),
+                    client_ip_middleware,
+                ))
+                .layer(MockConnectInfo(peer.parse::<SocketAddr>().unwrap()))
+        };
+        let request = |forwarded_for: &str| {
+            Request::get("/health")
+                .header("x-forwarded-for", forwarded_for)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app("203.0.113.1:5000")
+            .oneshot(request("1.1.1.1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A different direct client isn't throttled by the first one's usage
+        let response = app("203.0.113.2:5000")
+            .oneshot(request("1.1.1.1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Spoofing another header doesn't reset the first client's bucket
+        let response = app("203.0.113.1:5001")
+            .oneshot(request("9.9.9.9"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Behind a trusted proxy, the forwarded client is limited
+        let response = app("10.0.0.2:5000")
+            .oneshot(request("198.51.100.7"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app("10.0.0.2:5001")
+            .oneshot(request("198.51.100.7"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,133 @@
+//! Fixtures shared by the integration tests. Each test file pulls this in
+//! with `mod common;` and uses only part of it.
+#![allow(dead_code)]
+
+use chrono::Utc;
+use product_api::cache::DisabledProductCache;
+use product_api::config::Config;
+use product_api::entities::product;
+use product_api::middleware::{rate_limit::RateLimiter, rbac::RbacConfig};
+use product_api::models::CreateProductRequest;
+use product_api::rate_limit::{InMemoryRateLimitStore, Quota, RateLimitPolicies};
+use product_api::services::TokenDenylist;
+use product_api::storage::LocalBlobStore;
+use product_api::utils::CurrencyConversion;
+use product_api::AppState;
+use rust_decimal_macros::dec;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// An active, unreserved product; override fields with struct update syntax
+pub fn sample_product() -> product::Model {
+    product::Model {
+        id: Uuid::new_v4(),
+        name: "Coffee Mug".to_string(),
+        description: Some("Ceramic".to_string()),
+        price: dec!(15.99),
+        currency: "USD".to_string(),
+        quantity: 50,
+        reserved_quantity: 0,
+        category_id: None,
+        sku: None,
+        barcode: None,
+        category: Some("Office Supplies".to_string()),
+        created_by: None,
+        updated_by: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        version: 1,
+        deleted_at: None,
+    }
+}
+
+/// A USD product at 10.00 without category or identifiers, for repository tests
+pub fn create_request(name: &str, quantity: i32) -> CreateProductRequest {
+    CreateProductRequest {
+        name: name.to_string(),
+        description: None,
+        price: dec!(10.00),
+        currency: Some("USD".to_string()),
+        quantity,
+        category: None,
+        category_id: None,
+        sku: None,
+        barcode: None,
+        created_by: None,
+        updated_by: None,
+    }
+}
+
+/// Conversion into `currency` over the USD-based rates seeded by `init.sql`
+pub fn conversion(currency: &str) -> CurrencyConversion {
+    let rates = BTreeMap::from([
+        ("USD".to_string(), dec!(1)),
+        ("EUR".to_string(), dec!(0.92)),
+        ("GBP".to_string(), dec!(0.79)),
+        ("JPY".to_string(), dec!(149.5)),
+    ]);
+    CurrencyConversion::new(currency, rates).unwrap()
+}
+
+/// State for building the router without a database: queries fail, but
+/// routing, middleware and the OpenAPI document work
+pub fn app_state() -> AppState {
+    let config = Arc::new(Config::from_env().unwrap());
+    AppState {
+        db: DatabaseConnection::Disconnected,
+        rate_limiter: RateLimiter::new(
+            Arc::new(InMemoryRateLimitStore::new(1)),
+            config.rate_limit_per_ip,
+            RateLimitPolicies::new(Quota::per_minute(config.rate_limit_per_user), Vec::new()),
+        ),
+        blob_store: Arc::new(LocalBlobStore::new(
+            &config.media_root,
+            &config.media_base_url,
+        )),
+        product_cache: Arc::new(DisabledProductCache),
+        token_denylist: Arc::new(TokenDenylist::new()),
+        rbac: Arc::new(RbacConfig::new()),
+        config,
+    }
+}
+
+/// A connection to a fresh copy of the schema in `init.sql`, seed data included,
+/// or `None` when `TEST_DATABASE_URL` is unset and database tests should be skipped.
+/// Every call gets its own Postgres schema, so tests can run in parallel.
+pub async fn test_db() -> Option<DatabaseConnection> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping database test");
+        return None;
+    };
+
+    let schema = format!("test_{}", Uuid::new_v4().simple());
+    let admin = Database::connect(&url)
+        .await
+        .expect("connect to TEST_DATABASE_URL");
+    admin
+        .execute_unprepared(&format!("CREATE SCHEMA {schema}"))
+        .await
+        .expect("create test schema");
+    // Extensions are per database; a parallel test may win the race to create it
+    let _ = admin
+        .execute_unprepared(r#"CREATE EXTENSION IF NOT EXISTS "uuid-ossp" SCHEMA public"#)
+        .await;
+    admin.close().await.ok();
+
+    // Every pooled connection starts in the test schema, falling back to public for
+    // the extension's functions
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let mut options = ConnectOptions::new(format!(
+        "{url}{separator}options=-c%20search_path%3D{schema},public"
+    ));
+    options.max_connections(4).sqlx_logging(false);
+    let db = Database::connect(options)
+        .await
+        .expect("connect to test schema");
+    db.execute_unprepared(include_str!("../../init.sql"))
+        .await
+        .expect("apply init.sql");
+
+    Some(db)
+}
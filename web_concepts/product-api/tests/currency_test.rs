@@ -0,0 +1,208 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use product_api::models::{
+        CreateProductRequest, ProductSearchRequest, UpsertExchangeRateRequest,
+    };
+    use product_api::repository::{
+        ExchangeRateRepository, ProductRepository, ProductRepositoryTrait,
+    };
+    use product_api::services::ExchangeRateService;
+    use product_api::utils::{currency_minor_units, is_valid_currency_code, CurrencyConversion};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    fn rates() -> BTreeMap<String, Decimal> {
+        BTreeMap::from([
+            ("USD".to_string(), dec!(1)),
+            ("EUR".to_string(), dec!(0.8)),
+            ("JPY".to_string(), dec!(150)),
+            ("KWD".to_string(), dec!(0.3)),
+        ])
+    }
+
+    #[test]
+    fn test_currency_codes() {
+        assert!(is_valid_currency_code("EUR"));
+        assert!(!is_valid_currency_code("eur"));
+        assert!(!is_valid_currency_code("EURO"));
+        assert!(!is_valid_currency_code("E1R"));
+
+        assert_eq!(currency_minor_units("USD"), 2);
+        assert_eq!(currency_minor_units("JPY"), 0);
+        assert_eq!(currency_minor_units("KWD"), 3);
+    }
+
+    #[test]
+    fn test_conversion_rounds_to_the_minor_unit() {
+        let to_eur = CurrencyConversion::new("EUR", rates()).unwrap();
+        assert_eq!(to_eur.convert(dec!(10.00), "USD"), Some(dec!(8.00)));
+        // 0.10625 rounds half away from zero
+        assert_eq!(to_eur.convert(dec!(0.1328125), "USD"), Some(dec!(0.11)));
+        assert_eq!(to_eur.convert(dec!(8.00), "EUR"), Some(dec!(8.00)));
+        assert_eq!(to_eur.convert(dec!(1), "CHF"), None, "No rate for CHF");
+
+        let to_jpy = CurrencyConversion::new("JPY", rates()).unwrap();
+        assert_eq!(to_jpy.convert(dec!(19.99), "USD"), Some(dec!(2999)));
+        assert_eq!(to_jpy.convert(dec!(1.00), "EUR"), Some(dec!(188)));
+
+        let to_kwd = CurrencyConversion::new("KWD", rates()).unwrap();
+        assert_eq!(to_kwd.convert(dec!(12.34), "USD"), Some(dec!(3.702)));
+
+        assert!(CurrencyConversion::new("CHF", rates()).is_none());
+    }
+
+    #[test]
+    fn test_conversion_sql_inlines_the_rates() {
+        let to_jpy = CurrencyConversion::new("JPY", rates()).unwrap();
+
+        let factor = to_jpy.factor_sql("p.currency");
+        assert!(factor.starts_with("(150::numeric / CASE p.currency"));
+        assert!(factor.contains("WHEN 'EUR' THEN 0.8::numeric"));
+        assert!(factor.ends_with("END)"));
+
+        let amount = to_jpy.amount_sql("p.price", "p.currency");
+        assert!(amount.starts_with("ROUND(p.price * "));
+        assert!(amount.ends_with(", 0)"), "JPY has no minor unit");
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let request: CreateProductRequest = serde_json::from_value(json!({
+            "name": "Kettle",
+            "price": "39.90",
+            "quantity": 1,
+            "currency": "eur"
+        }))
+        .unwrap();
+        assert!(request.validate().is_err());
+
+        let valid = UpsertExchangeRateRequest { rate: dec!(0.92) };
+        assert!(valid.validate().is_ok());
+        // Trailing zeros don't count against the scale
+        let padded = UpsertExchangeRateRequest {
+            rate: dec!(1.2000000000),
+        };
+        assert!(padded.validate().is_ok());
+
+        for rate in [dec!(0), dec!(-1), dec!(0.123456789)] {
+            let request = UpsertExchangeRateRequest { rate };
+            assert!(request.validate().is_err(), "{rate} must be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prices_compare_in_the_base_currency_by_default() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        db.execute_unprepared("TRUNCATE products CASCADE")
+            .await
+            .unwrap();
+        let repo = ProductRepository::new(db.clone());
+        // 10.00 EUR is 10.87 USD at the seeded rate of 0.92
+        for (name, currency) in [("Mix probe USD", "USD"), ("Mix probe EUR", "EUR")] {
+            repo.create(CreateProductRequest {
+                currency: Some(currency.to_string()),
+                ..common::create_request(name, 1)
+            })
+            .await
+            .unwrap();
+        }
+
+        // No currency= parameter
+        let rate_repository = Arc::new(ExchangeRateRepository::new(db));
+        let conversion = ExchangeRateService::new(rate_repository, "USD".to_string())
+            .conversion(None)
+            .await
+            .unwrap();
+        assert_eq!(conversion.currency(), "USD");
+
+        let request: ProductSearchRequest = serde_json::from_value(json!({
+            "min_price": "10.50",
+            "price_buckets": "10.50",
+        }))
+        .unwrap();
+        let page = repo.search(request.clone(), &conversion).await.unwrap();
+        let names: Vec<_> = page.products.into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["Mix probe EUR"]);
+        let request = ProductSearchRequest {
+            min_price: None,
+            ..request
+        };
+        let facets = repo.search_facets(request, &conversion).await.unwrap();
+        let counts: Vec<_> = facets
+            .price_buckets
+            .iter()
+            .map(|bucket| (bucket.min, bucket.count))
+            .collect();
+        assert_eq!(counts, vec![(None, 1), (Some(dec!(10.50)), 1)]);
+
+        let in_range = repo
+            .find_by_price_range(dec!(10.50), dec!(11.00), &conversion)
+            .await
+            .unwrap();
+        assert_eq!(in_range.len(), 1);
+        assert_eq!(in_range[0].currency, "EUR");
+
+        // Totals are converted per product and rounded once
+        let stats = repo.get_product_stats(&conversion).await.unwrap();
+        assert_eq!(stats.currency, "USD");
+        assert_eq!(stats.total_products, 2);
+        assert_eq!(stats.total_value, dec!(20.87));
+        assert_eq!(stats.avg_price, Some(dec!(10.43)));
+    }
+
+    #[tokio::test]
+    async fn test_trending_totals_are_converted() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        db.execute_unprepared("TRUNCATE products CASCADE")
+            .await
+            .unwrap();
+        let category_id: Uuid = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Postgres,
+                "SELECT id FROM categories WHERE slug = 'electronics'",
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get("", "id")
+            .unwrap();
+        let repo = ProductRepository::new(db);
+        for (name, currency) in [("Trend probe USD", "USD"), ("Trend probe EUR", "EUR")] {
+            repo.create(CreateProductRequest {
+                currency: Some(currency.to_string()),
+                category_id: Some(category_id),
+                ..common::create_request(name, 1)
+            })
+            .await
+            .unwrap();
+        }
+
+        // 10.00 USD + 10.00 EUR at the seeded rate of 0.92
+        let trending = repo
+            .get_trending_categories(10, &common::conversion("USD"))
+            .await
+            .unwrap();
+        assert_eq!(trending.len(), 1);
+        assert_eq!(trending[0].count, 2);
+        assert_eq!(trending[0].total_value, dec!(20.87));
+
+        let trending = repo
+            .get_trending_categories(10, &common::conversion("EUR"))
+            .await
+            .unwrap();
+        assert_eq!(trending[0].total_value, dec!(19.20));
+    }
+}
@@ -0,0 +1,105 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{create_request, test_db};
+    use axum::{http::StatusCode, response::IntoResponse};
+    use product_api::error::{AppError, ErrorRegistry};
+    use product_api::models::UpdateProductRequest;
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use product_api::utils::{version_etag, IfMatch};
+    use serde_json::json;
+
+    fn rename(name: &str) -> UpdateProductRequest {
+        serde_json::from_value(json!({ "name": name })).unwrap()
+    }
+
+    #[test]
+    fn test_version_etag_is_strong_and_quoted() {
+        assert_eq!(version_etag(7), "\"7\"");
+        assert_eq!(IfMatch::parse(&version_etag(7)), IfMatch::Versions(vec![7]));
+    }
+
+    #[test]
+    fn test_if_match_parsing() {
+        assert_eq!(IfMatch::parse("*"), IfMatch::Any);
+        assert_eq!(
+            IfMatch::parse("\"1\", \"3\""),
+            IfMatch::Versions(vec![1, 3])
+        );
+
+        // Weak and foreign tags can never match under If-Match
+        assert_eq!(IfMatch::parse("W/\"3\""), IfMatch::Versions(vec![]));
+        assert_eq!(IfMatch::parse("\"abc\""), IfMatch::Versions(vec![]));
+    }
+
+    #[test]
+    fn test_if_match_matches() {
+        assert!(IfMatch::Any.matches(42));
+        assert!(IfMatch::parse("\"2\"").matches(2));
+        assert!(!IfMatch::parse("\"2\"").matches(3));
+        assert!(!IfMatch::parse("W/\"2\"").matches(2));
+    }
+
+    #[test]
+    fn test_precondition_failed_is_412_with_current_etag() {
+        let error =
+            AppError::precondition_failed("Product".to_string(), None, Some(version_etag(4)));
+        let definition = ErrorRegistry::get_definition(&error);
+        assert_eq!(definition.status_code, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(definition.code, "RESOURCE_PRECONDITION_FAILED");
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(response.headers()["etag"], "\"4\"");
+    }
+
+    #[tokio::test]
+    async fn test_stale_if_match_is_rejected_and_leaves_the_row_alone() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let product = repo.create(create_request("Desk", 3)).await.unwrap();
+        let first = repo
+            .update(product.id, rename("Standing desk"), None, None)
+            .await
+            .unwrap();
+
+        // A client still holding the ETag from before the first update
+        let stale = IfMatch::parse(&version_etag(product.version));
+        let error = repo
+            .update(product.id, rename("Corner desk"), Some(stale), None)
+            .await
+            .unwrap_err();
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(response.headers()["etag"], version_etag(first.version));
+
+        let current = repo.find_by_id(product.id).await.unwrap().unwrap();
+        assert_eq!(current.name, "Standing desk");
+        assert_eq!(current.version, first.version);
+        assert_eq!(current.updated_at, first.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_matching_if_match_updates_and_bumps_the_etag() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let product = repo.create(create_request("Desk", 3)).await.unwrap();
+
+        let current = IfMatch::parse(&version_etag(product.version));
+        let updated = repo
+            .update(product.id, rename("Standing desk"), Some(current), None)
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "Standing desk");
+        assert_eq!(updated.version, product.version + 1);
+
+        // `*` matches whatever the current version is
+        let updated = repo
+            .update(product.id, rename("Corner desk"), Some(IfMatch::Any), None)
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "Corner desk");
+        assert_eq!(updated.version, product.version + 2);
+    }
+}
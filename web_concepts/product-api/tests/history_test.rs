@@ -0,0 +1,111 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{create_request, sample_product, test_db};
+    use chrono::Utc;
+    use product_api::entities::product_history::HistoryAction;
+    use product_api::models::UpdateProductRequest;
+    use product_api::repository::product_history::diff_products;
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use product_api::utils::IfMatch;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    #[test]
+    fn test_update_diff_only_lists_changed_fields() {
+        let old = sample_product();
+        let mut new = old.clone();
+        new.price = dec!(17.50);
+        new.quantity = 45;
+        // Bookkeeping columns are not audited
+        new.version = 2;
+        new.updated_at = Utc::now();
+
+        let changes = diff_products(Some(&old), Some(&new));
+        assert_eq!(
+            changes,
+            json!({
+                "price": { "old": "15.99", "new": "17.50" },
+                "quantity": { "old": 50, "new": 45 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_unchanged_product_has_empty_diff() {
+        let product = sample_product();
+        assert_eq!(diff_products(Some(&product), Some(&product)), json!({}));
+    }
+
+    #[test]
+    fn test_create_and_purge_diff_against_nothing() {
+        let product = sample_product();
+
+        let created = diff_products(None, Some(&product));
+        assert_eq!(created["name"], json!({ "old": null, "new": "Coffee Mug" }));
+        // Fields that are null on both sides are left out
+        assert!(created.get("deleted_at").is_none());
+
+        let purged = diff_products(Some(&product), None);
+        assert_eq!(purged["quantity"], json!({ "old": 50, "new": null }));
+    }
+
+    #[test]
+    fn test_history_action_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_value(HistoryAction::Restore).unwrap(),
+            json!("restore")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writes_record_their_diff_in_the_same_transaction() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let product = repo.create(create_request("Desk", 3)).await.unwrap();
+
+        let request: UpdateProductRequest =
+            serde_json::from_value(json!({ "price": "12.50", "description": "Oak top" })).unwrap();
+        repo.update(product.id, request, None, None).await.unwrap();
+
+        // A write that fails rolls its history entry back with it
+        let request: UpdateProductRequest =
+            serde_json::from_value(json!({ "name": "Standing desk" })).unwrap();
+        let stale = IfMatch::Versions(vec![product.version]);
+        assert!(repo
+            .update(product.id, request, Some(stale), None)
+            .await
+            .is_err());
+
+        let (history, total) = repo.find_history(product.id, 1, 10).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(history[0].action, HistoryAction::Update);
+        assert_eq!(
+            history[0].changes,
+            json!({
+                "description": { "old": null, "new": "Oak top" },
+                "price": { "old": "10.00", "new": "12.50" },
+            })
+        );
+
+        assert!(repo.delete(product.id, None).await.unwrap());
+
+        // History stays readable while the product is in the trash
+        let (history, total) = repo.find_history(product.id, 1, 10).await.unwrap();
+        assert_eq!(total, 3);
+        let actions: Vec<_> = history.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                HistoryAction::Delete,
+                HistoryAction::Update,
+                HistoryAction::Create
+            ]
+        );
+        let changes = history[0].changes.as_object().unwrap();
+        assert_eq!(changes.keys().collect::<Vec<_>>(), vec!["deleted_at"]);
+        assert_eq!(changes["deleted_at"]["old"], json!(null));
+        assert!(changes["deleted_at"]["new"].is_string());
+    }
+}
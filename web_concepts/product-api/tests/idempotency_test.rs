@@ -0,0 +1,314 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{app_state, test_db};
+    use axum::{
+        body::{to_bytes, Body, Bytes},
+        extract::{DefaultBodyLimit, Request},
+        http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+        response::IntoResponse,
+        routing::post,
+        Extension, Router,
+    };
+    use chrono::{Duration, Utc};
+    use product_api::entities::user::UserRole;
+    use product_api::error::{AppError, ErrorRegistry, ErrorResponse};
+    use product_api::middleware::idempotency::{
+        idempotency_middleware, is_multipart, is_valid_idempotency_key, request_fingerprint,
+        RouteBodyLimits, DEFAULT_BODY_LIMIT_BYTES, IDEMPOTENCY_KEY_HEADER,
+        IDEMPOTENT_REPLAYED_HEADER,
+    };
+    use product_api::models::Claims;
+    use product_api::repository::auth::{AuthRepository, AuthRepositoryTrait};
+    use product_api::repository::idempotency_key::{
+        IdempotencyKeyRepository, IdempotencyKeyRepositoryTrait, NewIdempotencyKey,
+    };
+    use sea_orm::DatabaseConnection;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    const IMPORT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+    async fn create_user(db: &DatabaseConnection) -> Uuid {
+        AuthRepository::new(db.clone())
+            .create_user(
+                "ivan".to_string(),
+                "ivan@example.com".to_string(),
+                "hash".to_string(),
+                UserRole::Manager,
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    /// Echoes the body back with a 201, counting how often it actually ran
+    fn app(db: DatabaseConnection, user_id: Uuid, calls: Arc<AtomicUsize>) -> Router {
+        let echo = move |body: Bytes| async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            (StatusCode::CREATED, body)
+        };
+        let state = product_api::AppState { db, ..app_state() };
+        let body_limits = RouteBodyLimits::new([(Method::POST, "/import", IMPORT_BODY_LIMIT)]);
+        Router::new()
+            .route("/orders", post(echo.clone()))
+            .route(
+                "/import",
+                post(echo).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                (state, body_limits),
+                idempotency_middleware,
+            ))
+            // Stands in for auth_middleware
+            .layer(Extension(Claims {
+                sub: user_id.to_string(),
+                username: "ivan".to_string(),
+                role: UserRole::Manager,
+                exp: usize::MAX,
+                iat: 0,
+                jti: "test".to_string(),
+            }))
+    }
+
+    async fn send(
+        app: &Router,
+        uri: &str,
+        key: &str,
+        body: Vec<u8>,
+    ) -> (StatusCode, HeaderMap, Bytes) {
+        let request = Request::post(uri)
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        (
+            parts.status,
+            parts.headers,
+            to_bytes(body, usize::MAX).await.unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_idempotency_key_validation() {
+        assert!(is_valid_idempotency_key(
+            "5f1c0a7e-8a8e-4b8e-9a53-2f0f3c2d9b11"
+        ));
+        assert!(is_valid_idempotency_key(&"k".repeat(255)));
+
+        assert!(!is_valid_idempotency_key(""));
+        assert!(!is_valid_idempotency_key(&"k".repeat(256)));
+        assert!(!is_valid_idempotency_key("has space"));
+        assert!(!is_valid_idempotency_key("ünicode"));
+    }
+
+    #[test]
+    fn test_fingerprint_covers_method_path_and_body() {
+        let body = br#"{"name":"Mouse","price":"19.99","quantity":10}"#;
+        let fingerprint = request_fingerprint(&Method::POST, "/products", body);
+
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(
+            fingerprint,
+            request_fingerprint(&Method::POST, "/products", body)
+        );
+
+        assert_ne!(
+            fingerprint,
+            request_fingerprint(&Method::PUT, "/products", body)
+        );
+        assert_ne!(
+            fingerprint,
+            request_fingerprint(&Method::POST, "/products?atomic=true", body)
+        );
+        assert_ne!(
+            fingerprint,
+            request_fingerprint(
+                &Method::POST,
+                "/products",
+                br#"{"name":"Mouse","price":"19.99","quantity":11}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_multipart_uploads_are_not_buffered() {
+        let headers = |content_type: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+            headers
+        };
+
+        assert!(is_multipart(&headers(
+            "multipart/form-data; boundary=X-BOUNDARY"
+        )));
+        assert!(!is_multipart(&headers("application/json")));
+        assert!(!is_multipart(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_reused_key_is_a_registered_422() {
+        let error = AppError::idempotency_key_reused("order-42".to_string());
+        let definition = ErrorRegistry::get_definition(&error);
+        assert_eq!(definition.code, "RESOURCE_IDEMPOTENCY_KEY_REUSED");
+        assert_eq!(definition.status_code, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(ErrorRegistry::is_valid_error_code(definition.code));
+
+        let response = ErrorResponse::from_app_error(&error, None);
+        assert_eq!(response.error.details["idempotency_key"], "order-42");
+
+        assert_eq!(
+            error.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[test]
+    fn test_routes_without_a_limit_get_the_default() {
+        let limits = RouteBodyLimits::new([(Method::POST, "/products/import", IMPORT_BODY_LIMIT)]);
+        assert_eq!(
+            limits.limit(&Method::POST, "/products/import"),
+            IMPORT_BODY_LIMIT
+        );
+        assert_eq!(
+            limits.limit(&Method::PUT, "/products/import"),
+            DEFAULT_BODY_LIMIT_BYTES
+        );
+        assert_eq!(
+            limits.limit(&Method::POST, "/products"),
+            DEFAULT_BODY_LIMIT_BYTES
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finished_key_replays_the_stored_response() {
+        let Some(db) = test_db().await else { return };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(db.clone(), create_user(&db).await, calls.clone());
+
+        let (status, _, body) = send(&app, "/orders", "order-1", b"two mugs".to_vec()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body, "two mugs");
+
+        let (status, headers, body) = send(&app, "/orders", "order-1", b"two mugs".to_vec()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body, "two mugs");
+        assert_eq!(headers[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_for_a_different_body_is_rejected() {
+        let Some(db) = test_db().await else { return };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(db.clone(), create_user(&db).await, calls.clone());
+
+        let (status, _, _) = send(&app, "/orders", "order-1", b"two mugs".to_vec()).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _, _) = send(&app, "/orders", "order-1", b"three mugs".to_vec()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_still_in_flight_conflicts() {
+        let Some(db) = test_db().await else { return };
+        let user_id = create_user(&db).await;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(db.clone(), user_id, calls.clone());
+
+        // The first request holds the claim but has not finished yet
+        let body = b"two mugs".to_vec();
+        IdempotencyKeyRepository::new(db)
+            .try_claim(NewIdempotencyKey {
+                user_id,
+                idempotency_key: "order-1".to_string(),
+                request_method: "POST".to_string(),
+                request_path: "/orders".to_string(),
+                fingerprint: request_fingerprint(&Method::POST, "/orders", &body),
+                expires_at: Utc::now() + Duration::hours(1),
+            })
+            .await
+            .unwrap()
+            .expect("key is free");
+
+        let (status, _, _) = send(&app, "/orders", "order-1", body).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_keyed_bodies_are_buffered_up_to_the_route_limit() {
+        let Some(db) = test_db().await else { return };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(db.clone(), create_user(&db).await, calls.clone());
+        let body = vec![b'x'; DEFAULT_BODY_LIMIT_BYTES + 1];
+
+        let (status, _, _) = send(&app, "/import", "import-1", body.clone()).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _, _) = send(&app, "/orders", "order-1", body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_taken_over_claim_is_not_completed_or_released() {
+        let Some(db) = test_db().await else { return };
+        let user_id = create_user(&db).await;
+        let repository = IdempotencyKeyRepository::new(db);
+        let claimed_at = repository
+            .try_claim(NewIdempotencyKey {
+                user_id,
+                idempotency_key: "order-1".to_string(),
+                request_method: "POST".to_string(),
+                request_path: "/orders".to_string(),
+                fingerprint: "f".repeat(64),
+                expires_at: Utc::now() + Duration::hours(1),
+            })
+            .await
+            .unwrap()
+            .expect("key is free");
+
+        // A request whose claim was taken over holds an older claim time
+        let stale = claimed_at - Duration::seconds(61);
+        repository
+            .complete(
+                user_id,
+                "order-1",
+                stale,
+                201,
+                serde_json::json!({}),
+                b"stale".to_vec(),
+            )
+            .await
+            .unwrap();
+        repository.release(user_id, "order-1", stale).await.unwrap();
+
+        let row = repository.find(user_id, "order-1").await.unwrap().unwrap();
+        assert_eq!(row.status_code, None);
+
+        repository
+            .complete(
+                user_id,
+                "order-1",
+                claimed_at,
+                201,
+                serde_json::json!({}),
+                b"fresh".to_vec(),
+            )
+            .await
+            .unwrap();
+        let row = repository.find(user_id, "order-1").await.unwrap().unwrap();
+        assert_eq!(row.status_code, Some(201));
+        assert_eq!(row.response_body.as_deref(), Some(&b"fresh"[..]));
+    }
+}
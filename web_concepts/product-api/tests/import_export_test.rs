@@ -0,0 +1,116 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use product_api::entities::product;
+    use product_api::utils::{export_header, export_rows, parse_import, TransferFormat};
+    use rust_decimal_macros::dec;
+
+    fn sample_product() -> product::Model {
+        product::Model {
+            name: "Desk, oak".to_string(),
+            description: None,
+            price: dec!(149.99),
+            quantity: 3,
+            category: Some("Furniture".to_string()),
+            ..common::sample_product()
+        }
+    }
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(TransferFormat::from_name("CSV"), Some(TransferFormat::Csv));
+        assert_eq!(
+            TransferFormat::from_name("jsonl"),
+            Some(TransferFormat::Ndjson)
+        );
+        assert_eq!(TransferFormat::from_name("xml"), None);
+
+        assert_eq!(
+            TransferFormat::from_content_type("text/csv; charset=utf-8"),
+            Some(TransferFormat::Csv)
+        );
+        assert_eq!(
+            TransferFormat::from_content_type("application/x-ndjson"),
+            Some(TransferFormat::Ndjson)
+        );
+        assert_eq!(TransferFormat::from_content_type("application/json"), None);
+    }
+
+    #[test]
+    fn test_parse_csv_reports_line_numbers() {
+        let body = b"name,description,price,quantity,category\n\
+            Mouse,Wireless,19.99,10,Electronics\n\
+            Keyboard,,not-a-price,5,Electronics\n\
+            Mug,Ceramic,7.50,100,\n";
+
+        let rows = parse_import(body, TransferFormat::Csv);
+        assert_eq!(rows.len(), 3);
+
+        let mouse = rows[0].result.as_ref().unwrap();
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(mouse.name, "Mouse");
+        assert_eq!(mouse.price, dec!(19.99));
+        assert_eq!(mouse.quantity, 10);
+
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].result.is_err());
+
+        // Empty CSV fields become None for optional columns
+        let mug = rows[2].result.as_ref().unwrap();
+        assert_eq!(rows[2].line, 4);
+        assert_eq!(mug.category, None);
+    }
+
+    #[test]
+    fn test_parse_ndjson_skips_blank_lines() {
+        let body = b"{\"name\":\"Mouse\",\"price\":\"19.99\",\"quantity\":10}\n\
+            \n\
+            {\"name\":\"Broken\"\n";
+
+        let rows = parse_import(body, TransferFormat::Ndjson);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 1);
+        assert!(rows[0].result.is_ok());
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].result.is_err());
+    }
+
+    #[test]
+    fn test_csv_export_round_trips_through_import() {
+        let product = sample_product();
+
+        let mut body = export_header(TransferFormat::Csv);
+        body.extend(export_rows(std::slice::from_ref(&product), TransferFormat::Csv).unwrap());
+
+        let text = String::from_utf8(body.clone()).unwrap();
+        assert!(text.starts_with("id,name,description,price,quantity,category,"));
+        assert!(text.contains("\"Desk, oak\""), "Commas must be quoted");
+
+        let rows = parse_import(&body, TransferFormat::Csv);
+        let imported = rows[0].result.as_ref().unwrap();
+        assert_eq!(imported.name, product.name);
+        assert_eq!(imported.price, product.price);
+        assert_eq!(imported.category, product.category);
+    }
+
+    #[test]
+    fn test_ndjson_export_has_one_object_per_line() {
+        assert!(export_header(TransferFormat::Ndjson).is_empty());
+
+        let body = export_rows(
+            &[sample_product(), sample_product()],
+            TransferFormat::Ndjson,
+        )
+        .unwrap();
+        let text = String::from_utf8(body).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["name"], "Desk, oak");
+        }
+    }
+}
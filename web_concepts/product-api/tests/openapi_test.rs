@@ -0,0 +1,174 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::app_state;
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use product_api::{
+        app::{create_app, protected_routes, public_routes},
+        openapi::ApiDoc,
+    };
+    use serde_json::Value;
+    use std::collections::BTreeSet;
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+    /// (method, OpenAPI path) of every route `create_app` serves from its route tables
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        let state = app_state();
+        let (public, protected) = (public_routes(&state), protected_routes(&state));
+
+        public
+            .routes()
+            .iter()
+            .chain(protected.routes())
+            .map(|(method, path)| {
+                // Axum's `:id` is OpenAPI's `{id}`
+                let path = path
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(name) => format!("{{{name}}}"),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (method.as_str().to_lowercase(), path)
+            })
+            .collect()
+    }
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    fn documented_operations(spec: &Value) -> BTreeSet<(String, String)> {
+        let mut operations = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in METHODS {
+                if item.get(method).is_some() {
+                    operations.insert((method.to_string(), path.clone()));
+                }
+            }
+        }
+        operations
+    }
+
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference.clone());
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_spec_covers_every_route() {
+        let routes = registered_routes();
+        assert!(routes.len() > 50, "Only found {} routes", routes.len());
+
+        let documented = documented_operations(&spec());
+
+        let undocumented: Vec<_> = routes.difference(&documented).collect();
+        assert!(
+            undocumented.is_empty(),
+            "Routes missing from the OpenAPI spec: {undocumented:?}"
+        );
+
+        let unrouted: Vec<_> = documented.difference(&routes).collect();
+        assert!(
+            unrouted.is_empty(),
+            "Spec operations without a route: {unrouted:?}"
+        );
+    }
+
+    #[test]
+    fn test_every_reference_resolves() {
+        let spec = spec();
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+        assert!(!refs.is_empty());
+
+        for reference in refs {
+            let target = reference
+                .strip_prefix("#/")
+                .unwrap_or_else(|| panic!("Non-local reference {reference}"))
+                .split('/')
+                .fold(&spec, |node, key| &node[key]);
+            assert!(!target.is_null(), "Dangling reference {reference}");
+        }
+    }
+
+    #[test]
+    fn test_protected_operations_document_error_envelope() {
+        let spec = spec();
+        assert_eq!(
+            spec["components"]["securitySchemes"]["bearer_auth"]["scheme"],
+            "bearer"
+        );
+        assert!(spec["components"]["schemas"]["ErrorResponse"]["properties"]["error"].is_object());
+
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in METHODS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                if operation.get("security").is_none() {
+                    continue;
+                }
+                for (status, response) in [("401", "Unauthorized"), ("403", "Forbidden")] {
+                    assert_eq!(
+                        operation["responses"][status]["$ref"],
+                        format!("#/components/responses/{response}"),
+                        "{method} {path} lacks a {status} response"
+                    );
+                }
+            }
+        }
+
+        // Handler-specific errors use the same envelope
+        assert_eq!(
+            spec["paths"]["/products/{id}"]["get"]["responses"]["404"]["content"]
+                ["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+        // Login is public
+        assert!(spec["paths"]["/auth/login"]["post"]
+            .get("security")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_openapi_json_is_served() {
+        let app = create_app(app_state()).unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/api-docs/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let served: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(served, spec());
+
+        let response = app
+            .oneshot(Request::get("/swagger-ui/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
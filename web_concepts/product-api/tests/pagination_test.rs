@@ -0,0 +1,128 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{self, conversion};
+    use product_api::models::{CreateProductRequest, ProductSearchRequest};
+    use product_api::repository::product::{ProductSort, ProductSortField};
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use product_api::utils::{decode_cursor, encode_cursor, CurrencyConversion, Cursor};
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            sort: "price:asc".to_string(),
+            value: serde_json::json!("49.99"),
+            id: Uuid::new_v4(),
+        };
+
+        let encoded = encode_cursor(&cursor);
+        assert!(!encoded.contains('='), "Cursor should be URL safe");
+        assert_eq!(decode_cursor(&encoded).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_invalid_cursor_rejected() {
+        assert!(decode_cursor("not-a-cursor").is_err());
+        assert!(decode_cursor("").is_err());
+    }
+
+    #[test]
+    fn test_sort_defaults() {
+        // No sort requested: newest first
+        let sort = ProductSort::from_params(None, None);
+        assert_eq!(sort.field, ProductSortField::CreatedAt);
+        assert!(sort.descending);
+
+        // Other fields default to ascending
+        let sort = ProductSort::from_params(Some("price"), None);
+        assert_eq!(sort.key(), "price:asc");
+
+        let sort = ProductSort::from_params(Some("name"), Some("desc"));
+        assert_eq!(sort.key(), "name:desc");
+
+        // Unknown fields fall back to the default sort
+        let sort = ProductSort::from_params(Some("bogus"), Some("asc"));
+        assert_eq!(sort.key(), "created_at:desc");
+
+        let sort = ProductSort::from_params(Some("created_at"), Some("asc"));
+        assert_eq!(sort.key(), "created_at:asc");
+    }
+
+    /// Names of the "Sort probe" products one cursor page at a time
+    async fn walk_pages(
+        repo: &ProductRepository,
+        sort_order: &str,
+        conversion: &CurrencyConversion,
+    ) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let request: ProductSearchRequest = serde_json::from_value(json!({
+                "query": "Sort probe",
+                "sort_by": "price",
+                "sort_order": sort_order,
+                "per_page": 1,
+                "cursor": cursor,
+            }))
+            .unwrap();
+            let page = repo.search(request, conversion).await.unwrap();
+            names.extend(page.products.into_iter().map(|product| product.name));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return names,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_price_sort_pages_by_listed_price() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let repo = ProductRepository::new(db);
+        // 10.00 EUR lists at 10.87 USD, above the 10.50 USD product
+        for (name, price, currency) in [
+            ("Sort probe A", dec!(10.00), "USD"),
+            ("Sort probe B", dec!(10.00), "EUR"),
+            ("Sort probe C", dec!(10.50), "USD"),
+        ] {
+            repo.create(CreateProductRequest {
+                price,
+                currency: Some(currency.to_string()),
+                ..common::create_request(name, 1)
+            })
+            .await
+            .unwrap();
+        }
+
+        let usd = conversion("USD");
+        assert_eq!(
+            walk_pages(&repo, "asc", &usd).await,
+            vec!["Sort probe A", "Sort probe C", "Sort probe B"]
+        );
+        assert_eq!(
+            walk_pages(&repo, "desc", &usd).await,
+            vec!["Sort probe B", "Sort probe C", "Sort probe A"]
+        );
+
+        // A USD price cursor can't continue a EUR listing
+        let request = |cursor: Option<String>| -> ProductSearchRequest {
+            serde_json::from_value(json!({
+                "query": "Sort probe",
+                "sort_by": "price",
+                "per_page": 1,
+                "cursor": cursor,
+            }))
+            .unwrap()
+        };
+        let first = repo.search(request(None), &usd).await.unwrap();
+        assert!(repo
+            .search(request(first.next_cursor), &conversion("EUR"))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{conversion, create_request, test_db};
+    use chrono::{Duration, Utc};
+    use product_api::entities::price_schedule::{self, PriceScheduleStatus};
+    use product_api::models::{
+        CreatePriceScheduleRequest, CreateProductRequest, ProductSearchRequest,
+        UpdatePriceScheduleRequest,
+    };
+    use product_api::repository::{
+        effective_price_sql, PriceScheduleRepository, PriceScheduleRepositoryTrait,
+        ProductRepository, ProductRepositoryTrait,
+    };
+    use rust_decimal_macros::dec;
+    use sea_orm::ConnectionTrait;
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    fn schedule(
+        effective_from: chrono::DateTime<Utc>,
+        effective_to: Option<chrono::DateTime<Utc>>,
+    ) -> price_schedule::Model {
+        price_schedule::Model {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            price: dec!(9.99),
+            effective_from,
+            effective_to,
+            label: None,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_status_follows_the_window() {
+        let now = Utc::now();
+        let day = Duration::days(1);
+
+        let upcoming = schedule(now + day, None);
+        assert_eq!(upcoming.status_at(now), PriceScheduleStatus::Scheduled);
+
+        let running = schedule(now - day, Some(now + day));
+        assert_eq!(running.status_at(now), PriceScheduleStatus::Active);
+
+        let open_ended = schedule(now - day, None);
+        assert_eq!(open_ended.status_at(now), PriceScheduleStatus::Active);
+
+        // The window is half-open: it has ended exactly at effective_to
+        let ended = schedule(now - day - day, Some(now));
+        assert_eq!(ended.status_at(now), PriceScheduleStatus::Expired);
+        assert_eq!(
+            ended.status_at(ended.effective_from),
+            PriceScheduleStatus::Active
+        );
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let valid = CreatePriceScheduleRequest {
+            price: dec!(0),
+            effective_from: Utc::now(),
+            effective_to: None,
+            label: Some("Clearance".to_string()),
+        };
+        assert!(valid.validate().is_ok(), "A free item is allowed");
+
+        let negative = CreatePriceScheduleRequest {
+            price: dec!(-1),
+            ..valid
+        };
+        assert!(negative.validate().is_err());
+
+        let long_label = UpdatePriceScheduleRequest {
+            price: None,
+            effective_from: None,
+            effective_to: None,
+            clear_effective_to: false,
+            label: Some("x".repeat(101)),
+        };
+        assert!(long_label.validate().is_err());
+    }
+
+    #[test]
+    fn test_effective_price_sql_falls_back_to_base_price() {
+        let sql = effective_price_sql("p");
+
+        assert!(sql.starts_with("COALESCE("));
+        assert!(sql.contains("ps.product_id = p.id"));
+        assert!(sql.ends_with(", p.price)"));
+        // Latest start wins when windows overlap
+        assert!(sql.contains("ORDER BY ps.effective_from DESC"));
+    }
+
+    #[tokio::test]
+    async fn test_price_filters_use_the_scheduled_price_while_it_is_active() {
+        let Some(db) = test_db().await else { return };
+        db.execute_unprepared("TRUNCATE products CASCADE")
+            .await
+            .unwrap();
+        let repo = ProductRepository::new(db.clone());
+        let schedules = PriceScheduleRepository::new(db);
+        let now = Utc::now();
+
+        // Both list at 10.00; only the lamp's 30.00 schedule is running now
+        let lamp = repo.create(create_request("Lamp", 1)).await.unwrap();
+        let fan = repo.create(create_request("Fan", 1)).await.unwrap();
+        repo.create(CreateProductRequest {
+            price: dec!(40.00),
+            ..create_request("Clock", 1)
+        })
+        .await
+        .unwrap();
+        let windows = [
+            (lamp.id, now - Duration::hours(1), now + Duration::hours(1)),
+            (fan.id, now - Duration::hours(2), now - Duration::hours(1)),
+        ];
+        for (product_id, from, to) in windows {
+            schedules
+                .create(product_id, dec!(30.00), from, Some(to), None, None)
+                .await
+                .unwrap();
+        }
+
+        let search = |min: &str, max: &str| {
+            let request: ProductSearchRequest =
+                serde_json::from_value(json!({ "min_price": min, "max_price": max })).unwrap();
+            let repo = repo.clone();
+            async move {
+                let page = repo.search(request, &conversion("USD")).await.unwrap();
+                page.products
+                    .into_iter()
+                    .map(|product| product.name)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(search("25", "35").await, vec!["Lamp"]);
+        // The fan's schedule has ended, so it is back at its listed price
+        assert_eq!(search("5", "15").await, vec!["Fan"]);
+
+        let in_range = repo
+            .find_by_price_range(dec!(25), dec!(35), &conversion("USD"))
+            .await
+            .unwrap();
+        let ids: Vec<_> = in_range.iter().map(|product| product.id).collect();
+        assert_eq!(ids, vec![lamp.id]);
+
+        let current = repo.find_current_prices(&[lamp.id, fan.id]).await.unwrap();
+        assert_eq!(current.get(&lamp.id), Some(&dec!(30.00)));
+        assert_eq!(current.get(&fan.id), None);
+    }
+}
@@ -0,0 +1,203 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::sample_product;
+    use product_api::cache::{
+        CacheKey, CachedValue, DisabledProductCache, InMemoryProductCache, ProductCache,
+    };
+    use product_api::entities::product;
+    use product_api::models::{CategoryStats, ProductStatsResponse};
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn cached_product(id: Uuid) -> CachedValue {
+        CachedValue::Product(product::Model {
+            id,
+            ..sample_product()
+        })
+    }
+
+    fn cached_stats() -> CachedValue {
+        CachedValue::Stats(ProductStatsResponse {
+            currency: "USD".to_string(),
+            total_products: 1,
+            total_value: dec!(199.90),
+            avg_price: Some(dec!(19.99)),
+            total_variants: 0,
+            variant_value: dec!(0),
+            categories: Vec::new(),
+        })
+    }
+
+    fn cached_trending() -> CachedValue {
+        CachedValue::TrendingCategories(vec![CategoryStats {
+            category_id: Uuid::new_v4(),
+            category: "Electronics".to_string(),
+            slug: "electronics".to_string(),
+            parent_id: None,
+            count: 1,
+            total_value: dec!(199.90),
+        }])
+    }
+
+    /// Read-through `put`: the generation is taken right before the value
+    async fn store(cache: &impl ProductCache, key: CacheKey, value: CachedValue) {
+        let generation = cache.generation(&key).await;
+        cache.put(key, value, generation).await;
+    }
+
+    fn stats_key() -> CacheKey {
+        CacheKey::Stats {
+            currency: "USD".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hits_and_misses_are_counted() {
+        let cache = InMemoryProductCache::new(Duration::from_secs(60), 10);
+
+        assert!(cache.get(&stats_key()).await.is_none());
+        store(&cache, stats_key(), cached_stats()).await;
+        assert!(matches!(
+            cache.get(&stats_key()).await,
+            Some(CachedValue::Stats(_))
+        ));
+        assert!(cache.get(&stats_key()).await.is_some());
+
+        // Other currencies are cached separately
+        let euro_stats = CacheKey::Stats {
+            currency: "EUR".to_string(),
+        };
+        assert!(cache.get(&euro_stats).await.is_none());
+
+        let metrics = cache.metrics();
+        assert!(metrics.enabled);
+        assert_eq!(metrics.hits, 2);
+        assert_eq!(metrics.misses, 2);
+        assert_eq!(metrics.hit_ratio, 0.5);
+        assert_eq!(metrics.entries, 1);
+        assert_eq!(metrics.ttl_seconds, 60);
+    }
+
+    #[tokio::test]
+    async fn test_entries_expire_after_ttl() {
+        let cache = InMemoryProductCache::new(Duration::from_millis(50), 10);
+        store(&cache, stats_key(), cached_stats()).await;
+        assert!(cache.get(&stats_key()).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+
+        assert!(cache.get(&stats_key()).await.is_none());
+        assert_eq!(cache.metrics().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_used_entry_is_evicted() {
+        let cache = InMemoryProductCache::new(Duration::from_secs(60), 2);
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        store(&cache, CacheKey::Product(first), cached_product(first)).await;
+        store(&cache, CacheKey::Product(second), cached_product(second)).await;
+        // Reading `first` makes `second` the least recently used
+        assert!(cache.get(&CacheKey::Product(first)).await.is_some());
+        store(&cache, CacheKey::Product(third), cached_product(third)).await;
+
+        assert!(cache.get(&CacheKey::Product(first)).await.is_some());
+        assert!(cache.get(&CacheKey::Product(second)).await.is_none());
+        assert!(cache.get(&CacheKey::Product(third)).await.is_some());
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.evictions, 1);
+        assert_eq!(metrics.entries, 2);
+        assert_eq!(metrics.capacity, 2);
+    }
+
+    #[tokio::test]
+    async fn test_product_write_invalidates_product_and_aggregates() {
+        let cache = InMemoryProductCache::new(Duration::from_secs(60), 10);
+        let (written, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let trending = CacheKey::TrendingCategories {
+            limit: 10,
+            currency: "USD".to_string(),
+        };
+
+        store(&cache, CacheKey::Product(written), cached_product(written)).await;
+        store(&cache, CacheKey::Product(other), cached_product(other)).await;
+        store(&cache, stats_key(), cached_stats()).await;
+        store(&cache, trending.clone(), cached_trending()).await;
+
+        cache.invalidate_product(written).await;
+
+        assert!(cache.get(&CacheKey::Product(written)).await.is_none());
+        assert!(cache.get(&CacheKey::Product(other)).await.is_some());
+        assert!(cache.get(&stats_key()).await.is_none());
+        assert!(cache.get(&trending).await.is_none());
+
+        store(&cache, stats_key(), cached_stats()).await;
+        cache.invalidate_aggregates().await;
+        assert!(cache.get(&stats_key()).await.is_none());
+        assert!(cache.get(&CacheKey::Product(other)).await.is_some());
+
+        cache.invalidate_all().await;
+        assert_eq!(cache.metrics().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cache_never_hits() {
+        let cache = DisabledProductCache;
+        store(&cache, stats_key(), cached_stats()).await;
+
+        assert!(cache.get(&stats_key()).await.is_none());
+        let metrics = cache.metrics();
+        assert!(!metrics.enabled);
+        assert_eq!(metrics.hits + metrics.misses, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reads_started_before_an_invalidation_are_not_stored() {
+        let cache = InMemoryProductCache::new(Duration::from_secs(60), 10);
+        let written = Uuid::new_v4();
+        // Next to `written`, so never on the same counter
+        let other = Uuid::from_u128(written.as_u128().wrapping_add(1));
+        let product_generation = cache.generation(&CacheKey::Product(written)).await;
+        let stats_generation = cache.generation(&stats_key()).await;
+        let other_generation = cache.generation(&CacheKey::Product(other)).await;
+
+        // The write lands while the reads are still in the database
+        cache.invalidate_product(written).await;
+
+        cache
+            .put(
+                CacheKey::Product(written),
+                cached_product(written),
+                product_generation,
+            )
+            .await;
+        cache
+            .put(stats_key(), cached_stats(), stats_generation)
+            .await;
+        assert!(cache.get(&CacheKey::Product(written)).await.is_none());
+        assert!(cache.get(&stats_key()).await.is_none());
+
+        // A read that starts after the write is stored
+        store(&cache, CacheKey::Product(written), cached_product(written)).await;
+        assert!(cache.get(&CacheKey::Product(written)).await.is_some());
+
+        // Other products keep their entries
+        cache
+            .put(
+                CacheKey::Product(other),
+                cached_product(other),
+                other_generation,
+            )
+            .await;
+        assert!(cache.get(&CacheKey::Product(other)).await.is_some());
+
+        let generation = cache.generation(&stats_key()).await;
+        cache.invalidate_all().await;
+        cache.put(stats_key(), cached_stats(), generation).await;
+        assert!(cache.get(&stats_key()).await.is_none());
+    }
+}
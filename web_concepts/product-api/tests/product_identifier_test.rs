@@ -0,0 +1,189 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{create_request, test_db};
+    use axum::{http::StatusCode, response::IntoResponse};
+    use product_api::error::{field_conflict_error, AppError, ErrorResponse};
+    use product_api::models::{CreateProductRequest, ProductLookupQuery, UpdateProductRequest};
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use product_api::utils::{gtin_check_digit, is_valid_barcode, normalize_barcode};
+    use serde_json::json;
+    use validator::Validate;
+
+    #[test]
+    fn test_check_digits() {
+        // EAN-13 4006381333931 and UPC-A 036000291452
+        assert_eq!(gtin_check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+        assert_eq!(gtin_check_digit(&[0, 3, 6, 0, 0, 0, 2, 9, 1, 4, 5]), 2);
+
+        assert!(is_valid_barcode("4006381333931"));
+        assert!(is_valid_barcode("036000291452"));
+        assert!(!is_valid_barcode("4006381333932"), "Wrong check digit");
+        assert!(!is_valid_barcode("036000291453"), "Wrong check digit");
+    }
+
+    #[test]
+    fn test_barcode_shape() {
+        assert!(!is_valid_barcode(""));
+        assert!(!is_valid_barcode("12345670"), "EAN-8 is not accepted");
+        assert!(
+            !is_valid_barcode("40063813339310"),
+            "GTIN-14 is not accepted"
+        );
+        assert!(!is_valid_barcode("400638133393A"));
+        assert!(!is_valid_barcode(" 036000291452"));
+    }
+
+    #[test]
+    fn test_upc_a_normalizes_to_ean_13() {
+        assert_eq!(normalize_barcode("036000291452"), "0036000291452");
+        assert_eq!(normalize_barcode("4006381333931"), "4006381333931");
+        // The leading zero keeps the check digit valid
+        assert!(is_valid_barcode(&normalize_barcode("036000291452")));
+    }
+
+    #[test]
+    fn test_product_request_validation() {
+        let request: CreateProductRequest = serde_json::from_value(json!({
+            "name": "Ballpoint pen",
+            "price": "1.99",
+            "quantity": 100,
+            "sku": "PEN-BLU-01",
+            "barcode": "4006381333931"
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+
+        let request: CreateProductRequest = serde_json::from_value(json!({
+            "name": "Ballpoint pen",
+            "price": "1.99",
+            "quantity": 100,
+            "sku": "PEN BLU",
+            "barcode": "4006381333932"
+        }))
+        .unwrap();
+        let errors = request.validate().unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("sku"));
+        assert!(fields.contains_key("barcode"));
+
+        let request: UpdateProductRequest =
+            serde_json::from_value(json!({ "barcode": "03600029145" })).unwrap();
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_lookup_query_validation() {
+        let query: ProductLookupQuery =
+            serde_json::from_value(json!({ "barcode": "036000291452" })).unwrap();
+        assert!(query.validate().is_ok());
+
+        let query: ProductLookupQuery =
+            serde_json::from_value(json!({ "barcode": "036000291453" })).unwrap();
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_conflict_names_the_field() {
+        let error = field_conflict_error("Product", "barcode", "Barcode is already in use");
+        let response = ErrorResponse::from_app_error(&error, None);
+
+        assert_eq!(response.error.code, "RESOURCE_CONFLICT");
+        assert_eq!(response.error.details["field"], "barcode");
+        assert_eq!(response.error.details["resource_type"], "Product");
+    }
+
+    fn with_identifiers(name: &str, sku: &str, barcode: &str) -> CreateProductRequest {
+        CreateProductRequest {
+            sku: Some(sku.to_string()),
+            barcode: Some(barcode.to_string()),
+            ..create_request(name, 10)
+        }
+    }
+
+    fn conflict_field(error: AppError) -> String {
+        let response = ErrorResponse::from_app_error(&error, None);
+        assert_eq!(error.into_response().status(), StatusCode::CONFLICT);
+        response.error.details["field"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_identifiers_conflict() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        repo.create(with_identifiers("Blue pen", "PEN-BLU-01", "4006381333931"))
+            .await
+            .unwrap();
+
+        let error = repo
+            .create(with_identifiers("Blue pen", "PEN-BLU-01", "036000291452"))
+            .await
+            .unwrap_err();
+        assert_eq!(conflict_field(error), "sku");
+
+        // The UPC-A spelling of a stored EAN-13 code is the same barcode
+        repo.create(with_identifiers("Red pen", "PEN-RED-01", "036000291452"))
+            .await
+            .unwrap();
+        let error = repo
+            .create(with_identifiers("Red pen", "PEN-RED-02", "0036000291452"))
+            .await
+            .unwrap_err();
+        assert_eq!(conflict_field(error), "barcode");
+    }
+
+    #[tokio::test]
+    async fn test_racing_creates_with_one_sku_conflict_once() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let request = || CreateProductRequest {
+            sku: Some("STAPLER-01".to_string()),
+            ..create_request("Stapler", 10)
+        };
+
+        let (first, second) = tokio::join!(repo.create(request()), repo.create(request()));
+        let error = match (first, second) {
+            (Ok(_), Err(error)) | (Err(error), Ok(_)) => error,
+            (first, second) => panic!("expected one conflict, got {first:?} and {second:?}"),
+        };
+        assert_eq!(conflict_field(error), "sku");
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_sku_and_barcode() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let pen = repo
+            .create(with_identifiers("Blue pen", "PEN-BLU-01", "036000291452"))
+            .await
+            .unwrap();
+
+        let found = repo.find_by_sku("PEN-BLU-01").await.unwrap().unwrap();
+        assert_eq!(found.id, pen.id);
+        assert!(repo.find_by_sku("PEN-BLU-02").await.unwrap().is_none());
+
+        // Either spelling of the code finds the product
+        for barcode in ["036000291452", "0036000291452"] {
+            let found = repo.find_by_barcode(barcode).await.unwrap().unwrap();
+            assert_eq!(found.id, pen.id);
+        }
+        assert!(repo
+            .find_by_barcode("4006381333931")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Trashed products are not found
+        assert!(repo.delete(pen.id, None).await.unwrap());
+        assert!(repo.find_by_sku("PEN-BLU-01").await.unwrap().is_none());
+        assert!(repo
+            .find_by_barcode("036000291452")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
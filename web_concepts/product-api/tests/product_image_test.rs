@@ -0,0 +1,140 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use axum::body::Bytes;
+    use product_api::error::AppError;
+    use product_api::models::MAX_IMAGES_PER_PRODUCT;
+    use product_api::repository::{
+        NewProductImage, ProductImageRepository, ProductImageRepositoryTrait, ProductRepository,
+        ProductRepositoryTrait,
+    };
+    use product_api::storage::{is_valid_blob_key, BlobStore, LocalBlobStore};
+    use product_api::utils::{image_extension, sniff_image_type, validate_image};
+    use uuid::Uuid;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+
+    #[test]
+    fn test_sniff_image_type() {
+        assert_eq!(sniff_image_type(PNG), Some("image/png"));
+        assert_eq!(sniff_image_type(JPEG), Some("image/jpeg"));
+        assert_eq!(sniff_image_type(b"GIF89a..."), Some("image/gif"));
+        assert_eq!(
+            sniff_image_type(b"RIFF\x10\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_image_type(b"%PDF-1.7"), None);
+        assert_eq!(image_extension("image/jpeg"), "jpg");
+    }
+
+    #[test]
+    fn test_validate_image() {
+        assert_eq!(
+            validate_image(Some("image/png"), PNG, 1024).unwrap(),
+            "image/png"
+        );
+        // Parameters and case in the declared type are ignored
+        assert_eq!(
+            validate_image(Some("Image/PNG; name=a.png"), PNG, 1024).unwrap(),
+            "image/png"
+        );
+
+        assert!(validate_image(None, PNG, 1024).is_err());
+        assert!(validate_image(Some("image/png"), b"", 1024).is_err());
+        assert!(
+            validate_image(Some("image/png"), PNG, 4).is_err(),
+            "Too large"
+        );
+        assert!(validate_image(Some("image/svg+xml"), PNG, 1024).is_err());
+        assert!(
+            validate_image(Some("image/png"), JPEG, 1024).is_err(),
+            "Declared type must match the content"
+        );
+    }
+
+    #[test]
+    fn test_blob_keys_stay_inside_the_root() {
+        assert!(is_valid_blob_key("products/1f0c/abc.png"));
+        assert!(!is_valid_blob_key(""));
+        assert!(!is_valid_blob_key("/etc/passwd"));
+        assert!(!is_valid_blob_key("products/../../secret"));
+        assert!(!is_valid_blob_key("products//a.png"));
+        assert!(!is_valid_blob_key("products/a b.png"));
+    }
+
+    #[tokio::test]
+    async fn test_local_blob_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("blob-store-{}", Uuid::new_v4()));
+        let store = LocalBlobStore::new(&root, "/media/");
+        let key = "products/p1/image.png";
+
+        let url = store
+            .put(key, "image/png", Bytes::from_static(PNG))
+            .await
+            .unwrap();
+        assert_eq!(url, "/media/products/p1/image.png");
+        assert_eq!(
+            store.get(key).await.unwrap().unwrap(),
+            Bytes::from_static(PNG)
+        );
+
+        store.delete(key).await.unwrap();
+        assert!(store.get(key).await.unwrap().is_none());
+        // Deleting twice is fine
+        store.delete(key).await.unwrap();
+
+        assert!(store
+            .put("../escape.png", "image/png", Bytes::from_static(PNG))
+            .await
+            .is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn stored_images(count: usize) -> Vec<NewProductImage> {
+        (0..count)
+            .map(|_| {
+                let id = Uuid::new_v4();
+                NewProductImage {
+                    id,
+                    blob_key: format!("products/test/{id}.png"),
+                    url: format!("/media/products/test/{id}.png"),
+                    content_type: "image/png".to_string(),
+                    size_bytes: PNG.len() as i64,
+                }
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_uploads_stay_within_the_image_limit() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let product = ProductRepository::new(db.clone())
+            .create(common::create_request("Gallery", 1))
+            .await
+            .unwrap();
+        let images = ProductImageRepository::new(db);
+
+        // Each upload fits on its own, but not both together
+        let half = MAX_IMAGES_PER_PRODUCT / 2 + 1;
+        let (first, second) = tokio::join!(
+            images.create_many(product.id, stored_images(half), None),
+            images.create_many(product.id, stored_images(half), None)
+        );
+        assert!(first.is_ok() != second.is_ok(), "{first:?} {second:?}");
+        let error = first.and(second).unwrap_err();
+        assert!(
+            matches!(error, AppError::BusinessRuleViolation { ref rule, .. } if rule == "max_images_per_product"),
+            "{error:?}"
+        );
+        assert_eq!(
+            images.find_by_product(product.id).await.unwrap().len(),
+            half
+        );
+    }
+}
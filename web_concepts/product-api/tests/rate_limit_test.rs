@@ -2,91 +2,568 @@
 mod tests {
     use std::net::IpAddr;
     use std::str::FromStr;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
 
-    // Import your rate limiter from the main crate
-    // Note: This assumes your main crate is named "product_api"
-    // Adjust the import path based on your actual crate name
-    use product_api::middleware::rate_limit::RateLimiter;
+    use async_trait::async_trait;
+    use axum::{body::Body, http::Request, http::StatusCode, routing::get, Router};
+    use product_api::entities::user::UserRole;
+    use product_api::error::AppError;
+    use product_api::middleware::rate_limit::{
+        ip_rate_limit_middleware, user_rate_limit_middleware, RateLimiter,
+    };
+    use product_api::models::Claims;
+    use product_api::rate_limit::{
+        gcra, InMemoryRateLimitStore, Quota, RateLimitDecision, RateLimitPolicies, RateLimitPolicy,
+        RateLimitStore, RedisRateLimitStore, RouteGroup,
+    };
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+    use tower::ServiceExt;
 
-    #[test]
-    fn test_ip_rate_limiting() {
-        let rate_limiter = RateLimiter::new(5, 10); // 5 requests per minute per IP
+    fn in_memory_limiter(per_ip: u32, per_user: u32) -> RateLimiter {
+        limiter_with_policies(per_ip, per_user, "")
+    }
+
+    fn limiter_with_policies(per_ip: u32, per_user: u32, policies: &str) -> RateLimiter {
+        RateLimiter::new(
+            Arc::new(InMemoryRateLimitStore::new(4)),
+            per_ip,
+            RateLimitPolicies::new(
+                Quota::per_minute(per_user),
+                RateLimitPolicy::parse_list(policies).unwrap(),
+            ),
+        )
+    }
+
+    async fn allowed_ip(rate_limiter: &RateLimiter, ip: IpAddr) -> bool {
+        rate_limiter.check_ip(ip).await.unwrap().allowed
+    }
+
+    async fn allowed_user(rate_limiter: &RateLimiter, user: &str) -> bool {
+        rate_limiter
+            .check_user(user, &UserRole::User, RouteGroup::Read)
+            .await
+            .unwrap()
+            .allowed
+    }
+
+    #[tokio::test]
+    async fn test_ip_rate_limiting() {
+        let rate_limiter = in_memory_limiter(5, 10); // 5 requests per minute per IP
         let test_ip = IpAddr::from_str("192.168.1.1").unwrap();
 
         // First 5 requests should succeed
         for i in 0..5 {
             assert!(
-                rate_limiter.check_ip_rate_limit(test_ip),
+                allowed_ip(&rate_limiter, test_ip).await,
                 "Request {} should have been allowed",
                 i + 1
             );
         }
 
         // 6th request should be rate limited
+        let decision = rate_limiter.check_ip(test_ip).await.unwrap();
         assert!(
-            !rate_limiter.check_ip_rate_limit(test_ip),
+            !decision.allowed,
             "6th request should have been rate limited"
         );
 
         // Check remaining requests
-        assert_eq!(rate_limiter.get_ip_remaining_requests(test_ip), 0);
+        assert_eq!(decision.remaining, 0);
     }
 
-    #[test]
-    fn test_user_rate_limiting() {
-        let rate_limiter = RateLimiter::new(5, 3); // 3 requests per minute per user
+    #[tokio::test]
+    async fn test_user_rate_limiting() {
+        let rate_limiter = in_memory_limiter(5, 3); // 3 requests per minute per user
         let test_user = "user123";
 
         // First 3 requests should succeed
         for i in 0..3 {
             assert!(
-                rate_limiter.check_user_rate_limit(test_user),
+                allowed_user(&rate_limiter, test_user).await,
                 "Request {} should have been allowed",
                 i + 1
             );
         }
 
         // 4th request should be rate limited
+        let decision = rate_limiter
+            .check_user(test_user, &UserRole::User, RouteGroup::Read)
+            .await
+            .unwrap();
         assert!(
-            !rate_limiter.check_user_rate_limit(test_user),
+            !decision.allowed,
             "4th request should have been rate limited"
         );
 
         // Check remaining requests
-        assert_eq!(rate_limiter.get_user_remaining_requests(test_user), 0);
+        assert_eq!(decision.remaining, 0);
     }
 
-    #[test]
-    fn test_different_ips_separate_limits() {
-        let rate_limiter = RateLimiter::new(2, 5); // 2 requests per minute per IP
+    #[tokio::test]
+    async fn test_different_ips_separate_limits() {
+        let rate_limiter = in_memory_limiter(2, 5); // 2 requests per minute per IP
         let ip1 = IpAddr::from_str("192.168.1.1").unwrap();
         let ip2 = IpAddr::from_str("192.168.1.2").unwrap();
 
         // Use up limit for IP1
-        assert!(rate_limiter.check_ip_rate_limit(ip1));
-        assert!(rate_limiter.check_ip_rate_limit(ip1));
-        assert!(!rate_limiter.check_ip_rate_limit(ip1)); // Should be rate limited
+        assert!(allowed_ip(&rate_limiter, ip1).await);
+        assert!(allowed_ip(&rate_limiter, ip1).await);
+        assert!(!allowed_ip(&rate_limiter, ip1).await); // Should be rate limited
 
         // IP2 should still have full limit available
-        assert!(rate_limiter.check_ip_rate_limit(ip2));
-        assert!(rate_limiter.check_ip_rate_limit(ip2));
-        assert!(!rate_limiter.check_ip_rate_limit(ip2)); // Should be rate limited
+        assert!(allowed_ip(&rate_limiter, ip2).await);
+        assert!(allowed_ip(&rate_limiter, ip2).await);
+        assert!(!allowed_ip(&rate_limiter, ip2).await); // Should be rate limited
     }
 
-    #[test]
-    fn test_different_users_separate_limits() {
-        let rate_limiter = RateLimiter::new(5, 2); // 2 requests per minute per user
+    #[tokio::test]
+    async fn test_different_users_separate_limits() {
+        let rate_limiter = in_memory_limiter(5, 2); // 2 requests per minute per user
         let user1 = "user1";
         let user2 = "user2";
 
         // Use up limit for user1
-        assert!(rate_limiter.check_user_rate_limit(user1));
-        assert!(rate_limiter.check_user_rate_limit(user1));
-        assert!(!rate_limiter.check_user_rate_limit(user1)); // Should be rate limited
+        assert!(allowed_user(&rate_limiter, user1).await);
+        assert!(allowed_user(&rate_limiter, user1).await);
+        assert!(!allowed_user(&rate_limiter, user1).await); // Should be rate limited
 
         // user2 should still have full limit available
-        assert!(rate_limiter.check_user_rate_limit(user2));
-        assert!(rate_limiter.check_user_rate_limit(user2));
-        assert!(!rate_limiter.check_user_rate_limit(user2)); // Should be rate limited
+        assert!(allowed_user(&rate_limiter, user2).await);
+        assert!(allowed_user(&rate_limiter, user2).await);
+        assert!(!allowed_user(&rate_limiter, user2).await); // Should be rate limited
+    }
+
+    #[test]
+    fn test_gcra_refills_one_request_per_emission_interval() {
+        // 2 requests per second: a burst of 2, then one every 500ms
+        let quota = Quota {
+            limit: 2,
+            period: Duration::from_secs(1),
+            burst: 2,
+        };
+
+        let (first, tat) = gcra(0, None, quota);
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+        let (second, tat) = gcra(0, tat, quota);
+        assert!(second.allowed);
+        assert_eq!(second.remaining, 0);
+        assert_eq!(second.reset_after, Duration::from_millis(1000));
+
+        let (denied, unchanged) = gcra(100, tat, quota);
+        assert!(!denied.allowed);
+        assert_eq!(unchanged, None, "Denied requests must not consume budget");
+        assert_eq!(denied.retry_after, Duration::from_millis(400));
+        assert_eq!(denied.retry_after_seconds(), 1);
+
+        // One emission interval later exactly one more request fits
+        let (refilled, tat) = gcra(500, tat, quota);
+        assert!(refilled.allowed);
+        assert_eq!(refilled.remaining, 0);
+
+        // Long after, the bucket is full again
+        let (idle, _) = gcra(10_000, tat, quota);
+        assert_eq!(idle.remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_evicts_refilled_buckets() {
+        let store = InMemoryRateLimitStore::new(4);
+        let quota = Quota {
+            limit: 2,
+            period: Duration::from_millis(100),
+            burst: 2,
+        };
+
+        for key in ["ip:10.0.0.1", "ip:10.0.0.2", "user:alice"] {
+            store.check(key, quota).await.unwrap();
+        }
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.evict_expired(), 0, "Active buckets are kept");
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(store.evict_expired(), 3);
+        assert!(store.is_empty());
+
+        // An evicted key starts over with a full bucket
+        let decision = store.check("user:alice", quota).await.unwrap();
+        assert_eq!(decision.remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_headers_report_remaining_budget_and_retry_after() {
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                in_memory_limiter(1, 1),
+                ip_rate_limit_middleware,
+            ));
+        let request = || Request::get("/health").body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["X-RateLimit-Limit"], "1");
+        assert_eq!(response.headers()["X-RateLimit-Remaining"], "0");
+        assert_eq!(response.headers()["X-RateLimit-Reset"], "60");
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["Retry-After"], "60");
+        assert_eq!(response.headers()["X-RateLimit-Remaining"], "0");
+    }
+
+    #[test]
+    fn test_gcra_burst_above_sustained_rate() {
+        // 60 per minute sustained, but up to 5 back to back
+        let quota = Quota {
+            limit: 60,
+            period: Duration::from_secs(60),
+            burst: 5,
+        };
+
+        let mut tat = None;
+        for _ in 0..5 {
+            let (decision, next) = gcra(0, tat, quota);
+            assert!(decision.allowed);
+            assert_eq!(decision.limit, 5);
+            tat = next;
+        }
+        let (denied, _) = gcra(0, tat, quota);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_policy_parsing() {
+        let policies =
+            RateLimitPolicy::parse_list(" read:*=300/1m+50, create:user=20/30s,*:admin=1000/h ")
+                .unwrap();
+        assert_eq!(
+            policies,
+            vec![
+                RateLimitPolicy {
+                    group: Some(RouteGroup::Read),
+                    role: None,
+                    quota: Quota {
+                        limit: 300,
+                        period: Duration::from_secs(60),
+                        burst: 50,
+                    },
+                },
+                RateLimitPolicy {
+                    group: Some(RouteGroup::Create),
+                    role: Some(UserRole::User),
+                    quota: Quota {
+                        limit: 20,
+                        period: Duration::from_secs(30),
+                        burst: 20,
+                    },
+                },
+                RateLimitPolicy {
+                    group: None,
+                    role: Some(UserRole::Admin),
+                    quota: Quota {
+                        limit: 1000,
+                        period: Duration::from_secs(3600),
+                        burst: 1000,
+                    },
+                },
+            ]
+        );
+        assert!(RateLimitPolicy::parse_list("").unwrap().is_empty());
+
+        for invalid in [
+            "read=10/1m",
+            "orders:*=10/1m",
+            "read:guest=10/1m",
+            "read:*=10",
+            "read:*=10/1d",
+            "read:*=0/1m",
+            "read:*=10/0m",
+            "read:*=10/1m+x",
+        ] {
+            assert!(
+                RateLimitPolicy::parse_list(invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_most_specific_policy_wins() {
+        let policies = RateLimitPolicies::new(
+            Quota::per_minute(10),
+            RateLimitPolicy::parse_list("*:admin=100/1m,read:*=50/1m,read:admin=500/1m").unwrap(),
+        );
+
+        let limit = |group, role| policies.quota_for(group, &role).limit;
+        assert_eq!(limit(RouteGroup::Read, UserRole::Admin), 500);
+        assert_eq!(limit(RouteGroup::Read, UserRole::User), 50);
+        assert_eq!(limit(RouteGroup::Create, UserRole::Admin), 100);
+        assert_eq!(limit(RouteGroup::Create, UserRole::Manager), 10);
+    }
+
+    #[tokio::test]
+    async fn test_route_groups_have_separate_budgets() {
+        let rate_limiter = limiter_with_policies(5, 10, "read:*=2/1m");
+
+        let check = |group| {
+            let rate_limiter = rate_limiter.clone();
+            async move {
+                rate_limiter
+                    .check_user("user1", &UserRole::User, group)
+                    .await
+                    .unwrap()
+                    .allowed
+            }
+        };
+        assert!(check(RouteGroup::Read).await);
+        assert!(check(RouteGroup::Read).await);
+        assert!(!check(RouteGroup::Read).await);
+
+        // Exhausting reads doesn't lock the user out of writes
+        assert!(check(RouteGroup::Create).await);
+        assert!(check(RouteGroup::Update).await);
+    }
+
+    #[tokio::test]
+    async fn test_admins_get_a_higher_ceiling() {
+        let rate_limiter = limiter_with_policies(5, 1, "*:admin=3/1m");
+
+        for (user, role, allowed) in [("user1", UserRole::User, 1), ("admin1", UserRole::Admin, 3)]
+        {
+            for _ in 0..allowed {
+                assert!(
+                    rate_limiter
+                        .check_user(user, &role, RouteGroup::Delete)
+                        .await
+                        .unwrap()
+                        .allowed
+                );
+            }
+            assert!(
+                !rate_limiter
+                    .check_user(user, &role, RouteGroup::Delete)
+                    .await
+                    .unwrap()
+                    .allowed
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_limit_violation_uses_error_envelope() {
+        let app = Router::new()
+            .route("/products", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                (in_memory_limiter(5, 1), RouteGroup::Create),
+                user_rate_limit_middleware,
+            ))
+            // Stands in for auth_middleware
+            .layer(axum::middleware::from_fn(
+                |mut request: axum::extract::Request, next: axum::middleware::Next| async move {
+                    request.extensions_mut().insert(Claims {
+                        sub: "user1".to_string(),
+                        username: "user1".to_string(),
+                        role: UserRole::User,
+                        exp: usize::MAX,
+                        iat: 0,
+                        jti: "test".to_string(),
+                    });
+                    next.run(request).await
+                },
+            ));
+        let request = || Request::get("/products").body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "60");
+        assert_eq!(response.headers()["X-RateLimit-Limit"], "1");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "RATE_LIMIT_EXCEEDED");
+        assert_eq!(body["error"]["retry_after"], 60);
+        assert_eq!(body["error"]["details"]["limit_type"], "user:create");
+    }
+
+    struct UnavailableStore;
+
+    #[async_trait]
+    impl RateLimitStore for UnavailableStore {
+        async fn check(&self, _key: &str, _quota: Quota) -> Result<RateLimitDecision, AppError> {
+            Err(AppError::ExternalServiceError {
+                service: "redis".to_string(),
+                operation: "rate_limit_check".to_string(),
+                status_code: None,
+                error_id: uuid::Uuid::new_v4(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_outage_does_not_block_requests() {
+        let rate_limiter = RateLimiter::new(
+            Arc::new(UnavailableStore),
+            1,
+            RateLimitPolicies::new(Quota::per_minute(1), Vec::new()),
+        );
+        assert_eq!(
+            rate_limiter
+                .check_user("user1", &UserRole::User, RouteGroup::Read)
+                .await,
+            None
+        );
+
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                rate_limiter,
+                ip_rate_limit_middleware,
+            ));
+        for _ in 0..3 {
+            let response = app
+                .clone()
+                .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    /// Local stand-in for Redis: speaks enough RESP for the store and runs the
+    /// GCRA script natively, on its own clock like a real server would
+    struct FakeRedis {
+        buckets: Mutex<HashMap<String, u64>>,
+        scripts_loaded: Mutex<bool>,
+        started: Instant,
+    }
+
+    async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.ok()? == 0 {
+            return None;
+        }
+        let count: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+
+        let mut parts = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+            let mut data = vec![0; len + 2];
+            reader.read_exact(&mut data).await.ok()?;
+            data.truncate(len);
+            parts.push(String::from_utf8(data).ok()?);
+        }
+        Some(parts)
+    }
+
+    impl FakeRedis {
+        fn reply(&self, command: &[String]) -> String {
+            match command[0].to_uppercase().as_str() {
+                "SCRIPT" => {
+                    *self.scripts_loaded.lock().unwrap() = true;
+                    format!("$40\r\n{}\r\n", "0".repeat(40))
+                }
+                "EVALSHA" if !*self.scripts_loaded.lock().unwrap() => {
+                    "-NOSCRIPT No matching script. Please use EVAL.\r\n".to_string()
+                }
+                "EVALSHA" | "EVAL" => {
+                    // EVALSHA <sha> 1 <key> <emission ms> <burst ms>
+                    let key = command[3].clone();
+                    let emission: u64 = command[4].parse().unwrap();
+                    let burst: u64 = command[5].parse().unwrap();
+                    let quota = Quota {
+                        limit: (burst / emission) as u32,
+                        period: Duration::from_millis(burst),
+                        burst: (burst / emission) as u32,
+                    };
+
+                    let now = self.started.elapsed().as_millis() as u64;
+                    let mut buckets = self.buckets.lock().unwrap();
+                    let (decision, new_tat) = gcra(now, buckets.get(&key).copied(), quota);
+                    if let Some(new_tat) = new_tat {
+                        buckets.insert(key, new_tat);
+                    }
+
+                    format!(
+                        "*4\r\n:{}\r\n:{}\r\n:{}\r\n:{}\r\n",
+                        u8::from(decision.allowed),
+                        decision.remaining,
+                        decision.retry_after.as_millis(),
+                        decision.reset_after.as_millis()
+                    )
+                }
+                "PING" => "+PONG\r\n".to_string(),
+                _ => "+OK\r\n".to_string(),
+            }
+        }
+    }
+
+    async fn spawn_fake_redis() -> (String, Arc<FakeRedis>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let server = Arc::new(FakeRedis {
+            buckets: Mutex::new(HashMap::new()),
+            scripts_loaded: Mutex::new(false),
+            started: Instant::now(),
+        });
+
+        let shared = server.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let server = shared.clone();
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(socket);
+                    while let Some(command) = read_command(&mut reader).await {
+                        let reply = server.reply(&command);
+                        if reader.get_mut().write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_shares_limits_across_replicas() {
+        let (url, server) = spawn_fake_redis().await;
+        let quota = Quota::per_minute(3);
+
+        // Two replicas, one budget
+        let replica_a = RedisRateLimitStore::connect(&url, "test:").await.unwrap();
+        let replica_b = RedisRateLimitStore::connect(&url, "test:").await.unwrap();
+
+        let first = replica_a.check("user:alice", quota).await.unwrap();
+        assert!(first.allowed);
+        assert_eq!(first.limit, 3);
+        assert_eq!(first.remaining, 2);
+        assert!(replica_b.check("user:alice", quota).await.unwrap().allowed);
+        assert!(replica_a.check("user:alice", quota).await.unwrap().allowed);
+
+        let denied = replica_b.check("user:alice", quota).await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert_eq!(denied.retry_after_seconds(), 20);
+
+        // Other keys are unaffected, and the prefix namespaces them
+        assert!(replica_b.check("user:bob", quota).await.unwrap().allowed);
+        let buckets = server.buckets.lock().unwrap();
+        assert!(buckets.contains_key("test:user:alice"));
+        assert!(buckets.contains_key("test:user:bob"));
     }
 }
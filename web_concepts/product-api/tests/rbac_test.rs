@@ -0,0 +1,254 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::app_state;
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{Method, StatusCode},
+        middleware::Next,
+        routing::get,
+        Extension, Router,
+    };
+    use product_api::{
+        app::{create_app, protected_routes},
+        entities::user::{Permission, UserRole},
+        middleware::rbac::{rbac_middleware, RbacConfig, UserContext},
+        models::Claims,
+        AppState,
+    };
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn claims(username: &str, role: UserRole) -> Claims {
+        Claims {
+            sub: "user-1".to_string(),
+            username: username.to_string(),
+            role,
+            exp: usize::MAX,
+            iat: 0,
+            jti: "test".to_string(),
+        }
+    }
+
+    fn app(rbac: RbacConfig, claims: Option<Claims>) -> Router {
+        Router::new()
+            .route(
+                "/products/:id",
+                get(|Extension(user): Extension<UserContext>| async move {
+                    format!("{:?}", user.role)
+                })
+                .delete(|| async { StatusCode::NO_CONTENT }),
+            )
+            .route("/products/search", get(|| async { "search" }))
+            .route("/unlisted", get(|| async { "unlisted" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(rbac),
+                rbac_middleware,
+            ))
+            // Stands in for auth_middleware
+            .layer(axum::middleware::from_fn(
+                move |mut request: Request, next: Next| {
+                    let claims = claims.clone();
+                    async move {
+                        if let Some(claims) = claims {
+                            request.extensions_mut().insert(claims);
+                        }
+                        next.run(request).await
+                    }
+                },
+            ))
+    }
+
+    async fn status(app: Router, method: Method, uri: &str) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_literal_segments_win_over_parameters() {
+        let rbac = RbacConfig::default()
+            .route("/products/:id", &[(Method::GET, Permission::Delete)])
+            .route("/products/search", &[(Method::GET, Permission::Read)])
+            .route(
+                "/products/:id/variants/:variant_id",
+                &[(Method::PUT, Permission::Update)],
+            );
+
+        assert_eq!(
+            rbac.required_permissions(&Method::GET, "/products/search"),
+            Some(&[Permission::Read][..])
+        );
+        assert_eq!(
+            rbac.required_permissions(&Method::GET, "/products/42"),
+            Some(&[Permission::Delete][..])
+        );
+        assert_eq!(
+            rbac.required_permissions(&Method::PUT, "/products/42/variants/7"),
+            Some(&[Permission::Update][..])
+        );
+        // Patterns look themselves up, whatever their parameters are called
+        assert_eq!(
+            rbac.required_permissions(&Method::PUT, "/products/{id}/variants/{vid}"),
+            Some(&[Permission::Update][..])
+        );
+
+        // Unknown methods and paths have no policy
+        assert_eq!(
+            rbac.required_permissions(&Method::POST, "/products/42"),
+            None
+        );
+        assert_eq!(rbac.required_permissions(&Method::GET, "/products"), None);
+        assert_eq!(
+            rbac.required_permissions(&Method::GET, "/products/42/extra"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_permissions_are_per_method_and_accumulate() {
+        let rbac = RbacConfig::default()
+            .route(
+                "/categories/:id",
+                &[
+                    (Method::GET, Permission::Read),
+                    (Method::PUT, Permission::Update),
+                ],
+            )
+            .route("/categories/:id", &[(Method::PUT, Permission::Create)]);
+
+        assert_eq!(
+            rbac.required_permissions(&Method::GET, "/categories/1"),
+            Some(&[Permission::Read][..])
+        );
+        assert_eq!(
+            rbac.required_permissions(&Method::PUT, "/categories/1"),
+            Some(&[Permission::Update, Permission::Create][..])
+        );
+    }
+
+    #[test]
+    fn test_check_routes_reports_uncovered_routes() {
+        let rbac = RbacConfig::default()
+            .route("/products/:id", &[(Method::GET, Permission::Read)])
+            .route("/products/search", &[(Method::GET, Permission::Read)]);
+
+        assert!(rbac
+            .check_routes([
+                (Method::GET, "/products/{id}"),
+                (Method::GET, "/products/search")
+            ])
+            .is_ok());
+
+        // A parameter pattern doesn't cover a literal route, nor other methods
+        let error = rbac
+            .check_routes([
+                (Method::GET, "/products/lookup"),
+                (Method::DELETE, "/products/{id}"),
+            ])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Configuration error");
+    }
+
+    #[test]
+    fn test_every_protected_route_has_a_policy() {
+        let routes = protected_routes(&app_state());
+        let routes = routes.routes();
+        assert!(routes.len() > 50, "Only found {} routes", routes.len());
+        assert!(routes.contains(&(Method::POST, "/auth/logout")));
+        assert!(routes.contains(&(Method::PATCH, "/users/:id/status")));
+
+        if let Err(e) = RbacConfig::new().check_routes(routes.iter().cloned()) {
+            panic!("{e:?}");
+        }
+
+        // The router isn't built while a route lacks a policy
+        let state = AppState {
+            rbac: Arc::new(
+                RbacConfig::default().route("/products", &[(Method::GET, Permission::Read)]),
+            ),
+            ..app_state()
+        };
+        assert!(create_app(state).is_err());
+    }
+
+    #[test]
+    fn test_operating_the_service_needs_the_admin_permission() {
+        let rbac = RbacConfig::new();
+        for (method, path) in [
+            (Method::PUT, "/exchange-rates/EUR"),
+            (Method::DELETE, "/exchange-rates/EUR"),
+            (Method::POST, "/webhooks"),
+            (Method::GET, "/webhooks/1/deliveries"),
+            (Method::GET, "/metrics/cache"),
+            (Method::PATCH, "/users/1/status"),
+            (Method::GET, "/products/trash"),
+            (Method::DELETE, "/products/1/purge"),
+        ] {
+            assert_eq!(
+                rbac.required_permissions(&method, path),
+                Some(&[Permission::Admin][..]),
+                "{method} {path}"
+            );
+        }
+
+        assert!(UserRole::Admin.has_permission(&Permission::Admin));
+        assert!(!UserRole::Manager.has_permission(&Permission::Admin));
+        assert!(!UserRole::User.has_permission(&Permission::Admin));
+    }
+
+    #[tokio::test]
+    async fn test_role_comes_from_the_token_not_the_username() {
+        let rbac = || {
+            RbacConfig::default()
+                .route(
+                    "/products/:id",
+                    &[
+                        (Method::GET, Permission::Read),
+                        (Method::DELETE, Permission::Delete),
+                    ],
+                )
+                .route("/products/search", &[(Method::GET, Permission::Read)])
+        };
+
+        let impostor = Some(claims("admin-lookalike", UserRole::User));
+        assert_eq!(
+            status(app(rbac(), impostor.clone()), Method::GET, "/products/1").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app(rbac(), impostor), Method::DELETE, "/products/1").await,
+            StatusCode::FORBIDDEN
+        );
+
+        let admin = Some(claims("alice", UserRole::Admin));
+        assert_eq!(
+            status(app(rbac(), admin.clone()), Method::DELETE, "/products/1").await,
+            StatusCode::NO_CONTENT
+        );
+        let response = app(rbac(), admin.clone())
+            .oneshot(Request::get("/products/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"Admin", "Handlers get the role as UserContext");
+
+        // Routes without a policy are denied, even to admins
+        assert_eq!(
+            status(app(rbac(), admin), Method::GET, "/unlisted").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(app(rbac(), None), Method::GET, "/products/search").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use chrono::{Duration, Utc};
+    use product_api::entities::{
+        product,
+        stock_movement::StockMovementReason,
+        stock_reservation::{self, ReservationStatus},
+    };
+    use product_api::error::AppError;
+    use product_api::models::{CreateProductRequest, CreateReservationRequest, ProductResponse};
+    use product_api::repository::{NewStockMovement, ProductRepository, ProductRepositoryTrait};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    fn sample_product(quantity: i32, reserved_quantity: i32) -> product::Model {
+        product::Model {
+            quantity,
+            reserved_quantity,
+            ..common::sample_product()
+        }
+    }
+
+    fn sample_reservation(status: ReservationStatus, ttl: Duration) -> stock_reservation::Model {
+        let now = Utc::now();
+        stock_reservation::Model {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            quantity: 2,
+            status,
+            reference: Some("CHECKOUT-7".to_string()),
+            expires_at: now + ttl,
+            created_by: None,
+            created_at: now,
+            resolved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_available_quantity_subtracts_reservations() {
+        assert_eq!(sample_product(25, 0).available_quantity(), 25);
+        assert_eq!(sample_product(25, 10).available_quantity(), 15);
+        // Stock adjusted below the held amount never reports negative availability
+        assert_eq!(sample_product(3, 5).available_quantity(), 0);
+
+        let response = ProductResponse::from(sample_product(25, 10));
+        assert_eq!(response.quantity, 25);
+        assert_eq!(response.reserved_quantity, 10);
+        assert_eq!(response.available_quantity, 15);
+    }
+
+    #[test]
+    fn test_only_unexpired_active_reservations_hold_stock() {
+        let now = Utc::now();
+
+        let active = sample_reservation(ReservationStatus::Active, Duration::minutes(15));
+        assert!(active.is_holding(now));
+
+        let stale = sample_reservation(ReservationStatus::Active, Duration::seconds(-1));
+        assert!(!stale.is_holding(now));
+
+        for status in [
+            ReservationStatus::Confirmed,
+            ReservationStatus::Released,
+            ReservationStatus::Expired,
+        ] {
+            let resolved = sample_reservation(status, Duration::minutes(15));
+            assert!(!resolved.is_holding(now));
+        }
+    }
+
+    #[test]
+    fn test_reservation_request_validation() {
+        let request: CreateReservationRequest =
+            serde_json::from_str(r#"{"quantity": 2, "reference": "CHECKOUT-7"}"#).unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.ttl_seconds, None);
+
+        let empty: CreateReservationRequest = serde_json::from_str(r#"{"quantity": 0}"#).unwrap();
+        assert!(empty.validate().is_err());
+
+        let too_long: CreateReservationRequest =
+            serde_json::from_str(r#"{"quantity": 1, "ttl_seconds": 86401}"#).unwrap();
+        assert!(too_long.validate().is_err());
+    }
+
+    #[test]
+    fn test_reservation_status_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_value(ReservationStatus::Expired).unwrap(),
+            "expired"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stock_adjustments_keep_reserved_units() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let repo = ProductRepository::new(db);
+        let product = repo
+            .create(CreateProductRequest {
+                name: "Reserved Mug".to_string(),
+                description: None,
+                price: dec!(15.99),
+                currency: Some("USD".to_string()),
+                quantity: 5,
+                category: None,
+                category_id: None,
+                sku: None,
+                barcode: None,
+                created_by: None,
+                updated_by: None,
+            })
+            .await
+            .unwrap();
+        repo.reserve_stock(
+            product.id,
+            3,
+            Utc::now() + Duration::minutes(10),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let adjustment = |quantity_delta| NewStockMovement {
+            reason: StockMovementReason::Adjustment,
+            quantity_delta,
+            reference: None,
+            note: None,
+        };
+
+        // Only 2 of the 5 units are free to take out
+        let error = repo
+            .adjust_stock(product.id, adjustment(-3), None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, AppError::BusinessRuleViolation { ref rule, .. } if rule == "reserved_stock"),
+            "{error:?}"
+        );
+
+        let (updated, _) = repo
+            .adjust_stock(product.id, adjustment(-2), None)
+            .await
+            .unwrap();
+        assert_eq!(updated.quantity, 3);
+        assert_eq!(updated.reserved_quantity, 3);
+        assert_eq!(updated.available_quantity(), 0);
+    }
+}
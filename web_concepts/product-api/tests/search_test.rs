@@ -0,0 +1,187 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{conversion, create_request, test_db};
+    use chrono::{Duration, Utc};
+    use product_api::models::{CreateProductRequest, ProductSearchRequest};
+    use product_api::repository::product::parse_price_buckets;
+    use product_api::repository::{ProductRepository, ProductRepositoryTrait};
+    use product_api::utils::build_tsquery;
+    use rust_decimal_macros::dec;
+    use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    /// A repository over an empty catalogue, so seed products never match
+    async fn empty_catalogue(db: DatabaseConnection) -> ProductRepository {
+        db.execute_unprepared("TRUNCATE products CASCADE")
+            .await
+            .unwrap();
+        ProductRepository::new(db)
+    }
+
+    #[test]
+    fn test_plain_terms_are_anded() {
+        assert_eq!(
+            build_tsquery("Wireless  mouse").as_deref(),
+            Some("wireless & mouse")
+        );
+    }
+
+    #[test]
+    fn test_phrase_and_prefix_queries() {
+        assert_eq!(
+            build_tsquery("\"coffee mug\" lap*").as_deref(),
+            Some("(coffee <-> mug) & lap:*")
+        );
+        assert_eq!(
+            build_tsquery("\"wireless mou*\"").as_deref(),
+            Some("(wireless <-> mou:*)")
+        );
+    }
+
+    #[test]
+    fn test_tsquery_operators_are_stripped() {
+        // Raw tsquery syntax must never reach Postgres
+        assert_eq!(
+            build_tsquery("a & !b | c:*").as_deref(),
+            Some("a & b & c:*")
+        );
+        assert_eq!(build_tsquery("e-mail").as_deref(), Some("(e <-> mail)"));
+        assert_eq!(build_tsquery("  ! & | \"\" "), None);
+    }
+
+    #[test]
+    fn test_price_bucket_edges() {
+        let defaults = parse_price_buckets(None).unwrap();
+        assert_eq!(defaults.first(), Some(&dec!(0)));
+        assert!(defaults.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(
+            parse_price_buckets(Some("0, 49.99,100")).unwrap(),
+            vec![dec!(0), dec!(49.99), dec!(100)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_price_bucket_edges_rejected() {
+        assert!(parse_price_buckets(Some("100,50")).is_err());
+        assert!(parse_price_buckets(Some("10,10")).is_err());
+        assert!(parse_price_buckets(Some("cheap,expensive")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fulltext_ranks_name_hits_first_and_highlights_them() {
+        let Some(db) = test_db().await else { return };
+        let repo = empty_catalogue(db).await;
+        for (name, description) in [
+            ("USB Hub", "Four ports, works next to a wireless mouse"),
+            ("Wireless Mouse", "Ergonomic, two buttons"),
+            ("Desk Lamp", "Warm white light"),
+        ] {
+            repo.create(CreateProductRequest {
+                description: Some(description.to_string()),
+                ..create_request(name, 5)
+            })
+            .await
+            .unwrap();
+        }
+
+        let request: ProductSearchRequest = serde_json::from_value(json!({
+            "query": "wireless mouse",
+            "search_mode": "fulltext",
+            "sort_by": "relevance",
+            "sort_order": "desc",
+        }))
+        .unwrap();
+        let page = repo.search(request, &conversion("USD")).await.unwrap();
+
+        // Name matches weigh more than description matches
+        let names: Vec<_> = page.products.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Wireless Mouse", "USB Hub"]);
+        let ids: Vec<_> = page.products.iter().map(|p| p.id).collect();
+        let highlighted: Vec<_> = page.highlights.iter().map(|h| h.product_id).collect();
+        assert_eq!(highlighted, ids);
+        assert!(page.highlights[0].rank > page.highlights[1].rank);
+
+        let snippet = |i: usize| page.highlights[i].snippet.as_deref().unwrap();
+        assert!(snippet(0).contains("<mark>Wireless</mark> <mark>Mouse</mark>"));
+        assert!(snippet(1).contains("<mark>wireless</mark> <mark>mouse</mark>"));
+        assert!(!snippet(1).contains("<mark>Hub</mark>"));
+    }
+
+    #[tokio::test]
+    async fn test_facets_count_categories_prices_and_available_stock() {
+        let Some(db) = test_db().await else { return };
+        let electronics: Uuid = db
+            .query_one(Statement::from_string(
+                DatabaseBackend::Postgres,
+                "SELECT id FROM categories WHERE slug = 'electronics'",
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get("", "id")
+            .unwrap();
+        let repo = empty_catalogue(db).await;
+        let mut ids = Vec::new();
+        for (name, price, quantity, category_id) in [
+            ("Cable", dec!(5.00), 10, Some(electronics)),
+            ("Charger", dec!(30.00), 2, Some(electronics)),
+            ("Pen", dec!(10.00), 3, None),
+            ("Notebook", dec!(120.00), 4, None),
+        ] {
+            let product = repo
+                .create(CreateProductRequest {
+                    price,
+                    category_id,
+                    ..create_request(name, quantity)
+                })
+                .await
+                .unwrap();
+            ids.push(product.id);
+        }
+        // Fully reserved stock is not available to sell
+        repo.reserve_stock(ids[1], 2, Utc::now() + Duration::hours(1), None, None)
+            .await
+            .unwrap();
+
+        let request: ProductSearchRequest =
+            serde_json::from_value(json!({ "price_buckets": "10,50,100" })).unwrap();
+        let facets = repo
+            .search_facets(request, &conversion("USD"))
+            .await
+            .unwrap();
+
+        let categories: Vec<_> = facets
+            .categories
+            .iter()
+            .map(|facet| (facet.category_id, facet.count))
+            .collect();
+        assert_eq!(categories, vec![(Some(electronics), 2), (None, 2)]);
+        assert_eq!(
+            facets.categories[0].category.as_deref(),
+            Some("Electronics")
+        );
+
+        // Edges are inclusive below and exclusive above; empty buckets are kept
+        let buckets: Vec<_> = facets
+            .price_buckets
+            .iter()
+            .map(|bucket| (bucket.min, bucket.max, bucket.count))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![
+                (None, Some(dec!(10)), 1),
+                (Some(dec!(10)), Some(dec!(50)), 2),
+                (Some(dec!(50)), Some(dec!(100)), 0),
+                (Some(dec!(100)), None, 1),
+            ]
+        );
+
+        assert_eq!(facets.stock.in_stock, 3);
+        assert_eq!(facets.stock.out_of_stock, 1);
+    }
+}
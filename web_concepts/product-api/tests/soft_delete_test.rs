@@ -0,0 +1,140 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{conversion, test_db};
+    use product_api::{
+        entities::{product_history::HistoryAction, Product},
+        models::{CreateProductRequest, ProductSearchRequest},
+        repository::{ProductRepository, ProductRepositoryTrait},
+    };
+    use rust_decimal_macros::dec;
+    use sea_orm::{DatabaseBackend, QueryTrait};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    async fn create(repo: &ProductRepository, name: &str) -> Uuid {
+        let request = CreateProductRequest {
+            name: name.to_string(),
+            description: None,
+            price: dec!(10.00),
+            currency: Some("USD".to_string()),
+            quantity: 5,
+            category: None,
+            category_id: None,
+            sku: None,
+            barcode: None,
+            created_by: None,
+            updated_by: None,
+        };
+        repo.create(request).await.unwrap().id
+    }
+
+    async fn listed(repo: &ProductRepository) -> Vec<Uuid> {
+        let page = repo
+            .find_all(Some(1), Some(100), None, Some(true))
+            .await
+            .unwrap();
+        page.products.iter().map(|product| product.id).collect()
+    }
+
+    async fn searched(repo: &ProductRepository, query: &str) -> Vec<Uuid> {
+        let request: ProductSearchRequest =
+            serde_json::from_value(json!({ "query": query })).unwrap();
+        let page = repo.search(request, &conversion("USD")).await.unwrap();
+        page.products.iter().map(|product| product.id).collect()
+    }
+
+    async fn trashed(repo: &ProductRepository) -> Vec<Uuid> {
+        let page = repo
+            .find_deleted(Some(1), Some(100), None, Some(true))
+            .await
+            .unwrap();
+        page.products.iter().map(|product| product.id).collect()
+    }
+
+    #[test]
+    fn test_find_active_excludes_trash() {
+        let sql = Product::find_active()
+            .build(DatabaseBackend::Postgres)
+            .to_string();
+        assert!(
+            sql.ends_with(r#"WHERE "products"."deleted_at" IS NULL"#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_find_deleted_only_returns_trash() {
+        let sql = Product::find_deleted()
+            .build(DatabaseBackend::Postgres)
+            .to_string();
+        assert!(
+            sql.ends_with(r#"WHERE "products"."deleted_at" IS NOT NULL"#),
+            "{sql}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trashed_products_are_hidden_until_restored() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let id = create(&repo, "Trash Candidate").await;
+        let before = repo.get_product_stats(&conversion("USD")).await.unwrap();
+
+        assert!(repo.delete(id, None).await.unwrap());
+        assert!(repo.find_by_id(id).await.unwrap().is_none());
+        assert!(!listed(&repo).await.contains(&id));
+        assert!(searched(&repo, "Trash Candidate").await.is_empty());
+        let stats = repo.get_product_stats(&conversion("USD")).await.unwrap();
+        assert_eq!(stats.total_products, before.total_products - 1);
+        assert_eq!(stats.total_value, before.total_value - dec!(50.00));
+        assert_eq!(trashed(&repo).await, vec![id]);
+
+        // Already in the trash
+        assert!(!repo.delete(id, None).await.unwrap());
+
+        let restored = repo.restore(id, None).await.unwrap().unwrap();
+        assert_eq!(restored.deleted_at, None);
+        assert!(repo.find_by_id(id).await.unwrap().is_some());
+        assert!(listed(&repo).await.contains(&id));
+        assert_eq!(searched(&repo, "Trash Candidate").await, vec![id]);
+        let stats = repo.get_product_stats(&conversion("USD")).await.unwrap();
+        assert_eq!(stats.total_products, before.total_products);
+        assert!(trashed(&repo).await.is_empty());
+
+        // Only trashed products can be restored
+        assert!(repo.restore(id, None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_purge_only_removes_trashed_products() {
+        let Some(db) = test_db().await else { return };
+        let repo = ProductRepository::new(db);
+        let id = create(&repo, "Purge Candidate").await;
+
+        // Live products have to go through the trash first
+        assert!(!repo.purge(id, None).await.unwrap());
+        assert!(repo.find_by_id(id).await.unwrap().is_some());
+
+        assert!(repo.delete(id, None).await.unwrap());
+        assert!(repo.purge(id, None).await.unwrap());
+        assert!(!repo.exists_including_deleted(id).await.unwrap());
+        assert!(trashed(&repo).await.is_empty());
+        assert!(repo.restore(id, None).await.unwrap().is_none());
+        assert!(!repo.purge(id, None).await.unwrap());
+
+        // The audit trail outlives the row
+        let (history, total) = repo.find_history(id, 1, 10).await.unwrap();
+        assert_eq!(total, 3);
+        let actions: Vec<_> = history.into_iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                HistoryAction::Purge,
+                HistoryAction::Delete,
+                HistoryAction::Create
+            ]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use product_api::entities::stock_movement::StockMovementReason;
+    use product_api::models::StockAdjustmentRequest;
+    use validator::Validate;
+
+    #[test]
+    fn test_reason_sign_rules() {
+        assert!(StockMovementReason::Receipt.allows_delta(5));
+        assert!(!StockMovementReason::Receipt.allows_delta(-5));
+        assert!(StockMovementReason::Return.allows_delta(1));
+        assert!(!StockMovementReason::Return.allows_delta(0));
+
+        assert!(StockMovementReason::Sale.allows_delta(-3));
+        assert!(!StockMovementReason::Sale.allows_delta(3));
+
+        for reason in [
+            StockMovementReason::Adjustment,
+            StockMovementReason::Transfer,
+        ] {
+            assert!(reason.allows_delta(-7));
+            assert!(reason.allows_delta(7));
+            assert!(!reason.allows_delta(0));
+        }
+    }
+
+    #[test]
+    fn test_adjustment_request_deserializes_lowercase_reason() {
+        let request: StockAdjustmentRequest = serde_json::from_str(
+            r#"{"quantity_delta": -2, "reason": "sale", "reference": "ORDER-1042"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.reason, StockMovementReason::Sale);
+        assert_eq!(request.quantity_delta, -2);
+        assert_eq!(request.reference.as_deref(), Some("ORDER-1042"));
+        assert!(request.note.is_none());
+        assert!(request.validate().is_ok());
+
+        let unknown = serde_json::from_str::<StockAdjustmentRequest>(
+            r#"{"quantity_delta": 1, "reason": "theft"}"#,
+        );
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_adjustment_request_limits_reference_length() {
+        let request = StockAdjustmentRequest {
+            quantity_delta: 1,
+            reason: StockMovementReason::Receipt,
+            reference: Some("x".repeat(101)),
+            note: None,
+        };
+        assert!(request.validate().is_err());
+    }
+}
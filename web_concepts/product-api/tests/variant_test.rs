@@ -0,0 +1,137 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use chrono::Utc;
+    use product_api::entities::product_variant;
+    use product_api::error::AppError;
+    use product_api::models::{
+        normalize_options, CreateProductRequest, CreateVariantRequest, UpdateVariantRequest,
+    };
+    use product_api::repository::product::parse_variant_options;
+    use product_api::repository::{
+        ProductRepository, ProductRepositoryTrait, ProductVariantRepository,
+        ProductVariantRepositoryTrait,
+    };
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    #[test]
+    fn test_effective_price_falls_back_to_product_price() {
+        let mut variant = product_variant::Model {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            sku: "TEE-RED-M".to_string(),
+            options: json!({"size": "M", "colour": "red"}),
+            price: None,
+            quantity: 4,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert_eq!(variant.effective_price(dec!(19.99)), dec!(19.99));
+
+        variant.price = Some(dec!(24.99));
+        assert_eq!(variant.effective_price(dec!(19.99)), dec!(24.99));
+    }
+
+    #[test]
+    fn test_options_are_normalized() {
+        let options = BTreeMap::from([
+            (" Size ".to_string(), " M ".to_string()),
+            ("COLOUR".to_string(), "Red".to_string()),
+        ]);
+        let normalized = normalize_options(options);
+
+        // Names are case-insensitive, values keep their case
+        assert_eq!(normalized.get("size").map(String::as_str), Some("M"));
+        assert_eq!(normalized.get("colour").map(String::as_str), Some("Red"));
+    }
+
+    #[test]
+    fn test_variant_options_filter_parsing() {
+        let options = parse_variant_options("Size:M, colour:red").unwrap();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            json!({"size": "M", "colour": "red"})
+        );
+
+        assert!(parse_variant_options("size").is_err());
+        assert!(parse_variant_options("size:").is_err());
+        assert!(parse_variant_options(" , ").is_err());
+    }
+
+    #[test]
+    fn test_variant_request_validation() {
+        let request: CreateVariantRequest = serde_json::from_str(
+            r#"{"sku": "TEE-RED-M", "options": {"size": "M"}, "quantity": 4}"#,
+        )
+        .unwrap();
+        assert!(request.validate().is_ok());
+
+        let no_options: CreateVariantRequest =
+            serde_json::from_str(r#"{"sku": "TEE", "options": {}, "quantity": 1}"#).unwrap();
+        assert!(no_options.validate().is_err());
+
+        let negative_price: CreateVariantRequest = serde_json::from_str(
+            r#"{"sku": "TEE", "options": {"size": "M"}, "price": "-1", "quantity": 1}"#,
+        )
+        .unwrap();
+        assert!(negative_price.validate().is_err());
+
+        let blank_value: UpdateVariantRequest =
+            serde_json::from_str(r#"{"options": {"size": " "}}"#).unwrap();
+        assert!(blank_value.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicates_are_conflicts() {
+        let Some(db) = common::test_db().await else {
+            return;
+        };
+        let product = ProductRepository::new(db.clone())
+            .create(CreateProductRequest {
+                name: "Tee".to_string(),
+                description: None,
+                price: dec!(19.99),
+                currency: Some("USD".to_string()),
+                quantity: 0,
+                category: None,
+                category_id: None,
+                sku: None,
+                barcode: None,
+                created_by: None,
+                updated_by: None,
+            })
+            .await
+            .unwrap();
+        let variants = ProductVariantRepository::new(db);
+        let create = |sku: &str, size: &str| {
+            variants.create(
+                product.id,
+                sku.to_string(),
+                json!({ "size": size }),
+                None,
+                1,
+            )
+        };
+
+        // Whether the pre-check or the unique constraint catches the loser,
+        // it gets the same 409
+        for (first, second) in [
+            (create("TEE-M", "M"), create("TEE-M", "L")),
+            (create("TEE-S", "S"), create("TEE-S2", "S")),
+        ] {
+            let (first, second) = tokio::join!(first, second);
+            let conflicts = [&first, &second]
+                .into_iter()
+                .filter(|outcome| matches!(outcome, Err(AppError::Conflict { .. })))
+                .count();
+            assert_eq!(conflicts, 1, "{first:?} {second:?}");
+            assert!(first.is_ok() || second.is_ok());
+        }
+    }
+}
@@ -0,0 +1,302 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::test_db;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use product_api::entities::{
+        webhook_delivery, webhook_delivery::DeliveryStatus, webhook_event,
+        webhook_event::WebhookEventType,
+    };
+    use product_api::error::AppError;
+    use product_api::models::{CreateWebhookRequest, UpdateWebhookRequest};
+    use product_api::repository::webhook_delivery::{DueDelivery, WebhookDeliveryRepositoryTrait};
+    use product_api::repository::{WebhookDeliveryRepository, WebhookSubscriptionRepository};
+    use product_api::services::{
+        retry_delay, sign_webhook_payload, WebhookDispatchConfig, WebhookDispatcher,
+        WebhookService, MAX_RETRY_DELAY,
+    };
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use uuid::Uuid;
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SECRET: &str = "whsec_0123456789abcdef";
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Outcome {
+        Delivered(u16),
+        Failed {
+            status: Option<u16>,
+            retry_at: Option<DateTime<Utc>>,
+        },
+    }
+
+    /// Outbox holding a fixed set of due deliveries
+    #[derive(Default)]
+    struct FakeDeliveries {
+        due: Mutex<Vec<DueDelivery>>,
+        outcomes: Mutex<Vec<(Uuid, Outcome)>>,
+    }
+
+    #[async_trait]
+    impl WebhookDeliveryRepositoryTrait for FakeDeliveries {
+        async fn find_by_subscription(
+            &self,
+            _subscription_id: Uuid,
+            _status: Option<DeliveryStatus>,
+            _page: u64,
+            _per_page: u64,
+        ) -> Result<
+            (
+                Vec<(webhook_delivery::Model, Option<webhook_event::Model>)>,
+                u64,
+            ),
+            AppError,
+        > {
+            Ok((Vec::new(), 0))
+        }
+
+        async fn claim_due(
+            &self,
+            limit: u64,
+            _lease: Duration,
+        ) -> Result<Vec<DueDelivery>, AppError> {
+            let mut due = self.due.lock().unwrap();
+            let count = due.len().min(limit as usize);
+            Ok(due.drain(..count).collect())
+        }
+
+        async fn mark_delivered(&self, id: Uuid, response_status: u16) -> Result<(), AppError> {
+            self.outcomes
+                .lock()
+                .unwrap()
+                .push((id, Outcome::Delivered(response_status)));
+            Ok(())
+        }
+
+        async fn mark_failed(
+            &self,
+            id: Uuid,
+            response_status: Option<u16>,
+            _error: String,
+            retry_at: Option<DateTime<Utc>>,
+        ) -> Result<(), AppError> {
+            self.outcomes.lock().unwrap().push((
+                id,
+                Outcome::Failed {
+                    status: response_status,
+                    retry_at,
+                },
+            ));
+            Ok(())
+        }
+    }
+
+    fn due_delivery(url: String, attempts: i32) -> DueDelivery {
+        DueDelivery {
+            id: Uuid::new_v4(),
+            attempts,
+            event_id: Uuid::new_v4(),
+            event_type: "product.created".to_string(),
+            payload: serde_json::json!({
+                "type": "product.created",
+                "data": { "product": { "name": "Mouse" } }
+            }),
+            url,
+            secret: SECRET.to_string(),
+        }
+    }
+
+    fn dispatcher(repository: Arc<FakeDeliveries>) -> WebhookDispatcher<FakeDeliveries> {
+        WebhookDispatcher::new(
+            repository,
+            WebhookDispatchConfig {
+                max_attempts: 3,
+                retry_base: Duration::from_secs(30),
+                timeout: Duration::from_secs(5),
+                batch_size: 10,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_signature_is_hmac_of_timestamp_and_body() {
+        let body = br#"{"type":"product.created"}"#;
+        let signature = sign_webhook_payload(SECRET, 1_700_000_000, body);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign_webhook_payload(SECRET, 1_700_000_000, body));
+
+        // Timestamp, body and secret are all covered
+        assert_ne!(signature, sign_webhook_payload(SECRET, 1_700_000_001, body));
+        assert_ne!(
+            signature,
+            sign_webhook_payload(SECRET, 1_700_000_000, br#"{"type":"product.deleted"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_webhook_payload("another-secret-value", 1_700_000_000, body)
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let base = Duration::from_secs(30);
+        assert_eq!(retry_delay(1, base), Duration::from_secs(30));
+        assert_eq!(retry_delay(2, base), Duration::from_secs(60));
+        assert_eq!(retry_delay(4, base), Duration::from_secs(240));
+        assert_eq!(retry_delay(20, base), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(u32::MAX, base), MAX_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_signed_and_marked_delivered() {
+        let receiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hooks"))
+            .and(header("content-type", "application/json"))
+            .and(header("x-webhook-event", "product.created"))
+            .and(header_exists("x-webhook-id"))
+            .and(header_exists("x-webhook-timestamp"))
+            .and(header_exists("x-webhook-signature"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&receiver)
+            .await;
+
+        let delivery = due_delivery(format!("{}/hooks", receiver.uri()), 0);
+        let repository = Arc::new(FakeDeliveries::default());
+        repository.due.lock().unwrap().push(delivery.clone());
+
+        let attempted = dispatcher(repository.clone()).dispatch_due().await.unwrap();
+        assert_eq!(attempted, 1);
+        assert_eq!(
+            repository.outcomes.lock().unwrap().as_slice(),
+            &[(delivery.id, Outcome::Delivered(204))]
+        );
+
+        // The receiver can verify the signature from the headers and raw body
+        let requests = receiver.received_requests().await.unwrap();
+        let request = &requests[0];
+        let timestamp: i64 = request.headers["x-webhook-timestamp"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            request.headers["x-webhook-signature"].to_str().unwrap(),
+            sign_webhook_payload(SECRET, timestamp, &request.body)
+        );
+        assert_eq!(
+            request.headers["x-webhook-id"].to_str().unwrap(),
+            delivery.event_id.to_string()
+        );
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body, delivery.payload);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_until_max_attempts() {
+        let receiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&receiver)
+            .await;
+
+        let first_try = due_delivery(receiver.uri(), 0);
+        let last_try = due_delivery(receiver.uri(), 2);
+        let repository = Arc::new(FakeDeliveries::default());
+        repository
+            .due
+            .lock()
+            .unwrap()
+            .extend([first_try.clone(), last_try.clone()]);
+
+        let before = Utc::now();
+        dispatcher(repository.clone()).dispatch_due().await.unwrap();
+        let outcomes = repository.outcomes.lock().unwrap().clone();
+        assert_eq!(outcomes.len(), 2);
+
+        for (id, outcome) in outcomes {
+            let Outcome::Failed { status, retry_at } = outcome else {
+                panic!("A 500 must not count as delivered");
+            };
+            assert_eq!(status, Some(500));
+
+            if id == first_try.id {
+                // First failure waits the base delay
+                let retry_at = retry_at.expect("First failure must be retried");
+                assert!(retry_at >= before + chrono::Duration::seconds(30));
+                assert!(retry_at <= Utc::now() + chrono::Duration::seconds(30));
+            } else {
+                assert_eq!(id, last_try.id);
+                assert_eq!(retry_at, None, "Third of three attempts gives up");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_receiver_is_retried() {
+        // Nothing listens on the port once the listener is dropped
+        let url = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        let delivery = due_delivery(url, 0);
+        let repository = Arc::new(FakeDeliveries::default());
+        repository.due.lock().unwrap().push(delivery.clone());
+
+        dispatcher(repository.clone()).dispatch_due().await.unwrap();
+
+        let outcomes = repository.outcomes.lock().unwrap().clone();
+        let Outcome::Failed { status, retry_at } = &outcomes[0].1 else {
+            panic!("Connection errors must not count as delivered");
+        };
+        assert_eq!(*status, None);
+        assert!(retry_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_event_types_are_stored_once_each() {
+        let Some(db) = test_db().await else { return };
+        let service = WebhookService::new(
+            Arc::new(WebhookSubscriptionRepository::new(db.clone())),
+            Arc::new(WebhookDeliveryRepository::new(db)),
+        );
+
+        let request: CreateWebhookRequest = serde_json::from_value(serde_json::json!({
+            "url": "https://hooks.example.com/products",
+            "secret": SECRET,
+            "event_types": ["product.updated", "product.created", "product.updated"]
+        }))
+        .unwrap();
+        let webhook = service.create_webhook(request, None).await.unwrap();
+        assert_eq!(
+            webhook.event_types,
+            vec![
+                WebhookEventType::ProductCreated,
+                WebhookEventType::ProductUpdated
+            ]
+        );
+
+        let request: UpdateWebhookRequest = serde_json::from_value(serde_json::json!({
+            "event_types": ["product.deleted", "product.created", "product.deleted"]
+        }))
+        .unwrap();
+        let webhook = service.update_webhook(webhook.id, request).await.unwrap();
+        assert_eq!(
+            webhook.event_types,
+            vec![
+                WebhookEventType::ProductCreated,
+                WebhookEventType::ProductDeleted
+            ]
+        );
+    }
+}